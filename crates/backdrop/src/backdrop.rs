@@ -1,24 +1,40 @@
+mod capabilities;
+mod color;
 mod constants;
-mod hex_converter;
+mod effect;
+mod error;
 
 use std::mem;
 
+use constants::ACCENT_DISABLED;
 use constants::ACCENT_ENABLE_ACRYLICBLURBEHIND;
+use constants::ACCENT_ENABLE_BLURBEHIND;
+use constants::ACCENT_ENABLE_GRADIENT;
+use constants::ACCENT_ENABLE_TRANSPARENTGRADIENT;
 use constants::ACCENT_POLICY;
 use constants::WCA_ACCENT_POLICY;
 
 use constants::WINDOWCOMPOSITIONATTRIBDATA;
-use windows::core::PCSTR;
+use windows::core::s;
 use windows::Win32::Foundation::BOOL;
 use windows::Win32::Foundation::HWND;
+use windows::Win32::Graphics::Dwm::DwmExtendFrameIntoClientArea;
 use windows::Win32::Graphics::Dwm::DwmSetWindowAttribute;
+use windows::Win32::Graphics::Dwm::DWMSBT_AUTO;
 use windows::Win32::Graphics::Dwm::DWMSBT_MAINWINDOW;
+use windows::Win32::Graphics::Dwm::DWMSBT_NONE;
+use windows::Win32::Graphics::Dwm::DWMSBT_TABBEDWINDOW;
 use windows::Win32::Graphics::Dwm::DWMWA_SYSTEMBACKDROP_TYPE;
-use windows::Win32::Graphics::Dwm::DWMWINDOWATTRIBUTE;
+use windows::Win32::Graphics::Dwm::DWMWA_USE_IMMERSIVE_DARK_MODE;
+use windows::Win32::Graphics::Dwm::DWM_SYSTEMBACKDROP_TYPE;
 use windows::Win32::System::LibraryLoader::GetProcAddress;
 use windows::Win32::System::LibraryLoader::LoadLibraryA;
+use windows::Win32::UI::Controls::MARGINS;
 
-use crate::hex_converter::hex_to_rgba_int;
+pub use capabilities::*;
+pub use color::*;
+pub use effect::*;
+pub use error::*;
 
 type SetWindowCompositionAttributeFn =
   unsafe extern "system" fn(HWND, *mut WINDOWCOMPOSITIONATTRIBDATA) -> BOOL;
@@ -31,54 +47,112 @@ type SetWindowCompositionAttributeFn =
 //
 // https://learn.microsoft.com/en-us/windows/win32/api/dwmapi/ne-dwmapi-dwm_systembackdrop_type
 
-pub fn enable_blur<S: Into<String>>(hwnd: HWND, hex: S, always_active: bool) {
-  if !always_active {
-    // Set system backdrop
-    unsafe {
-      DwmSetWindowAttribute(
-        hwnd,
-        DWMWA_SYSTEMBACKDROP_TYPE,
-        &DWMSBT_MAINWINDOW as *const _ as _,
-        std::mem::size_of::<DWMWINDOWATTRIBUTE>() as _,
-      )
-      .expect("Failed to set window attribute")
-    };
-  } else {
-    // Always active backdrop
-
-    // Accent policy
-    let accent = ACCENT_POLICY {
-      nAccentState: ACCENT_ENABLE_ACRYLICBLURBEHIND,
-      nFlags: 2,
-      nGradientColor: hex_to_rgba_int(hex.into()).unwrap() as i32,
-      nAnimationId: 0,
-    };
-
-    // Window composition attribute data
-    let mut data = WINDOWCOMPOSITIONATTRIBDATA {
-      Attrib: WCA_ACCENT_POLICY,
-      pvData: &accent as *const _ as *mut _,
-      cbData: mem::size_of::<ACCENT_POLICY>(),
-    };
-
-    unsafe {
-      // Load user32.dll
-      let hmodule = LoadLibraryA(PCSTR("user32.dll\0".as_ptr() as *const u8))
-        .expect("Failed to load user32.dll");
-
-      // Get SetWindowCompositionAttribute address
-      #[allow(non_snake_case)]
-      let SetWindowCompositionAttribute: SetWindowCompositionAttributeFn = std::mem::transmute(
-        GetProcAddress(
-          hmodule,
-          PCSTR("SetWindowCompositionAttribute\0".as_ptr() as *const u8),
-        )
-        .expect("Failed to get SetWindowCompositionAttribute address"),
-      );
-
-      // Set window composition attribute
-      SetWindowCompositionAttribute(hwnd, &mut data)
-        .expect("Failed to set window composition attribute");
+/// Applies `backdrop` to the window, replacing whatever effect it had
+pub fn set_backdrop(hwnd: HWND, backdrop: Backdrop) -> Result<(), BackdropError> {
+  match backdrop {
+    Backdrop::None => clear_backdrop(hwnd),
+    Backdrop::Solid { color } => set_accent(hwnd, ACCENT_ENABLE_GRADIENT, color),
+    Backdrop::TransparentGradient { color } => {
+      set_accent(hwnd, ACCENT_ENABLE_TRANSPARENTGRADIENT, color)
     }
+    Backdrop::Blur { color } => set_accent(hwnd, ACCENT_ENABLE_BLURBEHIND, color),
+    Backdrop::Acrylic { color } => set_accent(hwnd, ACCENT_ENABLE_ACRYLICBLURBEHIND, color),
+    Backdrop::Mica { dark_mode } => set_system_backdrop(hwnd, DWMSBT_MAINWINDOW, dark_mode),
+    Backdrop::MicaAlt { dark_mode } => set_system_backdrop(hwnd, DWMSBT_TABBEDWINDOW, dark_mode),
+    Backdrop::Auto => set_system_backdrop(hwnd, DWMSBT_AUTO, false),
   }
 }
+
+/// Removes both the accent policy and the DWM system backdrop
+pub fn clear_backdrop(hwnd: HWND) -> Result<(), BackdropError> {
+  set_accent(hwnd, ACCENT_DISABLED, Color::TRANSPARENT)?;
+
+  // Builds without system backdrops reject the attribute, nothing to clear there
+  let _ = set_system_backdrop_type(hwnd, DWMSBT_NONE);
+  extend_frame(hwnd, 0)
+}
+
+fn set_system_backdrop(
+  hwnd: HWND,
+  backdrop_type: DWM_SYSTEMBACKDROP_TYPE,
+  dark_mode: bool,
+) -> Result<(), BackdropError> {
+  // An accent policy would draw over the system backdrop
+  let _ = set_accent(hwnd, ACCENT_DISABLED, Color::TRANSPARENT);
+
+  let dark_mode = BOOL::from(dark_mode);
+  unsafe {
+    DwmSetWindowAttribute(
+      hwnd,
+      DWMWA_USE_IMMERSIVE_DARK_MODE,
+      &dark_mode as *const _ as _,
+      mem::size_of::<BOOL>() as _,
+    )?;
+  }
+
+  // The backdrop is only drawn behind the frame, so extend it over the client area
+  extend_frame(hwnd, -1)?;
+  set_system_backdrop_type(hwnd, backdrop_type)
+}
+
+fn set_system_backdrop_type(
+  hwnd: HWND,
+  backdrop_type: DWM_SYSTEMBACKDROP_TYPE,
+) -> Result<(), BackdropError> {
+  unsafe {
+    DwmSetWindowAttribute(
+      hwnd,
+      DWMWA_SYSTEMBACKDROP_TYPE,
+      &backdrop_type as *const _ as _,
+      mem::size_of::<DWM_SYSTEMBACKDROP_TYPE>() as _,
+    )?;
+  }
+
+  Ok(())
+}
+
+fn extend_frame(hwnd: HWND, margin: i32) -> Result<(), BackdropError> {
+  let margins = MARGINS {
+    cxLeftWidth: margin,
+    cxRightWidth: margin,
+    cyTopHeight: margin,
+    cyBottomHeight: margin,
+  };
+
+  unsafe { DwmExtendFrameIntoClientArea(hwnd, &margins)? };
+  Ok(())
+}
+
+fn set_accent(hwnd: HWND, accent_state: i32, color: Color) -> Result<(), BackdropError> {
+  // Accent policy
+  let accent = ACCENT_POLICY {
+    nAccentState: accent_state,
+    nFlags: 2,
+    nGradientColor: color.to_abgr() as i32,
+    nAnimationId: 0,
+  };
+
+  // Window composition attribute data
+  let mut data = WINDOWCOMPOSITIONATTRIBDATA {
+    Attrib: WCA_ACCENT_POLICY,
+    pvData: &accent as *const _ as *mut _,
+    cbData: mem::size_of::<ACCENT_POLICY>(),
+  };
+
+  unsafe {
+    // Load user32.dll
+    let hmodule = LoadLibraryA(s!("user32.dll"))?;
+
+    // Get SetWindowCompositionAttribute address
+    let address = GetProcAddress(hmodule, s!("SetWindowCompositionAttribute"))
+      .ok_or(BackdropError::Unavailable("SetWindowCompositionAttribute"))?;
+
+    #[allow(non_snake_case)]
+    let SetWindowCompositionAttribute: SetWindowCompositionAttributeFn = mem::transmute(address);
+
+    // Set window composition attribute
+    SetWindowCompositionAttribute(hwnd, &mut data).ok()?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,153 @@
+use serde::Serialize;
+
+use windows::core::s;
+use windows::core::w;
+use windows::core::PCWSTR;
+use windows::Win32::Graphics::Dwm::DwmIsCompositionEnabled;
+use windows::Win32::System::LibraryLoader::GetProcAddress;
+use windows::Win32::System::LibraryLoader::LoadLibraryA;
+use windows::Win32::System::Power::GetSystemPowerStatus;
+use windows::Win32::System::Power::SYSTEM_POWER_STATUS;
+use windows::Win32::System::Registry::RegGetValueW;
+use windows::Win32::System::Registry::HKEY;
+use windows::Win32::System::Registry::HKEY_CURRENT_USER;
+use windows::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+use windows::Win32::System::Registry::RRF_RT_REG_DWORD;
+use windows::Win32::System::Registry::RRF_RT_REG_SZ;
+use windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics;
+use windows::Win32::UI::WindowsAndMessaging::SM_REMOTESESSION;
+
+use crate::Backdrop;
+
+// First builds supporting each family of effects
+const ACRYLIC_ACCENT_BUILD: u32 = 17134; // Windows 10 1803
+const SYSTEM_BACKDROP_BUILD: u32 = 22621; // Windows 11 22H2
+
+/// What the current session can render, probed from the OS
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Capabilities {
+  pub build: u32,
+  pub composition_enabled: bool,
+  pub transparency_enabled: bool,
+  pub battery_saver: bool,
+  pub remote_session: bool,
+  // `SetWindowCompositionAttribute` is undocumented and may not be exported
+  pub accent_policy: bool,
+}
+
+impl Capabilities {
+  pub fn probe() -> Self {
+    let build = read_string(
+      HKEY_LOCAL_MACHINE,
+      w!("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion"),
+      w!("CurrentBuildNumber"),
+    )
+    .and_then(|build| build.parse().ok())
+    .unwrap_or_default();
+
+    // Missing value means the user never touched the toggle, which defaults to on
+    let transparency_enabled = read_registry_dword(
+      HKEY_CURRENT_USER,
+      w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+      w!("EnableTransparency"),
+    ) != Some(0);
+
+    let mut power_status = SYSTEM_POWER_STATUS::default();
+    let battery_saver = unsafe { GetSystemPowerStatus(&mut power_status) }.is_ok()
+      && power_status.SystemStatusFlag == 1;
+
+    Self {
+      build,
+      composition_enabled: unsafe { DwmIsCompositionEnabled() }
+        .is_ok_and(|enabled| enabled.as_bool()),
+      transparency_enabled,
+      battery_saver,
+      remote_session: unsafe { GetSystemMetrics(SM_REMOTESESSION) } != 0,
+      accent_policy: has_accent_policy(),
+    }
+  }
+
+  // Whether translucent effects should be drawn at all
+  fn translucency(&self) -> bool {
+    self.composition_enabled && self.transparency_enabled && !self.battery_saver
+  }
+
+  pub fn supports(&self, backdrop: &Backdrop) -> bool {
+    match backdrop {
+      Backdrop::None => true,
+      Backdrop::Solid { .. } => self.accent_policy,
+      Backdrop::TransparentGradient { .. } | Backdrop::Blur { .. } => {
+        self.accent_policy && self.translucency()
+      }
+      // Acrylic lags badly over RDP, plain blur is used instead
+      Backdrop::Acrylic { .. } => {
+        self.accent_policy
+          && self.translucency()
+          && !self.remote_session
+          && self.build >= ACRYLIC_ACCENT_BUILD
+      }
+      Backdrop::Mica { .. } | Backdrop::MicaAlt { .. } | Backdrop::Auto => {
+        self.translucency() && !self.remote_session && self.build >= SYSTEM_BACKDROP_BUILD
+      }
+    }
+  }
+
+  /// Picks the first supported effect of `preferences`, or no effect at all
+  pub fn choose(&self, preferences: &[Backdrop]) -> Backdrop {
+    preferences
+      .iter()
+      .copied()
+      .find(|backdrop| self.supports(backdrop))
+      .unwrap_or(Backdrop::None)
+  }
+}
+
+fn has_accent_policy() -> bool {
+  unsafe {
+    LoadLibraryA(s!("user32.dll")).is_ok_and(|hmodule| {
+      GetProcAddress(hmodule, s!("SetWindowCompositionAttribute")).is_some()
+    })
+  }
+}
+
+/// Reads a `REG_DWORD` value, `None` if it is missing or of another type
+pub fn read_registry_dword(hkey: HKEY, subkey: PCWSTR, value: PCWSTR) -> Option<u32> {
+  let mut data = 0u32;
+  let mut size = std::mem::size_of::<u32>() as u32;
+
+  unsafe {
+    RegGetValueW(
+      hkey,
+      subkey,
+      value,
+      RRF_RT_REG_DWORD,
+      None,
+      Some(&mut data as *mut _ as _),
+      Some(&mut size),
+    )
+  }
+  .ok()?;
+
+  Some(data)
+}
+
+fn read_string(hkey: HKEY, subkey: PCWSTR, value: PCWSTR) -> Option<String> {
+  let mut data = [0u16; 64];
+  let mut size = std::mem::size_of_val(&data) as u32;
+
+  unsafe {
+    RegGetValueW(
+      hkey,
+      subkey,
+      value,
+      RRF_RT_REG_SZ,
+      None,
+      Some(data.as_mut_ptr() as _),
+      Some(&mut size),
+    )
+  }
+  .ok()?;
+
+  let len = data.iter().position(|c| *c == 0).unwrap_or(data.len());
+  String::from_utf16(&data[..len]).ok()
+}
@@ -0,0 +1,409 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+/// An sRGB colour with straight (non-premultiplied) alpha
+///
+/// Parses the CSS notations users are likely to write in the config:
+/// `#RGB`, `#RGBA`, `#RRGGBB`, `#RRGGBBAA`, `rgb()`/`rgba()`, `hsl()`/`hsla()`
+/// and named colours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color {
+  pub r: u8,
+  pub g: u8,
+  pub b: u8,
+  pub a: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorError {
+  Empty,
+  InvalidHex(String),
+  InvalidFunction(String),
+  InvalidComponent(String),
+  UnknownName(String),
+}
+
+impl fmt::Display for ColorError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ColorError::Empty => write!(f, "empty color"),
+      ColorError::InvalidHex(hex) => write!(
+        f,
+        "invalid hex color `{}`, expected #RGB, #RGBA, #RRGGBB or #RRGGBBAA",
+        hex
+      ),
+      ColorError::InvalidFunction(function) => write!(f, "invalid color function `{}`", function),
+      ColorError::InvalidComponent(component) => {
+        write!(f, "invalid color component `{}`", component)
+      }
+      ColorError::UnknownName(name) => write!(f, "unknown color name `{}`", name),
+    }
+  }
+}
+
+impl std::error::Error for ColorError {}
+
+impl Color {
+  pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+  pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+    Self { r, g, b, a: 0xff }
+  }
+
+  pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+    Self { r, g, b, a }
+  }
+
+  /// Packs the colour as `0xAABBGGRR`, the layout `ACCENT_POLICY` expects
+  pub fn to_abgr(self) -> u32 {
+    u32::from_le_bytes([self.r, self.g, self.b, self.a])
+  }
+
+  pub fn with_alpha(self, a: u8) -> Self {
+    Self { a, ..self }
+  }
+
+  fn from_hex(hex: &str) -> Result<Self, ColorError> {
+    let invalid = || ColorError::InvalidHex(format!("#{}", hex));
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+      return Err(invalid());
+    }
+
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).map(|d| d * 17);
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+
+    let channels = match hex.len() {
+      3 => [digit(0), digit(1), digit(2), Ok(0xff)],
+      4 => [digit(0), digit(1), digit(2), digit(3)],
+      6 => [pair(0), pair(2), pair(4), Ok(0xff)],
+      8 => [pair(0), pair(2), pair(4), pair(6)],
+      _ => return Err(invalid()),
+    };
+
+    match channels {
+      [Ok(r), Ok(g), Ok(b), Ok(a)] => Ok(Color::rgba(r, g, b, a)),
+      _ => Err(invalid()),
+    }
+  }
+
+  fn from_function(name: &str, arguments: &str, source: &str) -> Result<Self, ColorError> {
+    // Accept both `rgb(1, 2, 3, 0.5)` and `rgb(1 2 3 / 0.5)`
+    let arguments: Vec<&str> = arguments
+      .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+      .filter(|argument| !argument.is_empty())
+      .collect();
+
+    let (channels, alpha) = match arguments.as_slice() {
+      [x, y, z] => ([*x, *y, *z], None),
+      [x, y, z, a] => ([*x, *y, *z], Some(*a)),
+      _ => return Err(ColorError::InvalidFunction(source.to_string())),
+    };
+
+    let a = alpha.map(parse_alpha).transpose()?.unwrap_or(0xff);
+
+    match name {
+      "rgb" | "rgba" => {
+        let [r, g, b] = channels;
+        Ok(Color::rgba(
+          parse_channel(r)?,
+          parse_channel(g)?,
+          parse_channel(b)?,
+          a,
+        ))
+      }
+      "hsl" | "hsla" => {
+        let [h, s, l] = channels;
+        let (r, g, b) = hsl_to_rgb(parse_hue(h)?, parse_percent(s)?, parse_percent(l)?);
+        Ok(Color::rgba(r, g, b, a))
+      }
+      _ => Err(ColorError::InvalidFunction(source.to_string())),
+    }
+  }
+
+  fn from_name(name: &str) -> Result<Self, ColorError> {
+    if name == "transparent" {
+      return Ok(Color::TRANSPARENT);
+    }
+
+    NAMED_COLORS
+      .iter()
+      .find(|(named, _)| *named == name)
+      .map(|(_, [r, g, b])| Color::rgb(*r, *g, *b))
+      .ok_or_else(|| ColorError::UnknownName(name.to_string()))
+  }
+}
+
+impl FromStr for Color {
+  type Err = ColorError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let value = s.trim().to_ascii_lowercase();
+    if value.is_empty() {
+      return Err(ColorError::Empty);
+    }
+
+    if let Some(hex) = value.strip_prefix('#') {
+      return Color::from_hex(hex);
+    }
+
+    if let Some((name, rest)) = value.split_once('(') {
+      let arguments = rest
+        .strip_suffix(')')
+        .ok_or_else(|| ColorError::InvalidFunction(s.to_string()))?;
+      return Color::from_function(name.trim(), arguments, s);
+    }
+
+    Color::from_name(&value)
+  }
+}
+
+impl fmt::Display for Color {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "#{:02x}{:02x}{:02x}{:02x}",
+      self.r, self.g, self.b, self.a
+    )
+  }
+}
+
+impl Serialize for Color {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for Color {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+fn invalid_component(component: &str) -> ColorError {
+  ColorError::InvalidComponent(component.to_string())
+}
+
+// 0-255 or a percentage
+fn parse_channel(component: &str) -> Result<u8, ColorError> {
+  let value = match component.strip_suffix('%') {
+    Some(percent) => percent.parse::<f64>().map(|p| p * 2.55),
+    None => component.parse::<f64>(),
+  }
+  .map_err(|_| invalid_component(component))?;
+
+  if !(0.0..=255.0).contains(&value) {
+    return Err(invalid_component(component));
+  }
+
+  Ok(value.round() as u8)
+}
+
+// 0-1 or a percentage
+fn parse_alpha(component: &str) -> Result<u8, ColorError> {
+  let value = match component.strip_suffix('%') {
+    Some(percent) => percent.parse::<f64>().map(|p| p / 100.0),
+    None => component.parse::<f64>(),
+  }
+  .map_err(|_| invalid_component(component))?;
+
+  if !(0.0..=1.0).contains(&value) {
+    return Err(invalid_component(component));
+  }
+
+  Ok((value * 255.0).round() as u8)
+}
+
+fn parse_percent(component: &str) -> Result<f64, ColorError> {
+  component
+    .strip_suffix('%')
+    .and_then(|percent| percent.parse::<f64>().ok())
+    .filter(|percent| (0.0..=100.0).contains(percent))
+    .map(|percent| percent / 100.0)
+    .ok_or_else(|| invalid_component(component))
+}
+
+// Degrees, with or without the `deg` unit, wrapped to [0, 360)
+fn parse_hue(component: &str) -> Result<f64, ColorError> {
+  component
+    .strip_suffix("deg")
+    .unwrap_or(component)
+    .parse::<f64>()
+    .map(|hue| hue.rem_euclid(360.0))
+    .map_err(|_| invalid_component(component))
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+  let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+  let sector = h / 60.0;
+  let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+  let (r, g, b) = match sector as u32 {
+    0 => (chroma, x, 0.0),
+    1 => (x, chroma, 0.0),
+    2 => (0.0, chroma, x),
+    3 => (0.0, x, chroma),
+    4 => (x, 0.0, chroma),
+    _ => (chroma, 0.0, x),
+  };
+
+  let m = l - chroma / 2.0;
+  let channel = |value: f64| ((value + m) * 255.0).round() as u8;
+  (channel(r), channel(g), channel(b))
+}
+
+const NAMED_COLORS: [(&str, [u8; 3]); 148] = [
+  ("aliceblue", [240, 248, 255]),
+  ("antiquewhite", [250, 235, 215]),
+  ("aqua", [0, 255, 255]),
+  ("aquamarine", [127, 255, 212]),
+  ("azure", [240, 255, 255]),
+  ("beige", [245, 245, 220]),
+  ("bisque", [255, 228, 196]),
+  ("black", [0, 0, 0]),
+  ("blanchedalmond", [255, 235, 205]),
+  ("blue", [0, 0, 255]),
+  ("blueviolet", [138, 43, 226]),
+  ("brown", [165, 42, 42]),
+  ("burlywood", [222, 184, 135]),
+  ("cadetblue", [95, 158, 160]),
+  ("chartreuse", [127, 255, 0]),
+  ("chocolate", [210, 105, 30]),
+  ("coral", [255, 127, 80]),
+  ("cornflowerblue", [100, 149, 237]),
+  ("cornsilk", [255, 248, 220]),
+  ("crimson", [220, 20, 60]),
+  ("cyan", [0, 255, 255]),
+  ("darkblue", [0, 0, 139]),
+  ("darkcyan", [0, 139, 139]),
+  ("darkgoldenrod", [184, 134, 11]),
+  ("darkgray", [169, 169, 169]),
+  ("darkgreen", [0, 100, 0]),
+  ("darkgrey", [169, 169, 169]),
+  ("darkkhaki", [189, 183, 107]),
+  ("darkmagenta", [139, 0, 139]),
+  ("darkolivegreen", [85, 107, 47]),
+  ("darkorange", [255, 140, 0]),
+  ("darkorchid", [153, 50, 204]),
+  ("darkred", [139, 0, 0]),
+  ("darksalmon", [233, 150, 122]),
+  ("darkseagreen", [143, 188, 143]),
+  ("darkslateblue", [72, 61, 139]),
+  ("darkslategray", [47, 79, 79]),
+  ("darkslategrey", [47, 79, 79]),
+  ("darkturquoise", [0, 206, 209]),
+  ("darkviolet", [148, 0, 211]),
+  ("deeppink", [255, 20, 147]),
+  ("deepskyblue", [0, 191, 255]),
+  ("dimgray", [105, 105, 105]),
+  ("dimgrey", [105, 105, 105]),
+  ("dodgerblue", [30, 144, 255]),
+  ("firebrick", [178, 34, 34]),
+  ("floralwhite", [255, 250, 240]),
+  ("forestgreen", [34, 139, 34]),
+  ("fuchsia", [255, 0, 255]),
+  ("gainsboro", [220, 220, 220]),
+  ("ghostwhite", [248, 248, 255]),
+  ("gold", [255, 215, 0]),
+  ("goldenrod", [218, 165, 32]),
+  ("gray", [128, 128, 128]),
+  ("green", [0, 128, 0]),
+  ("greenyellow", [173, 255, 47]),
+  ("grey", [128, 128, 128]),
+  ("honeydew", [240, 255, 240]),
+  ("hotpink", [255, 105, 180]),
+  ("indianred", [205, 92, 92]),
+  ("indigo", [75, 0, 130]),
+  ("ivory", [255, 255, 240]),
+  ("khaki", [240, 230, 140]),
+  ("lavender", [230, 230, 250]),
+  ("lavenderblush", [255, 240, 245]),
+  ("lawngreen", [124, 252, 0]),
+  ("lemonchiffon", [255, 250, 205]),
+  ("lightblue", [173, 216, 230]),
+  ("lightcoral", [240, 128, 128]),
+  ("lightcyan", [224, 255, 255]),
+  ("lightgoldenrodyellow", [250, 250, 210]),
+  ("lightgray", [211, 211, 211]),
+  ("lightgreen", [144, 238, 144]),
+  ("lightgrey", [211, 211, 211]),
+  ("lightpink", [255, 182, 193]),
+  ("lightsalmon", [255, 160, 122]),
+  ("lightseagreen", [32, 178, 170]),
+  ("lightskyblue", [135, 206, 250]),
+  ("lightslategray", [119, 136, 153]),
+  ("lightslategrey", [119, 136, 153]),
+  ("lightsteelblue", [176, 196, 222]),
+  ("lightyellow", [255, 255, 224]),
+  ("lime", [0, 255, 0]),
+  ("limegreen", [50, 205, 50]),
+  ("linen", [250, 240, 230]),
+  ("magenta", [255, 0, 255]),
+  ("maroon", [128, 0, 0]),
+  ("mediumaquamarine", [102, 205, 170]),
+  ("mediumblue", [0, 0, 205]),
+  ("mediumorchid", [186, 85, 211]),
+  ("mediumpurple", [147, 112, 219]),
+  ("mediumseagreen", [60, 179, 113]),
+  ("mediumslateblue", [123, 104, 238]),
+  ("mediumspringgreen", [0, 250, 154]),
+  ("mediumturquoise", [72, 209, 204]),
+  ("mediumvioletred", [199, 21, 133]),
+  ("midnightblue", [25, 25, 112]),
+  ("mintcream", [245, 255, 250]),
+  ("mistyrose", [255, 228, 225]),
+  ("moccasin", [255, 228, 181]),
+  ("navajowhite", [255, 222, 173]),
+  ("navy", [0, 0, 128]),
+  ("oldlace", [253, 245, 230]),
+  ("olive", [128, 128, 0]),
+  ("olivedrab", [107, 142, 35]),
+  ("orange", [255, 165, 0]),
+  ("orangered", [255, 69, 0]),
+  ("orchid", [218, 112, 214]),
+  ("palegoldenrod", [238, 232, 170]),
+  ("palegreen", [152, 251, 152]),
+  ("paleturquoise", [175, 238, 238]),
+  ("palevioletred", [219, 112, 147]),
+  ("papayawhip", [255, 239, 213]),
+  ("peachpuff", [255, 218, 185]),
+  ("peru", [205, 133, 63]),
+  ("pink", [255, 192, 203]),
+  ("plum", [221, 160, 221]),
+  ("powderblue", [176, 224, 230]),
+  ("purple", [128, 0, 128]),
+  ("rebeccapurple", [102, 51, 153]),
+  ("red", [255, 0, 0]),
+  ("rosybrown", [188, 143, 143]),
+  ("royalblue", [65, 105, 225]),
+  ("saddlebrown", [139, 69, 19]),
+  ("salmon", [250, 128, 114]),
+  ("sandybrown", [244, 164, 96]),
+  ("seagreen", [46, 139, 87]),
+  ("seashell", [255, 245, 238]),
+  ("sienna", [160, 82, 45]),
+  ("silver", [192, 192, 192]),
+  ("skyblue", [135, 206, 235]),
+  ("slateblue", [106, 90, 205]),
+  ("slategray", [112, 128, 144]),
+  ("slategrey", [112, 128, 144]),
+  ("snow", [255, 250, 250]),
+  ("springgreen", [0, 255, 127]),
+  ("steelblue", [70, 130, 180]),
+  ("tan", [210, 180, 140]),
+  ("teal", [0, 128, 128]),
+  ("thistle", [216, 191, 216]),
+  ("tomato", [255, 99, 71]),
+  ("turquoise", [64, 224, 208]),
+  ("violet", [238, 130, 238]),
+  ("wheat", [245, 222, 179]),
+  ("white", [255, 255, 255]),
+  ("whitesmoke", [245, 245, 245]),
+  ("yellow", [255, 255, 0]),
+  ("yellowgreen", [154, 205, 50]),
+];
@@ -13,6 +13,7 @@ pub struct ACCENT_POLICY {
 
 #[repr(C)]
 #[allow(non_snake_case)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct WINDOWCOMPOSITIONATTRIBDATA {
   // Options for [Get/Set]WindowCompositionAttribute.
   pub Attrib: i32,                   // Type of what is being get or set.
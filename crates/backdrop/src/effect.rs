@@ -0,0 +1,41 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Color;
+
+/// Background effect of a window
+///
+/// `Solid` through `Acrylic` go through the undocumented accent policy and are
+/// active even when the window is not focused, `Mica`, `MicaAlt` and `Auto` are
+/// DWM system backdrops (Windows 11 22H2+) that fade out on inactive windows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Backdrop {
+  #[default]
+  None,
+  Solid {
+    #[serde(default)]
+    color: Color,
+  },
+  TransparentGradient {
+    #[serde(default)]
+    color: Color,
+  },
+  Blur {
+    #[serde(default)]
+    color: Color,
+  },
+  Acrylic {
+    #[serde(default)]
+    color: Color,
+  },
+  Mica {
+    #[serde(default)]
+    dark_mode: bool,
+  },
+  MicaAlt {
+    #[serde(default)]
+    dark_mode: bool,
+  },
+  Auto,
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum BackdropError {
+  // A DWM or user32 call failed
+  Windows(windows::core::Error),
+  // The entry point needed for the effect is not exported on this system
+  Unavailable(&'static str),
+}
+
+impl fmt::Display for BackdropError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      BackdropError::Windows(err) => write!(f, "{}", err),
+      BackdropError::Unavailable(name) => write!(f, "{} is not available", name),
+    }
+  }
+}
+
+impl std::error::Error for BackdropError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      BackdropError::Windows(err) => Some(err),
+      BackdropError::Unavailable(_) => None,
+    }
+  }
+}
+
+impl From<windows::core::Error> for BackdropError {
+  fn from(err: windows::core::Error) -> Self {
+    BackdropError::Windows(err)
+  }
+}
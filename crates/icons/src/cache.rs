@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Cursor;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use image::ImageFormat;
+
+use crate::effects::IconVariant;
+use crate::get_icon;
+
+// Keyed by executable path and variant key, base icons use the `base` key
+static ICON_CACHE: LazyLock<Mutex<HashMap<(String, String), Vec<u8>>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Same as [`get_icon`], but only extracts each executable's icon once
+pub fn get_cached_icon(exe_path: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+  get_icon_variant(exe_path, &IconVariant::default())
+}
+
+/// Returns the PNG bytes of `exe_path`'s icon with `variant` applied
+///
+/// Icons without any extractable image stay empty regardless of the variant.
+pub fn get_icon_variant(exe_path: &str, variant: &IconVariant) -> Result<Vec<u8>, Box<dyn Error>> {
+  let key = (exe_path.to_string(), variant.to_string());
+  if let Some(bytes) = ICON_CACHE.lock().unwrap().get(&key) {
+    return Ok(bytes.clone());
+  }
+
+  let bytes = if variant.is_base() {
+    get_icon(exe_path)?
+  } else {
+    let base = get_cached_icon(exe_path)?;
+    if base.is_empty() {
+      base
+    } else {
+      let mut image = image::load_from_memory(&base)?.to_rgba8();
+      variant.apply(&mut image);
+
+      let mut cursor = Cursor::new(Vec::new());
+      image.write_to(&mut cursor, ImageFormat::Png)?;
+      cursor.into_inner()
+    }
+  };
+
+  ICON_CACHE.lock().unwrap().insert(key, bytes.clone());
+  Ok(bytes)
+}
+
+/// Drops every cached icon of `exe_path`, e.g. after the app was updated
+pub fn invalidate_icon(exe_path: &str) {
+  ICON_CACHE
+    .lock()
+    .unwrap()
+    .retain(|(path, _), _| path != exe_path);
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+use std::str::FromStr;
+
+use image::Rgba;
+use image::RgbaImage;
+
+// Windows' notification badge red
+const BADGE_COLOR: Rgba<u8> = Rgba([0xe8, 0x11, 0x23, 0xff]);
+const BADGE_TEXT_COLOR: Rgba<u8> = Rgba([0xff, 0xff, 0xff, 0xff]);
+
+/// A single transformation applied on top of a base icon
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IconEffect {
+  /// Desaturate the icon, keeping its alpha channel
+  Grayscale,
+  /// Multiply the alpha channel by the given opacity (0-100)
+  Dimmed(u8),
+  /// Replace every colour with a single one, keeping the silhouette
+  Tinted(Rgba<u8>),
+  /// Composite a badge on the top-right corner
+  Badge(Badge),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Badge {
+  Dot,
+  Count(u32),
+}
+
+/// Ordered list of effects, addressable by a key such as `grayscale+badge:3`
+///
+/// An empty variant (key `base`) is the icon as extracted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IconVariant(pub Vec<IconEffect>);
+
+impl IconVariant {
+  pub fn is_base(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  pub fn apply(&self, image: &mut RgbaImage) {
+    for effect in &self.0 {
+      effect.apply(image);
+    }
+  }
+}
+
+impl IconEffect {
+  pub fn apply(&self, image: &mut RgbaImage) {
+    match self {
+      IconEffect::Grayscale => grayscale(image),
+      IconEffect::Dimmed(opacity) => dim(image, *opacity),
+      IconEffect::Tinted(color) => tint(image, *color),
+      IconEffect::Badge(badge) => draw_badge(image, *badge),
+    }
+  }
+}
+
+pub fn grayscale(image: &mut RgbaImage) {
+  for pixel in image.pixels_mut() {
+    let [r, g, b, a] = pixel.0;
+    let luma = (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32).round() as u8;
+    *pixel = Rgba([luma, luma, luma, a]);
+  }
+}
+
+pub fn dim(image: &mut RgbaImage, opacity: u8) {
+  let opacity = opacity.min(100) as u32;
+  for pixel in image.pixels_mut() {
+    pixel.0[3] = (pixel.0[3] as u32 * opacity / 100) as u8;
+  }
+}
+
+pub fn tint(image: &mut RgbaImage, color: Rgba<u8>) {
+  let [r, g, b, a] = color.0;
+  for pixel in image.pixels_mut() {
+    let alpha = (pixel.0[3] as u32 * a as u32 / 255) as u8;
+    *pixel = Rgba([r, g, b, alpha]);
+  }
+}
+
+pub fn draw_badge(image: &mut RgbaImage, badge: Badge) {
+  let size = image.width().min(image.height()) as f32;
+  if size == 0.0 {
+    return;
+  }
+
+  match badge {
+    Badge::Dot => {
+      let radius = size * 0.16;
+      let center = (image.width() as f32 - radius, radius);
+      fill_pill(image, center, center, radius, BADGE_COLOR);
+    }
+    Badge::Count(count) => {
+      let text = if count > 99 {
+        "99+".to_string()
+      } else {
+        count.to_string()
+      };
+
+      let radius = size * 0.24;
+      // Each glyph is 3x5 cells with a one cell gap between glyphs
+      let cell = (radius * 1.1 / 5.0).max(1.0);
+      let text_width = (text.len() as f32 * 4.0 - 1.0) * cell;
+      let half_span = (text_width / 2.0 - radius * 0.6).max(0.0);
+
+      let right = image.width() as f32 - radius;
+      let end = (right, radius);
+      let start = (right - half_span * 2.0, radius);
+      fill_pill(image, start, end, radius, BADGE_COLOR);
+
+      let center_x = (start.0 + end.0) / 2.0;
+      let origin = (center_x - text_width / 2.0, radius - 2.5 * cell);
+      draw_text(image, &text, origin, cell, BADGE_TEXT_COLOR);
+    }
+  }
+}
+
+// Fills a capsule between two centers, anti-aliasing the edge by distance
+fn fill_pill(
+  image: &mut RgbaImage,
+  start: (f32, f32),
+  end: (f32, f32),
+  radius: f32,
+  color: Rgba<u8>,
+) {
+  let min_x = (start.0.min(end.0) - radius - 1.0).max(0.0) as u32;
+  let max_x = ((start.0.max(end.0) + radius + 1.0) as u32).min(image.width());
+  let min_y = (start.1.min(end.1) - radius - 1.0).max(0.0) as u32;
+  let max_y = ((start.1.max(end.1) + radius + 1.0) as u32).min(image.height());
+
+  for y in min_y..max_y {
+    for x in min_x..max_x {
+      let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+      let nearest_x = px.clamp(start.0.min(end.0), start.0.max(end.0));
+      let distance = ((px - nearest_x).powi(2) + (py - start.1).powi(2)).sqrt();
+      let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+      if coverage > 0.0 {
+        blend(image.get_pixel_mut(x, y), color, coverage);
+      }
+    }
+  }
+}
+
+fn draw_text(image: &mut RgbaImage, text: &str, origin: (f32, f32), cell: f32, color: Rgba<u8>) {
+  for (index, ch) in text.chars().enumerate() {
+    let Some(rows) = glyph(ch) else { continue };
+    let glyph_x = origin.0 + index as f32 * 4.0 * cell;
+
+    for (row, bits) in rows.iter().enumerate() {
+      for column in 0..3 {
+        if bits & (0b100 >> column) == 0 {
+          continue;
+        }
+
+        let x0 = glyph_x + column as f32 * cell;
+        let y0 = origin.1 + row as f32 * cell;
+        fill_rect(image, x0, y0, cell, cell, color);
+      }
+    }
+  }
+}
+
+fn fill_rect(image: &mut RgbaImage, x: f32, y: f32, width: f32, height: f32, color: Rgba<u8>) {
+  let min_x = x.max(0.0) as u32;
+  let max_x = ((x + width).ceil() as u32).min(image.width());
+  let min_y = y.max(0.0) as u32;
+  let max_y = ((y + height).ceil() as u32).min(image.height());
+
+  for py in min_y..max_y {
+    for px in min_x..max_x {
+      // Partial coverage on the edges of fractional cells
+      let cover_x = ((px + 1) as f32).min(x + width) - (px as f32).max(x);
+      let cover_y = ((py + 1) as f32).min(y + height) - (py as f32).max(y);
+      let coverage = (cover_x * cover_y).clamp(0.0, 1.0);
+      if coverage > 0.0 {
+        blend(image.get_pixel_mut(px, py), color, coverage);
+      }
+    }
+  }
+}
+
+// Source-over compositing of `color` with the given coverage
+fn blend(dst: &mut Rgba<u8>, color: Rgba<u8>, coverage: f32) {
+  let src_a = color.0[3] as f32 / 255.0 * coverage;
+  let dst_a = dst.0[3] as f32 / 255.0;
+  let out_a = src_a + dst_a * (1.0 - src_a);
+  if out_a <= 0.0 {
+    *dst = Rgba([0, 0, 0, 0]);
+    return;
+  }
+
+  let channel = |src: u8, dst: u8| {
+    ((src as f32 * src_a + dst as f32 * dst_a * (1.0 - src_a)) / out_a).round() as u8
+  };
+
+  *dst = Rgba([
+    channel(color.0[0], dst.0[0]),
+    channel(color.0[1], dst.0[1]),
+    channel(color.0[2], dst.0[2]),
+    (out_a * 255.0).round() as u8,
+  ]);
+}
+
+// 3x5 bitmap font, most significant bit is the leftmost column
+fn glyph(ch: char) -> Option<[u8; 5]> {
+  Some(match ch {
+    '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+    '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+    '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+    '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+    '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+    '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+    '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+    '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+    '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+    '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+    '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+    _ => return None,
+  })
+}
+
+fn parse_hex_color(hex: &str) -> Option<Rgba<u8>> {
+  let digits = hex.strip_prefix('#')?;
+  let channel = |i: usize| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok();
+
+  match digits.len() {
+    6 => Some(Rgba([channel(0)?, channel(2)?, channel(4)?, 0xff])),
+    8 => Some(Rgba([channel(0)?, channel(2)?, channel(4)?, channel(6)?])),
+    _ => None,
+  }
+}
+
+impl FromStr for IconEffect {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (name, argument) = match s.split_once(':') {
+      Some((name, argument)) => (name, Some(argument)),
+      None => (s, None),
+    };
+
+    match (name, argument) {
+      ("grayscale", None) => Ok(IconEffect::Grayscale),
+      ("dimmed", Some(opacity)) => opacity
+        .parse::<u8>()
+        .ok()
+        .filter(|opacity| *opacity <= 100)
+        .map(IconEffect::Dimmed)
+        .ok_or_else(|| format!("Invalid opacity: {}", opacity)),
+      ("tinted", Some(color)) => parse_hex_color(color)
+        .map(IconEffect::Tinted)
+        .ok_or_else(|| format!("Invalid tint color: {}", color)),
+      ("badge", Some("dot")) => Ok(IconEffect::Badge(Badge::Dot)),
+      ("badge", Some(count)) => count
+        .parse::<u32>()
+        .map(|count| IconEffect::Badge(Badge::Count(count)))
+        .map_err(|_| format!("Invalid badge count: {}", count)),
+      _ => Err(format!("Unknown icon effect: {}", s)),
+    }
+  }
+}
+
+impl FromStr for IconVariant {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.is_empty() || s == "base" {
+      return Ok(IconVariant::default());
+    }
+
+    s.split('+')
+      .map(str::parse)
+      .collect::<Result<Vec<_>, _>>()
+      .map(IconVariant)
+  }
+}
+
+impl fmt::Display for IconEffect {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      IconEffect::Grayscale => write!(f, "grayscale"),
+      IconEffect::Dimmed(opacity) => write!(f, "dimmed:{}", opacity),
+      IconEffect::Tinted(Rgba([r, g, b, a])) => {
+        write!(f, "tinted:#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+      }
+      IconEffect::Badge(Badge::Dot) => write!(f, "badge:dot"),
+      IconEffect::Badge(Badge::Count(count)) => write!(f, "badge:{}", count),
+    }
+  }
+}
+
+impl fmt::Display for IconVariant {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_base() {
+      return write!(f, "base");
+    }
+
+    let keys: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+    write!(f, "{}", keys.join("+"))
+  }
+}
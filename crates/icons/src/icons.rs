@@ -1,4 +1,6 @@
+mod cache;
 mod convert;
+mod effects;
 
 use image::ImageFormat;
 use image::RgbaImage;
@@ -26,6 +28,9 @@ use std::path::Path;
 use convert::hicon_to_rgba;
 use util::Result as AppResult;
 
+pub use cache::*;
+pub use effects::*;
+
 pub fn get_images_from_exe(executable_path: &str) -> AppResult<Vec<RgbaImage>> {
   unsafe {
     let path_cstr =
@@ -77,6 +82,13 @@ pub fn get_images_from_exe(executable_path: &str) -> AppResult<Vec<RgbaImage>> {
   }
 }
 
+/// Returns the PNG bytes of `hicon`, which stays owned by the caller
+pub fn hicon_to_png(hicon: &HICON) -> Result<Vec<u8>, Box<dyn Error>> {
+  let mut cursor = Cursor::new(Vec::new());
+  hicon_to_rgba(hicon)?.write_to(&mut cursor, ImageFormat::Png)?;
+  Ok(cursor.into_inner())
+}
+
 pub fn decode_uri(s: impl AsRef<str>) -> String {
   let re = Regex::new(r"%([A-Fa-f0-9]{2})").unwrap();
   re.replace_all(s.as_ref(), |caps: &regex::Captures| {
@@ -0,0 +1,57 @@
+// CRC-64 Windows hashes AppUserModelIDs with to name destinations files
+const POLYNOMIAL: u64 = 0x92C6_4265_D321_39A4;
+
+// `KNOWNFOLDERID`s Windows puts in place of known folders when it derives an
+// AppID from an executable path
+pub const PROGRAM_FILES_X64: &str = "{6D809377-6AF0-444B-8957-A3773F02200E}";
+pub const PROGRAM_FILES_X86: &str = "{7C5A40EF-A0FB-4BFC-874A-C0F2E0B9FA8E}";
+pub const SYSTEM: &str = "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}";
+pub const SYSTEM_X86: &str = "{D65231B0-B2F1-4857-A4CE-A8E7C6EA7D27}";
+pub const WINDOWS: &str = "{F38BF404-1D43-42F2-9305-67DE0B28FC23}";
+
+fn crc64(data: impl Iterator<Item = u8>) -> u64 {
+  data.fold(u64::MAX, |crc, byte| {
+    (0..8).fold(crc ^ byte as u64, |crc, _| {
+      if crc & 1 == 1 {
+        (crc >> 1) ^ POLYNOMIAL
+      } else {
+        crc >> 1
+      }
+    })
+  })
+}
+
+/// Name the destinations files of `app_id` start with, the CRC-64 of the
+/// uppercased AppID in UTF-16LE
+pub fn app_id_hash(app_id: &str) -> String {
+  let bytes = app_id
+    .to_uppercase()
+    .encode_utf16()
+    .flat_map(u16::to_le_bytes)
+    .collect::<Vec<_>>();
+
+  format!("{:016x}", crc64(bytes.into_iter()))
+}
+
+/// AppID of an app that doesn't set one, its executable path with the longest
+/// matching `folders` prefix replaced by the folder id
+///
+/// `folders` pairs a folder path with its id, e.g. `C:\Windows` with
+/// [`WINDOWS`].
+pub fn default_app_id(exe_path: &str, folders: &[(String, &str)]) -> String {
+  folders
+    .iter()
+    .map(|(path, id)| (path.trim_end_matches('\\'), id))
+    .filter(|(path, _)| {
+      !path.is_empty()
+        && exe_path
+          .get(..path.len())
+          .is_some_and(|prefix| prefix.eq_ignore_ascii_case(path))
+        && exe_path[path.len()..].starts_with('\\')
+    })
+    .max_by_key(|(path, _)| path.len())
+    .map_or_else(
+      || exe_path.to_string(),
+      |(path, id)| format!("{}{}", id, &exe_path[path.len()..]),
+    )
+}
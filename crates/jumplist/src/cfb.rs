@@ -0,0 +1,239 @@
+use crate::read::{bytes, u16_at, u32_at, u64_at, utf16};
+use crate::JumpListError;
+use crate::Result;
+
+// Compound File Binary, the OLE container `.automaticDestinations-ms` files
+// are stored in ([MS-CFB])
+
+const SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+const HEADER_SIZE: usize = 512;
+// DIFAT entries that fit in the header, the rest are chained from it
+const HEADER_DIFAT: usize = 109;
+const DIRECTORY_ENTRY_SIZE: usize = 128;
+// Sector ids past this one are markers (free, end of chain, ...)
+const MAX_SECTOR: u32 = 0xFFFF_FFFA;
+const STREAM: u8 = 2;
+const ROOT: u8 = 5;
+
+struct Entry {
+  name: String,
+  kind: u8,
+  start: u32,
+  size: u64,
+}
+
+/// A parsed compound file, streams are read out of `data` on demand
+pub struct Compound<'a> {
+  data: &'a [u8],
+  sector_size: usize,
+  mini_sector_size: usize,
+  mini_cutoff: u64,
+  fat: Vec<u32>,
+  mini_fat: Vec<u32>,
+  mini_stream: Vec<u8>,
+  entries: Vec<Entry>,
+}
+
+impl<'a> Compound<'a> {
+  pub fn parse(data: &'a [u8]) -> Result<Self> {
+    if bytes(data, 0, 8, "compound file header")? != SIGNATURE {
+      return Err(JumpListError::Malformed("compound file signature"));
+    }
+
+    let sector_shift = u16_at(data, 30, "compound file header")?;
+    let mini_sector_shift = u16_at(data, 32, "compound file header")?;
+    if !(7..=16).contains(&sector_shift) || mini_sector_shift >= sector_shift {
+      return Err(JumpListError::Malformed("compound file sector size"));
+    }
+
+    let mut compound = Compound {
+      data,
+      sector_size: 1 << sector_shift,
+      mini_sector_size: 1 << mini_sector_shift,
+      mini_cutoff: u32_at(data, 56, "compound file header")? as u64,
+      fat: Vec::new(),
+      mini_fat: Vec::new(),
+      mini_stream: Vec::new(),
+      entries: Vec::new(),
+    };
+
+    compound.fat = compound.read_fat()?;
+    let first_mini_fat = u32_at(data, 60, "compound file header")?;
+    compound.mini_fat = compound
+      .read_chain(first_mini_fat)?
+      .chunks_exact(4)
+      .map(|id| u32::from_le_bytes([id[0], id[1], id[2], id[3]]))
+      .collect();
+
+    let first_directory = u32_at(data, 48, "compound file header")?;
+    let directory = compound.read_chain(first_directory)?;
+    compound.entries = directory
+      .chunks_exact(DIRECTORY_ENTRY_SIZE)
+      .map(|entry| compound.read_entry(entry))
+      .collect::<Result<_>>()?;
+
+    let root = compound
+      .entries
+      .first()
+      .filter(|entry| entry.kind == ROOT)
+      .ok_or(JumpListError::Malformed("compound file root entry"))?;
+    let (start, size) = (root.start, root.size);
+    let mut mini_stream = compound.read_chain(start)?;
+    mini_stream.truncate(size as usize);
+    compound.mini_stream = mini_stream;
+
+    Ok(compound)
+  }
+
+  /// Names of the streams in the file
+  pub fn streams(&self) -> impl Iterator<Item = &str> {
+    self
+      .entries
+      .iter()
+      .filter(|entry| entry.kind == STREAM)
+      .map(|entry| entry.name.as_str())
+  }
+
+  /// Contents of the stream called `name`
+  ///
+  /// Jump lists keep their streams directly under the root, storages are not
+  /// walked.
+  pub fn stream(&self, name: &str) -> Result<Vec<u8>> {
+    let entry = self
+      .entries
+      .iter()
+      .find(|entry| entry.kind == STREAM && entry.name == name)
+      .ok_or(JumpListError::Malformed("compound file stream name"))?;
+
+    let mut data = if entry.size < self.mini_cutoff {
+      self.read_mini_chain(entry.start)?
+    } else {
+      self.read_chain(entry.start)?
+    };
+    if (data.len() as u64) < entry.size {
+      return Err(JumpListError::Malformed("compound file stream size"));
+    }
+    data.truncate(entry.size as usize);
+
+    Ok(data)
+  }
+
+  fn sector(&self, id: u32) -> Result<&'a [u8]> {
+    let offset = (id as usize + 1)
+      .checked_mul(self.sector_size)
+      .ok_or(JumpListError::Malformed("compound file sector id"))?;
+    bytes(self.data, offset, self.sector_size, "compound file sector")
+  }
+
+  fn sector_ids(&self, id: u32) -> Result<impl Iterator<Item = u32> + 'a> {
+    Ok(
+      self
+        .sector(id)?
+        .chunks_exact(4)
+        .map(|id| u32::from_le_bytes([id[0], id[1], id[2], id[3]])),
+    )
+  }
+
+  // The FAT sectors are listed in the DIFAT, which starts in the header and
+  // continues in a chain of sectors whose last id points to the next one
+  fn read_fat(&self) -> Result<Vec<u32>> {
+    let fat_sectors = u32_at(self.data, 44, "compound file header")? as usize;
+    // Each FAT sector is a sector of the file, more than fit in it is a
+    // corrupt header and would size the allocations below
+    if fat_sectors > self.data.len() / self.sector_size {
+      return Err(JumpListError::Malformed("compound file FAT size"));
+    }
+    let mut difat = (0..HEADER_DIFAT)
+      .map(|index| u32_at(self.data, 76 + index * 4, "compound file header"))
+      .collect::<Result<Vec<_>>>()?;
+
+    let mut next = u32_at(self.data, 68, "compound file header")?;
+    let mut visited = 0;
+    while next <= MAX_SECTOR && difat.len() < fat_sectors {
+      visited += 1;
+      if visited > fat_sectors {
+        return Err(JumpListError::Malformed("compound file DIFAT chain"));
+      }
+
+      let ids = self.sector_ids(next)?.collect::<Vec<_>>();
+      let (last, ids) = ids
+        .split_last()
+        .ok_or(JumpListError::Malformed("compound file DIFAT sector"))?;
+      difat.extend_from_slice(ids);
+      next = *last;
+    }
+
+    let mut fat = Vec::with_capacity(fat_sectors * self.sector_size / 4);
+    for id in difat.into_iter().take(fat_sectors) {
+      if id > MAX_SECTOR {
+        break;
+      }
+      fat.extend(self.sector_ids(id)?);
+    }
+
+    Ok(fat)
+  }
+
+  fn read_entry(&self, entry: &[u8]) -> Result<Entry> {
+    let name_length = u16_at(entry, 64, "compound file directory entry")? as usize;
+    let name = bytes(
+      entry,
+      0,
+      name_length.min(64),
+      "compound file directory entry",
+    )?;
+
+    let mut size = u64_at(entry, 120, "compound file directory entry")?;
+    // Version 3 files only use the lower half
+    if self.sector_size == HEADER_SIZE {
+      size &= 0xFFFF_FFFF;
+    }
+
+    Ok(Entry {
+      name: utf16(name),
+      kind: entry[66],
+      start: u32_at(entry, 116, "compound file directory entry")?,
+      size,
+    })
+  }
+
+  // Follows a chain in `table` from `start`, refusing loops
+  fn chain(table: &[u32], start: u32) -> Result<Vec<u32>> {
+    let mut ids = Vec::new();
+    let mut id = start;
+    while id <= MAX_SECTOR {
+      if ids.len() >= table.len() {
+        return Err(JumpListError::Malformed("compound file sector chain"));
+      }
+      ids.push(id);
+      id = *table
+        .get(id as usize)
+        .ok_or(JumpListError::Malformed("compound file sector chain"))?;
+    }
+
+    Ok(ids)
+  }
+
+  fn read_chain(&self, start: u32) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    for id in Self::chain(&self.fat, start)? {
+      data.extend_from_slice(self.sector(id)?);
+    }
+
+    Ok(data)
+  }
+
+  fn read_mini_chain(&self, start: u32) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    for id in Self::chain(&self.mini_fat, start)? {
+      data.extend_from_slice(bytes(
+        &self.mini_stream,
+        id as usize * self.mini_sector_size,
+        self.mini_sector_size,
+        "compound file mini sector",
+      )?);
+    }
+
+    Ok(data)
+  }
+}
@@ -0,0 +1,106 @@
+use crate::lnk::{parse_link, Link, LINK_CLSID};
+use crate::read::{bytes, u16_at, u32_at, utf16};
+use crate::JumpListError;
+use crate::Result;
+
+// `.customDestinations-ms` files, the categories and tasks an app sets with
+// `ICustomDestinationList`
+
+const HEADER_SIZE: usize = 12;
+const CATEGORY_FOOTER: u32 = 0xBABF_FBAB;
+
+const CUSTOM: u32 = 0;
+const KNOWN: u32 = 1;
+const TASKS: u32 = 2;
+
+const KNOWN_FREQUENT: u32 = 1;
+const KNOWN_RECENT: u32 = 2;
+
+/// An automatic category an app asked to show
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KnownCategory {
+  Frequent,
+  Recent,
+}
+
+/// A category in the order the app added it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CustomCategory {
+  Custom { name: String, links: Vec<Link> },
+  Known(KnownCategory),
+  Tasks(Vec<Link>),
+}
+
+pub fn parse_custom(data: &[u8]) -> Result<Vec<CustomCategory>> {
+  let count = u32_at(data, 4, "custom destinations header")? as usize;
+  let mut offset = HEADER_SIZE;
+  let mut categories = Vec::new();
+
+  for _ in 0..count {
+    let category = match u32_at(data, offset, "custom destinations category")? {
+      CUSTOM => {
+        let length = u16_at(data, offset + 4, "custom destinations category")? as usize * 2;
+        let name = utf16(bytes(
+          data,
+          offset + 6,
+          length,
+          "custom destinations category",
+        )?);
+        offset += 6 + length;
+
+        let links = links(data, &mut offset)?;
+        CustomCategory::Custom { name, links }
+      }
+      KNOWN => {
+        let id = u32_at(data, offset + 4, "custom destinations category")?;
+        offset += 8;
+
+        match id {
+          KNOWN_FREQUENT => CustomCategory::Known(KnownCategory::Frequent),
+          KNOWN_RECENT => CustomCategory::Known(KnownCategory::Recent),
+          _ => {
+            return Err(JumpListError::Malformed(
+              "custom destinations known category",
+            ))
+          }
+        }
+      }
+      TASKS => {
+        offset += 4;
+        CustomCategory::Tasks(links(data, &mut offset)?)
+      }
+      _ => {
+        return Err(JumpListError::Malformed(
+          "custom destinations category type",
+        ))
+      }
+    };
+
+    if u32_at(data, offset, "custom destinations footer")? != CATEGORY_FOOTER {
+      return Err(JumpListError::Malformed("custom destinations footer"));
+    }
+    offset += 4;
+    categories.push(category);
+  }
+
+  Ok(categories)
+}
+
+// A count followed by that many links, each after the CLSID of its type
+fn links(data: &[u8], offset: &mut usize) -> Result<Vec<Link>> {
+  let count = u32_at(data, *offset, "custom destinations items")?;
+  *offset += 4;
+
+  (0..count)
+    .map(|_| {
+      // Other item types have no length to skip them by
+      if bytes(data, *offset, 16, "custom destinations item")? != LINK_CLSID {
+        return Err(JumpListError::Malformed("custom destinations item type"));
+      }
+
+      let (link, length) = parse_link(&data[*offset + 16..])?;
+      *offset += 16 + length;
+      Ok(link)
+    })
+    .collect()
+}
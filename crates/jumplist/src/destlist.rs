@@ -0,0 +1,62 @@
+use crate::read::{bytes, i32_at, u16_at, u32_at, u64_at, utf16};
+use crate::JumpListError;
+use crate::Result;
+
+// The `DestList` stream of `.automaticDestinations-ms` files, describing the
+// link stream of each destination
+
+const HEADER_SIZE: usize = 32;
+// Path lengths are at these offsets, before Windows 10 and after
+const PATH_LENGTH_V1: usize = 108;
+const PATH_LENGTH_V3: usize = 124;
+// Windows 10 entries end with 4 more bytes after the path
+const TRAILER_V3: usize = 4;
+
+/// A `DestList` entry
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DestListEntry {
+  // Its link stream is named after this number in hex
+  pub entry: u32,
+  pub path: String,
+  // FILETIME
+  pub accessed: u64,
+  // Position among the pinned destinations
+  pub pinned: Option<u32>,
+  // Always 0 before Windows 10
+  pub access_count: u32,
+}
+
+pub fn parse_dest_list(data: &[u8]) -> Result<Vec<DestListEntry>> {
+  let version = u32_at(data, 0, "DestList header")?;
+  let count = u32_at(data, 4, "DestList header")? as usize;
+  let (path_length, trailer) = match version {
+    1 => (PATH_LENGTH_V1, 0),
+    2.. => (PATH_LENGTH_V3, TRAILER_V3),
+    _ => return Err(JumpListError::Malformed("DestList version")),
+  };
+
+  let mut entries = Vec::with_capacity(count.min(data.len() / path_length));
+  let mut offset = HEADER_SIZE;
+  for _ in 0..count {
+    let entry = data
+      .get(offset..)
+      .ok_or(JumpListError::Malformed("DestList entry"))?;
+    let length = u16_at(entry, path_length, "DestList entry")? as usize * 2;
+    let pinned = i32_at(entry, 104, "DestList entry")?;
+
+    entries.push(DestListEntry {
+      entry: u32_at(entry, 88, "DestList entry")?,
+      path: utf16(bytes(entry, path_length + 2, length, "DestList entry")?),
+      accessed: u64_at(entry, 96, "DestList entry")?,
+      pinned: (pinned >= 0).then_some(pinned as u32),
+      access_count: if version == 1 {
+        0
+      } else {
+        u32_at(entry, 112, "DestList entry")?
+      },
+    });
+    offset += path_length + 2 + length + trailer;
+  }
+
+  Ok(entries)
+}
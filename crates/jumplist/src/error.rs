@@ -0,0 +1,34 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum JumpListError {
+  // The destinations file could not be read
+  Io(io::Error),
+  // The data does not follow the format, names what was being read
+  Malformed(&'static str),
+}
+
+impl fmt::Display for JumpListError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      JumpListError::Io(err) => write!(f, "{}", err),
+      JumpListError::Malformed(what) => write!(f, "Malformed {}", what),
+    }
+  }
+}
+
+impl std::error::Error for JumpListError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      JumpListError::Io(err) => Some(err),
+      JumpListError::Malformed(_) => None,
+    }
+  }
+}
+
+impl From<io::Error> for JumpListError {
+  fn from(err: io::Error) -> Self {
+    JumpListError::Io(err)
+  }
+}
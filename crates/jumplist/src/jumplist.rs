@@ -0,0 +1,253 @@
+mod appid;
+mod cfb;
+mod custom;
+mod destlist;
+mod error;
+mod lnk;
+mod read;
+
+use std::cmp::Reverse;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+pub use appid::*;
+pub use cfb::Compound;
+pub use custom::*;
+pub use destlist::*;
+pub use error::JumpListError;
+pub use lnk::*;
+
+pub type Result<T> = std::result::Result<T, JumpListError>;
+
+/// An entry of a jump list
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct JumpItem {
+  pub title: String,
+  // File or program the entry opens
+  pub path: String,
+  pub arguments: String,
+  pub working_dir: String,
+  pub icon_location: String,
+  pub icon_index: i32,
+  pub separator: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoryKind {
+  Pinned,
+  Frequent,
+  Recent,
+  Custom,
+  Tasks,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct JumpCategory {
+  pub kind: CategoryKind,
+  pub name: String,
+  pub items: Vec<JumpItem>,
+}
+
+/// Categories of a jump list in the order the taskbar shows them
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct JumpList {
+  pub categories: Vec<JumpCategory>,
+}
+
+/// A destination Windows recorded for an app
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Destination {
+  pub item: JumpItem,
+  pub accessed: u64,
+  pub pinned: Option<u32>,
+  pub access_count: u32,
+}
+
+impl JumpItem {
+  /// Application name and command line that open the item, documents are
+  /// opened with `app`
+  pub fn command(&self, app: &str) -> (String, String) {
+    let executable = self.path.to_lowercase().ends_with(".exe");
+    let (program, arguments) = if executable || app.is_empty() {
+      (self.path.as_str(), self.arguments.clone())
+    } else {
+      (app, format!("\"{}\"", self.path))
+    };
+
+    let commandline = if arguments.is_empty() {
+      format!("\"{}\"", program)
+    } else {
+      format!("\"{}\" {}", program, arguments)
+    };
+    (program.to_string(), commandline)
+  }
+}
+
+impl From<Link> for JumpItem {
+  fn from(link: Link) -> Self {
+    let title = [&link.title, &link.name]
+      .into_iter()
+      .find(|title| !title.is_empty())
+      .cloned()
+      .unwrap_or_else(|| file_name(&link.target));
+
+    JumpItem {
+      title,
+      path: link.target,
+      arguments: link.arguments,
+      working_dir: link.working_dir,
+      icon_location: link.icon_location,
+      icon_index: link.icon_index,
+      separator: link.separator,
+    }
+  }
+}
+
+fn file_name(path: &str) -> String {
+  let name = path.trim_end_matches(['\\', '/']);
+  name.rsplit(['\\', '/']).next().unwrap_or(name).to_string()
+}
+
+/// Destinations in an `.automaticDestinations-ms` file, in `DestList` order
+pub fn parse_automatic(data: &[u8]) -> Result<Vec<Destination>> {
+  let compound = Compound::parse(data)?;
+  let entries = parse_dest_list(&compound.stream("DestList")?)?;
+
+  Ok(
+    entries
+      .into_iter()
+      .map(|entry| {
+        // The link has the arguments and icon, the path is enough without it
+        let mut link = compound
+          .stream(&format!("{:x}", entry.entry))
+          .and_then(|data| parse_link(&data))
+          .map(|(link, _)| link)
+          .unwrap_or_default();
+        if link.target.is_empty() {
+          link.target = entry.path;
+        }
+
+        Destination {
+          item: JumpItem::from(link),
+          accessed: entry.accessed,
+          pinned: entry.pinned,
+          access_count: entry.access_count,
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Puts together a jump list the way the taskbar does: pinned destinations,
+/// then the app's categories, or recent destinations when it has none, then
+/// tasks. Automatic and custom categories keep up to `max_items` entries.
+pub fn build_jump_list(
+  destinations: Vec<Destination>,
+  custom: Option<Vec<CustomCategory>>,
+  max_items: usize,
+) -> JumpList {
+  let (mut pinned, mut unpinned): (Vec<_>, Vec<_>) = destinations
+    .into_iter()
+    .partition(|destination| destination.pinned.is_some());
+  pinned.sort_by_key(|destination| destination.pinned);
+
+  let known = |kind: KnownCategory, destinations: &mut Vec<Destination>| {
+    match kind {
+      KnownCategory::Recent => {
+        destinations.sort_by_key(|destination| Reverse(destination.accessed))
+      }
+      KnownCategory::Frequent => destinations
+        .sort_by_key(|destination| Reverse((destination.access_count, destination.accessed))),
+    }
+    let (kind, name) = match kind {
+      KnownCategory::Recent => (CategoryKind::Recent, "Recent"),
+      KnownCategory::Frequent => (CategoryKind::Frequent, "Frequent"),
+    };
+
+    JumpCategory {
+      kind,
+      name: name.into(),
+      items: destinations
+        .iter()
+        .take(max_items)
+        .map(|destination| destination.item.clone())
+        .collect(),
+    }
+  };
+
+  let mut categories = vec![JumpCategory {
+    kind: CategoryKind::Pinned,
+    name: "Pinned".into(),
+    items: pinned
+      .into_iter()
+      .map(|destination| destination.item)
+      .collect(),
+  }];
+  let mut tasks = Vec::new();
+
+  match custom {
+    None => categories.push(known(KnownCategory::Recent, &mut unpinned)),
+    Some(custom) => {
+      for category in custom {
+        match category {
+          CustomCategory::Custom { name, links } => categories.push(JumpCategory {
+            kind: CategoryKind::Custom,
+            name,
+            items: links
+              .into_iter()
+              .take(max_items)
+              .map(JumpItem::from)
+              .collect(),
+          }),
+          CustomCategory::Known(kind) => categories.push(known(kind, &mut unpinned)),
+          CustomCategory::Tasks(links) => tasks.extend(links.into_iter().map(JumpItem::from)),
+        }
+      }
+    }
+  }
+
+  categories.push(JumpCategory {
+    kind: CategoryKind::Tasks,
+    name: "Tasks".into(),
+    items: tasks,
+  });
+  categories.retain(|category| !category.items.is_empty());
+
+  JumpList { categories }
+}
+
+// Missing files mean the app has no list of that kind
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>> {
+  match fs::read(path) {
+    Ok(data) => Ok(Some(data)),
+    Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+    Err(err) => Err(err.into()),
+  }
+}
+
+/// Reads the jump list of `app_id` from the destinations files under
+/// `recent_dir`, `%APPDATA%\Microsoft\Windows\Recent` on Windows
+pub fn read_jump_list(recent_dir: &Path, app_id: &str, max_items: usize) -> Result<JumpList> {
+  let hash = app_id_hash(app_id);
+  let automatic = recent_dir
+    .join("AutomaticDestinations")
+    .join(format!("{}.automaticDestinations-ms", hash));
+  let custom = recent_dir
+    .join("CustomDestinations")
+    .join(format!("{}.customDestinations-ms", hash));
+
+  let destinations = match read_optional(&automatic)? {
+    Some(data) => parse_automatic(&data)?,
+    None => Vec::new(),
+  };
+  let custom = read_optional(&custom)?
+    .map(|data| parse_custom(&data))
+    .transpose()?;
+
+  Ok(build_jump_list(destinations, custom, max_items))
+}
@@ -0,0 +1,266 @@
+use crate::read::{ansi, bytes, i32_at, u16_at, u32_at, utf16};
+use crate::JumpListError;
+use crate::Result;
+
+// Shell links ([MS-SHLLINK]), each destination and task is stored as one
+
+const HEADER_SIZE: usize = 0x4C;
+pub(crate) const LINK_CLSID: [u8; 16] = [
+  0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+
+const HAS_TARGET_ID_LIST: u32 = 0x1;
+const HAS_LINK_INFO: u32 = 0x2;
+const HAS_NAME: u32 = 0x4;
+const HAS_RELATIVE_PATH: u32 = 0x8;
+const HAS_WORKING_DIR: u32 = 0x10;
+const HAS_ARGUMENTS: u32 = 0x20;
+const HAS_ICON_LOCATION: u32 = 0x40;
+const IS_UNICODE: u32 = 0x80;
+
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x1;
+
+const ENVIRONMENT_BLOCK: u32 = 0xA000_0001;
+const PROPERTY_STORE_BLOCK: u32 = 0xA000_0009;
+
+// Serialized property storages and the properties jump lists use
+const STORAGE_VERSION: u32 = 0x5350_5331;
+const VT_BOOL: u16 = 0x0B;
+const VT_LPWSTR: u16 = 0x1F;
+// PKEY_Title, {F29F85E0-4FF9-1068-AB91-08002B27B3D9} 2
+const TITLE: ([u8; 16], u32) = (
+  [
+    0xE0, 0x85, 0x9F, 0xF2, 0xF9, 0x4F, 0x68, 0x10, 0xAB, 0x91, 0x08, 0x00, 0x2B, 0x27, 0xB3, 0xD9,
+  ],
+  2,
+);
+// PKEY_AppUserModel_IsDestListSeparator, {9F4C2855-9F79-4B39-A8D0-E1D42DE1D5F3} 6
+const SEPARATOR: ([u8; 16], u32) = (
+  [
+    0x55, 0x28, 0x4C, 0x9F, 0x79, 0x9F, 0x39, 0x4B, 0xA8, 0xD0, 0xE1, 0xD4, 0x2D, 0xE1, 0xD5, 0xF3,
+  ],
+  6,
+);
+
+// Shell item types, `CLASS_TYPE_MASK` picks the class out of the type byte
+const CLASS_TYPE_MASK: u8 = 0x70;
+const VOLUME: u8 = 0x20;
+const FILE_ENTRY: u8 = 0x30;
+const FILE_ENTRY_UNICODE: u8 = 0x04;
+const FILE_ENTRY_EXTENSION: [u8; 4] = [0x04, 0x00, 0xEF, 0xBE];
+
+/// What a shell link points to
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Link {
+  pub target: String,
+  pub name: String,
+  pub arguments: String,
+  pub working_dir: String,
+  pub icon_location: String,
+  pub icon_index: i32,
+  // `System.Title`, tasks are labelled with it
+  pub title: String,
+  // Tasks can be separators between other tasks
+  pub separator: bool,
+}
+
+/// Parses the shell link at the start of `data`, returning it and its length
+pub fn parse_link(data: &[u8]) -> Result<(Link, usize)> {
+  if u32_at(data, 0, "shell link header")? as usize != HEADER_SIZE
+    || bytes(data, 4, 16, "shell link header")? != LINK_CLSID
+  {
+    return Err(JumpListError::Malformed("shell link header"));
+  }
+
+  let flags = u32_at(data, 20, "shell link header")?;
+  let mut link = Link {
+    icon_index: i32_at(data, 56, "shell link header")?,
+    ..Default::default()
+  };
+  let mut offset = HEADER_SIZE;
+
+  let mut id_list_path = String::new();
+  if flags & HAS_TARGET_ID_LIST != 0 {
+    let size = u16_at(data, offset, "shell link id list")? as usize;
+    id_list_path = id_list(bytes(data, offset + 2, size, "shell link id list")?);
+    offset += 2 + size;
+  }
+
+  if flags & HAS_LINK_INFO != 0 {
+    let size = u32_at(data, offset, "shell link info")? as usize;
+    link.target = link_info(bytes(data, offset, size, "shell link info")?)?;
+    offset += size;
+  }
+
+  let unicode = flags & IS_UNICODE != 0;
+  let mut string = |flag: u32| -> Result<String> {
+    if flags & flag == 0 {
+      return Ok(String::new());
+    }
+
+    let count = u16_at(data, offset, "shell link string")? as usize;
+    let size = if unicode { count * 2 } else { count };
+    let text = bytes(data, offset + 2, size, "shell link string")?;
+    offset += 2 + size;
+
+    Ok(if unicode { utf16(text) } else { ansi(text) })
+  };
+  link.name = string(HAS_NAME)?;
+  string(HAS_RELATIVE_PATH)?;
+  link.working_dir = string(HAS_WORKING_DIR)?;
+  link.arguments = string(HAS_ARGUMENTS)?;
+  link.icon_location = string(HAS_ICON_LOCATION)?;
+
+  // Extra data blocks until the terminal block, which is under 4 bytes long
+  loop {
+    let size = u32_at(data, offset, "shell link extra data")? as usize;
+    if size < 4 {
+      offset += 4;
+      break;
+    }
+
+    let block = bytes(data, offset, size, "shell link extra data")?;
+    match u32_at(block, 4, "shell link extra data")? {
+      // Targets under environment variables, e.g. `%windir%\notepad.exe`
+      ENVIRONMENT_BLOCK if link.target.is_empty() => {
+        link.target = utf16(bytes(block, 268, 520, "shell link environment block")?);
+      }
+      PROPERTY_STORE_BLOCK => properties(&block[8..], &mut link)?,
+      _ => {}
+    }
+    offset += size;
+  }
+
+  if link.target.is_empty() {
+    link.target = id_list_path;
+  }
+
+  Ok((link, offset))
+}
+
+fn link_info(info: &[u8]) -> Result<String> {
+  let header_size = u32_at(info, 4, "shell link info")? as usize;
+  let flags = u32_at(info, 8, "shell link info")?;
+  if flags & VOLUME_ID_AND_LOCAL_BASE_PATH == 0 {
+    return Ok(String::new());
+  }
+
+  let text = |offset: usize, unicode: bool| -> Result<String> {
+    let text = bytes(
+      info,
+      offset,
+      info.len().saturating_sub(offset),
+      "shell link info",
+    )?;
+    Ok(if unicode { utf16(text) } else { ansi(text) })
+  };
+
+  // Unicode offsets only exist in the longer header
+  let (base, suffix) = if header_size >= 0x24 {
+    (
+      text(u32_at(info, 28, "shell link info")? as usize, true)?,
+      text(u32_at(info, 32, "shell link info")? as usize, true)?,
+    )
+  } else {
+    (
+      text(u32_at(info, 16, "shell link info")? as usize, false)?,
+      text(u32_at(info, 24, "shell link info")? as usize, false)?,
+    )
+  };
+
+  Ok(format!("{}{}", base, suffix))
+}
+
+// Best effort path out of a list of shell items, for links that only keep
+// the id list. Drive and file entries are joined, anything else is skipped.
+fn id_list(list: &[u8]) -> String {
+  let mut parts = Vec::new();
+  let mut offset = 0;
+
+  while let Ok(size) = u16_at(list, offset, "shell item") {
+    let size = size as usize;
+    let Some(item) = list.get(offset..offset + size).filter(|_| size > 3) else {
+      break;
+    };
+    offset += size;
+
+    match item[2] & CLASS_TYPE_MASK {
+      VOLUME => parts.push(ansi(&item[3..]).trim_end_matches('\\').to_string()),
+      FILE_ENTRY => parts.push(file_entry_name(item)),
+      _ => {}
+    }
+  }
+
+  parts.join("\\")
+}
+
+// The long name lives in the extension block, the primary name can be the
+// 8.3 name
+fn file_entry_name(item: &[u8]) -> String {
+  let primary = item.get(14..).unwrap_or_default();
+  let short = if item[2] & FILE_ENTRY_UNICODE != 0 {
+    utf16(primary)
+  } else {
+    ansi(primary)
+  };
+
+  let long = item
+    .windows(4)
+    .position(|window| window == FILE_ENTRY_EXTENSION)
+    .and_then(|signature| {
+      let block = &item[signature.checked_sub(4)?..];
+      let version = u16_at(block, 2, "shell item extension").ok()?;
+      let name = match version {
+        3..=6 => 20,
+        7 => 38,
+        8 => 42,
+        9.. => 46,
+        _ => return None,
+      };
+      Some(utf16(block.get(name..)?))
+    })
+    .filter(|name| !name.is_empty());
+
+  long.unwrap_or(short)
+}
+
+// Reads the properties jump lists use out of serialized property storages
+fn properties(data: &[u8], link: &mut Link) -> Result<()> {
+  let mut offset = 0;
+
+  loop {
+    let size = u32_at(data, offset, "property storage")? as usize;
+    if size == 0 {
+      return Ok(());
+    }
+    let storage = bytes(data, offset, size, "property storage")?;
+    offset += size;
+
+    if u32_at(storage, 4, "property storage")? != STORAGE_VERSION {
+      return Err(JumpListError::Malformed("property storage version"));
+    }
+    let format = bytes(storage, 8, 16, "property storage")?;
+
+    let mut value_offset = 24;
+    while let Ok(value_size) = u32_at(storage, value_offset, "property value") {
+      let value_size = value_size as usize;
+      if value_size == 0 {
+        break;
+      }
+      let value = bytes(storage, value_offset, value_size, "property value")?;
+      value_offset += value_size;
+
+      // Named properties have no integer id and are not used
+      let id = u32_at(value, 4, "property value")?;
+      let kind = u16_at(value, 9, "property value")?;
+      let key = (format, id);
+
+      if key == (&TITLE.0[..], TITLE.1) && kind == VT_LPWSTR {
+        let count = u32_at(value, 13, "property value")? as usize;
+        link.title = utf16(bytes(value, 17, count * 2, "property value")?);
+      } else if key == (&SEPARATOR.0[..], SEPARATOR.1) && kind == VT_BOOL {
+        link.separator = u16_at(value, 13, "property value")? != 0;
+      }
+    }
+  }
+}
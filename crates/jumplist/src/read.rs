@@ -0,0 +1,57 @@
+use crate::JumpListError;
+use crate::Result;
+
+// Little-endian readers over untrusted data, out of range reads are errors
+// naming `what` was being read
+
+pub fn bytes<'a>(
+  data: &'a [u8],
+  offset: usize,
+  len: usize,
+  what: &'static str,
+) -> Result<&'a [u8]> {
+  offset
+    .checked_add(len)
+    .and_then(|end| data.get(offset..end))
+    .ok_or(JumpListError::Malformed(what))
+}
+
+pub fn u16_at(data: &[u8], offset: usize, what: &'static str) -> Result<u16> {
+  let bytes = bytes(data, offset, 2, what)?;
+  Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+pub fn u32_at(data: &[u8], offset: usize, what: &'static str) -> Result<u32> {
+  let bytes = bytes(data, offset, 4, what)?;
+  Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+pub fn i32_at(data: &[u8], offset: usize, what: &'static str) -> Result<i32> {
+  u32_at(data, offset, what).map(|value| value as i32)
+}
+
+pub fn u64_at(data: &[u8], offset: usize, what: &'static str) -> Result<u64> {
+  let low = u32_at(data, offset, what)? as u64;
+  let high = u32_at(data, offset + 4, what)? as u64;
+  Ok(high << 32 | low)
+}
+
+/// UTF-16LE text up to the first NUL or the end of `data`
+pub fn utf16(data: &[u8]) -> String {
+  let units = data
+    .chunks_exact(2)
+    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+    .take_while(|unit| *unit != 0)
+    .collect::<Vec<_>>();
+  String::from_utf16_lossy(&units)
+}
+
+/// Single-byte text up to the first NUL, read as Latin-1 since the code page
+/// it was written in is unknown
+pub fn ansi(data: &[u8]) -> String {
+  data
+    .iter()
+    .take_while(|byte| **byte != 0)
+    .map(|byte| *byte as char)
+    .collect()
+}
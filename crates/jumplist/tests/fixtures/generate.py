@@ -0,0 +1,332 @@
+"""Writes the jump list fixtures next to this file.
+
+The files are built by hand from [MS-CFB] and [MS-SHLLINK] with only the
+fields the parser reads filled in, run `python3 generate.py` after changing
+them.
+"""
+
+import os
+import struct
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+LINK_CLSID = bytes.fromhex("0114020000000000c000000000000046")
+# PKEY_Title and PKEY_AppUserModel_IsDestListSeparator format ids
+TITLE_FORMAT = bytes.fromhex("e0859ff2f94f6810ab9108002b27b3d9")
+SEPARATOR_FORMAT = bytes.fromhex("55284c9f799f394ba8d0e1d42de1d5f3")
+CATEGORY_FOOTER = 0xBABFFBAB
+END_OF_CHAIN = 0xFFFFFFFE
+FAT_SECTOR = 0xFFFFFFFD
+NO_STREAM = 0xFFFFFFFF
+
+
+def u16(value):
+    return struct.pack("<H", value)
+
+
+def u32(value):
+    return struct.pack("<I", value & 0xFFFFFFFF)
+
+
+def u64(value):
+    return struct.pack("<Q", value)
+
+
+def utf16(text):
+    return text.encode("utf-16le")
+
+
+def counted(text):
+    return u16(len(text)) + utf16(text)
+
+
+def app_id_hash(app_id):
+    crc = (1 << 64) - 1
+    for byte in utf16(app_id.upper()):
+        crc ^= byte
+        for _ in range(8):
+            crc = (crc >> 1) ^ 0x92C64265D32139A4 if crc & 1 else crc >> 1
+    return "%016x" % crc
+
+
+# Shell links
+
+
+def property_storage(format_id, properties):
+    values = b""
+    for id, value in properties:
+        value = u32(id) + b"\0" + value
+        value += b"\0" * (-(len(value) + 4) % 4)
+        values += u32(len(value) + 4) + value
+    storage = u32(0x53505331) + format_id + values + u32(0)
+    return u32(len(storage) + 4) + storage
+
+
+def property_block(title=None, separator=False):
+    storages = b""
+    if title is not None:
+        text = utf16(title) + b"\0\0"
+        value = u16(0x1F) + u16(0) + u32(len(title) + 1) + text
+        storages += property_storage(TITLE_FORMAT, [(2, value)])
+    if separator:
+        value = u16(0x0B) + u16(0) + u16(0xFFFF) + u16(0)
+        storages += property_storage(SEPARATOR_FORMAT, [(6, value)])
+    storages += u32(0)
+    return u32(len(storages) + 8) + u32(0xA0000009) + storages
+
+
+def link_info(path):
+    base = path.encode("latin1") + b"\0"
+    header_size = 0x1C
+    # Volume id at the header, the parser only reads the base path
+    fields = [0, header_size, 1, header_size, header_size, 0, header_size + len(base)]
+    info = b"".join(u32(field) for field in fields) + base + b"\0"
+    return u32(len(info)) + info[4:]
+
+
+def file_entry(short, long):
+    item = b"\x32\0" + u32(0) + u32(0) + u16(0x10) + short.encode("latin1") + b"\0"
+    item += b"\0" * (len(item) % 2)
+    extension = (
+        u16(0) + u16(9) + u32(0xBEEF0004) + u32(0) + u32(0) + u16(0x2E) + u16(0)
+        + u64(0) + u64(0) + u16(0) + u32(0) + u32(0) + utf16(long) + b"\0\0" + u16(0)
+    )
+    item += u16(len(extension)) + extension[2:]
+    return u16(len(item) + 2) + item
+
+
+def id_list(drive, *names):
+    root = b"\x1f\x50" + b"\0" * 16
+    volume = b"\x2f" + drive.encode("latin1") + b"\0" * 19
+    items = u16(len(root) + 2) + root + u16(len(volume) + 2) + volume
+    for short, long in names:
+        items += file_entry(short, long)
+    return items + u16(0)
+
+
+def link(
+    target=None,
+    ids=None,
+    environment=None,
+    name=None,
+    working_dir=None,
+    arguments=None,
+    icon=None,
+    icon_index=0,
+    title=None,
+    separator=False,
+):
+    flags = 0x80
+    body = b""
+    if ids is not None:
+        flags |= 0x1
+        body += u16(len(ids)) + ids
+    if target is not None:
+        flags |= 0x2
+        body += link_info(target)
+    for flag, text in [(0x4, name), (0x10, working_dir), (0x20, arguments), (0x40, icon)]:
+        if text is not None:
+            flags |= flag
+            body += counted(text)
+
+    if environment is not None:
+        ansi = environment.encode("latin1").ljust(260, b"\0")
+        body += u32(0x314) + u32(0xA0000001) + ansi + utf16(environment).ljust(520, b"\0")
+    if title is not None or separator:
+        body += property_block(title, separator)
+    body += u32(0)
+
+    header = (
+        u32(0x4C) + LINK_CLSID + u32(flags) + u32(0) + b"\0" * 24 + u32(0)
+        + struct.pack("<i", icon_index) + u32(1) + u16(0) + b"\0" * 10
+    )
+    assert len(header) == 0x4C
+    return header + body
+
+
+# Compound files, version 3 with every stream in the mini stream
+
+SECTOR = 512
+MINI_SECTOR = 64
+
+
+def chain(start, count):
+    return [start + i + 1 if i < count - 1 else END_OF_CHAIN for i in range(count)]
+
+
+def sectors(data):
+    return data + b"\0" * (-len(data) % SECTOR)
+
+
+def directory_entry(name, kind, start, size, child=NO_STREAM, right=NO_STREAM):
+    name = utf16(name) + b"\0\0"
+    entry = (
+        name.ljust(64, b"\0") + u16(len(name)) + bytes([kind, 1]) + u32(NO_STREAM)
+        + u32(right) + u32(child) + b"\0" * 36 + u32(start) + u64(size)
+    )
+    assert len(entry) == 128
+    return entry
+
+
+def compound(streams):
+    mini_stream = b""
+    mini_fat = []
+    entries = []
+    for name, data in streams:
+        start = len(mini_stream) // MINI_SECTOR
+        count = -(-len(data) // MINI_SECTOR)
+        entries.append((name, start, len(data)))
+        mini_fat += chain(start, count)
+        mini_stream += data + b"\0" * (-len(data) % MINI_SECTOR)
+
+    mini_fat = sectors(b"".join(u32(id) for id in mini_fat))
+    directory = directory_entry("Root Entry", 5, 0, len(mini_stream), child=1)
+    for index, (name, start, size) in enumerate(entries):
+        right = index + 2 if index + 1 < len(entries) else NO_STREAM
+        directory += directory_entry(name, 2, start, size, right=right)
+    directory = sectors(directory)
+    mini_stream = sectors(mini_stream)
+
+    # Sector 0 is the FAT, then the mini FAT, the directory and the mini stream
+    mini_fat_start = 1
+    directory_start = mini_fat_start + len(mini_fat) // SECTOR
+    mini_stream_start = directory_start + len(directory) // SECTOR
+    fat = (
+        [FAT_SECTOR]
+        + chain(mini_fat_start, len(mini_fat) // SECTOR)
+        + chain(directory_start, len(directory) // SECTOR)
+        + chain(mini_stream_start, len(mini_stream) // SECTOR)
+    )
+    assert len(fat) <= SECTOR // 4
+    fat = b"".join(u32(id) for id in fat).ljust(SECTOR, b"\xff")
+
+    # The root's mini stream starts right after the directory
+    directory = directory[:116] + u32(mini_stream_start) + directory[120:]
+
+    header = (
+        bytes.fromhex("d0cf11e0a1b11ae1") + b"\0" * 16 + u16(0x3E) + u16(3) + u16(0xFFFE)
+        + u16(9) + u16(6) + b"\0" * 6 + u32(0) + u32(1) + u32(directory_start) + u32(0)
+        + u32(4096) + u32(mini_fat_start) + u32(len(mini_fat) // SECTOR)
+        + u32(END_OF_CHAIN) + u32(0) + u32(0) + b"\xff" * (108 * 4)
+    )
+    assert len(header) == SECTOR
+    return header + fat + mini_fat + directory + mini_stream
+
+
+# DestList streams, entries are (number, path, pin, accessed, access count)
+
+
+def dest_list(version, entries):
+    pinned = sum(1 for entry in entries if entry[2] >= 0)
+    data = u32(version) + u32(len(entries)) + u32(pinned) + u32(0) + u64(len(entries)) + u64(1)
+    for number, path, pin, accessed, count in entries:
+        data += b"\0" * 72 + b"host".ljust(16, b"\0") + u32(number) + u32(0) + u64(accessed)
+        data += struct.pack("<i", pin)
+        if version > 1:
+            data += u32(NO_STREAM) + u32(count) + u64(0)
+        data += counted(path)
+        if version > 1:
+            data += u32(0)
+    return data
+
+
+def automatic(version, entries, links):
+    streams = [("DestList", dest_list(version, entries))]
+    streams += [("%x" % number, data) for number, data in links]
+    return compound(streams)
+
+
+# `.customDestinations-ms` categories
+
+
+def custom_category(name, links):
+    return u32(0) + counted(name) + u32(len(links)) + b"".join(LINK_CLSID + l for l in links)
+
+
+def known_category(id):
+    return u32(1) + u32(id)
+
+
+def tasks(links):
+    return u32(2) + u32(len(links)) + b"".join(LINK_CLSID + l for l in links)
+
+
+def custom(categories):
+    data = u32(2) + u32(len(categories)) + u32(0)
+    for category in categories:
+        data += category + u32(CATEGORY_FOOTER)
+    return data
+
+
+def write(path, data):
+    path = os.path.join(HERE, path)
+    os.makedirs(os.path.dirname(path), exist_ok=True)
+    with open(path, "wb") as file:
+        file.write(data)
+
+
+APP_ID = "Contoso.Editor"
+
+DOCUMENT = link(
+    target="C:\\Users\\Public\\Documents\\report.txt",
+    working_dir="C:\\Users\\Public\\Documents",
+    icon="C:\\Windows\\System32\\imageres.dll",
+    icon_index=-102,
+)
+TASK = link(target="C:\\Program Files\\Contoso\\editor.exe", arguments="--new-window", title="New window")
+SEPARATOR_TASK = link(separator=True)
+ID_LIST = link(
+    ids=id_list("C:\\", ("PROGRA~1", "Program Files"), ("Contoso", "Contoso"), ("editor.exe", "editor.exe")),
+    arguments="--private",
+    title="New private window",
+)
+ENVIRONMENT = link(environment="%windir%\\notepad.exe", name="Notepad")
+
+write("links/document.lnk", DOCUMENT)
+write("links/task.lnk", TASK)
+write("links/separator.lnk", SEPARATOR_TASK)
+write("links/id_list.lnk", ID_LIST)
+write("links/environment.lnk", ENVIRONMENT)
+
+# Windows 10 list of the app, one entry without a link stream and one whose
+# stream is not a link fall back to the DestList path
+recent = os.path.join("Recent", "AutomaticDestinations", app_id_hash(APP_ID))
+write(
+    recent + ".automaticDestinations-ms",
+    automatic(
+        4,
+        [
+            (1, "C:\\Users\\Public\\Documents\\old.txt", -1, 100, 5),
+            (2, "C:\\Users\\Public\\Documents\\new.txt", -1, 300, 1),
+            (3, "C:\\Users\\Public\\Documents\\report.txt", 1, 50, 2),
+            (4, "C:\\Users\\Public\\Documents\\notes.txt", 0, 10, 1),
+            (0xA, "C:\\Users\\Public\\Documents\\often.txt", -1, 200, 9),
+            (0xB, "C:\\Users\\Public\\Documents\\broken.txt", -1, 150, 3),
+        ],
+        [
+            (1, link(target="C:\\Users\\Public\\Documents\\old.txt")),
+            (2, link(target="C:\\Users\\Public\\Documents\\new.txt", arguments="--flag")),
+            (3, DOCUMENT),
+            (0xB, b"not a link"),
+        ],
+    ),
+)
+
+write(
+    os.path.join("Recent", "CustomDestinations", app_id_hash(APP_ID)) + ".customDestinations-ms",
+    custom(
+        [
+            custom_category("Projects", [link(target="C:\\Projects\\a.txt"), link(target="C:\\Projects\\b.txt")]),
+            known_category(1),
+            tasks([TASK, SEPARATOR_TASK, ID_LIST, ENVIRONMENT]),
+        ]
+    ),
+)
+
+# The same destinations in the Windows 7 and Windows 10 before 1607 layouts
+ENTRIES = [
+    (1, "C:\\Users\\Public\\Documents\\old.txt", -1, 100, 5),
+    (2, "C:\\Users\\Public\\Documents\\pinned.txt", 0, 300, 1),
+]
+write("windows7.automaticDestinations-ms", automatic(1, ENTRIES, []))
+write("windows10.automaticDestinations-ms", automatic(3, ENTRIES, []))
@@ -0,0 +1,451 @@
+use std::fs;
+use std::path::PathBuf;
+
+use jumplist::*;
+
+// Files written by `tests/fixtures/generate.py`
+fn fixtures() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn fixture(path: &str) -> Vec<u8> {
+  fs::read(fixtures().join(path)).unwrap()
+}
+
+const APP_ID: &str = "Contoso.Editor";
+
+fn automatic() -> Vec<u8> {
+  fixture(&format!(
+    "Recent/AutomaticDestinations/{}.automaticDestinations-ms",
+    app_id_hash(APP_ID)
+  ))
+}
+
+fn custom() -> Vec<u8> {
+  fixture(&format!(
+    "Recent/CustomDestinations/{}.customDestinations-ms",
+    app_id_hash(APP_ID)
+  ))
+}
+
+fn link(name: &str) -> Link {
+  let data = fixture(&format!("links/{}.lnk", name));
+  let (link, length) = parse_link(&data).unwrap();
+  assert_eq!(length, data.len());
+  link
+}
+
+fn dest_list(name: &str) -> Vec<DestListEntry> {
+  let data = fixture(name);
+  let compound = Compound::parse(&data).unwrap();
+  parse_dest_list(&compound.stream("DestList").unwrap()).unwrap()
+}
+
+fn titles(category: &JumpCategory) -> Vec<&str> {
+  category
+    .items
+    .iter()
+    .map(|item| item.title.as_str())
+    .collect()
+}
+
+fn malformed<T>(result: Result<T>) -> bool {
+  matches!(result, Err(JumpListError::Malformed(_)))
+}
+
+#[test]
+fn app_id_hashes() {
+  let notepad = format!("{}\\notepad.exe", SYSTEM);
+  let notepad_x86 = format!("{}\\notepad.exe", SYSTEM_X86);
+  let explorer = format!("{}\\explorer.exe", WINDOWS);
+
+  assert_eq!(app_id_hash(&notepad), "9b9cdc69c1c24e2b");
+  assert_eq!(app_id_hash(&notepad_x86), "918e0ecb43d17e23");
+  assert_eq!(app_id_hash(&explorer), "1b4dd67f29cb1962");
+  assert_eq!(
+    app_id_hash("Microsoft.Windows.Explorer"),
+    "f01b4d95cf55d32a"
+  );
+  assert_eq!(app_id_hash("Chrome"), "5d696d521de238c3");
+}
+
+#[test]
+fn app_id_hash_ignores_case() {
+  let notepad = format!("{}\\NOTEPAD.EXE", SYSTEM.to_lowercase());
+
+  assert_eq!(app_id_hash(&notepad), "9b9cdc69c1c24e2b");
+}
+
+#[test]
+fn default_app_id_uses_the_longest_folder() {
+  let folders = [
+    ("C:\\Windows\\".to_string(), WINDOWS),
+    ("C:\\Windows\\System32".to_string(), SYSTEM),
+    ("C:\\Program Files".to_string(), PROGRAM_FILES_X64),
+  ];
+
+  assert_eq!(
+    default_app_id("C:\\WINDOWS\\system32\\notepad.exe", &folders),
+    format!("{}\\notepad.exe", SYSTEM)
+  );
+  assert_eq!(
+    default_app_id("C:\\Windows\\explorer.exe", &folders),
+    format!("{}\\explorer.exe", WINDOWS)
+  );
+  // Only whole folder names match
+  assert_eq!(
+    default_app_id("C:\\Program Files (x86)\\app.exe", &folders),
+    "C:\\Program Files (x86)\\app.exe"
+  );
+}
+
+#[test]
+fn dest_list_v1() {
+  let entries = dest_list("windows7.automaticDestinations-ms");
+
+  assert_eq!(
+    entries,
+    vec![
+      DestListEntry {
+        entry: 1,
+        path: "C:\\Users\\Public\\Documents\\old.txt".into(),
+        accessed: 100,
+        pinned: None,
+        access_count: 0,
+      },
+      DestListEntry {
+        entry: 2,
+        path: "C:\\Users\\Public\\Documents\\pinned.txt".into(),
+        accessed: 300,
+        pinned: Some(0),
+        access_count: 0,
+      },
+    ]
+  );
+}
+
+#[test]
+fn dest_list_v3() {
+  let entries = dest_list("windows10.automaticDestinations-ms");
+
+  assert_eq!(
+    entries,
+    vec![
+      DestListEntry {
+        entry: 1,
+        path: "C:\\Users\\Public\\Documents\\old.txt".into(),
+        accessed: 100,
+        pinned: None,
+        access_count: 5,
+      },
+      DestListEntry {
+        entry: 2,
+        path: "C:\\Users\\Public\\Documents\\pinned.txt".into(),
+        accessed: 300,
+        pinned: Some(0),
+        access_count: 1,
+      },
+    ]
+  );
+}
+
+#[test]
+fn dest_list_v4() {
+  let data = automatic();
+  let compound = Compound::parse(&data).unwrap();
+  let entries = parse_dest_list(&compound.stream("DestList").unwrap()).unwrap();
+
+  assert_eq!(
+    entries
+      .iter()
+      .map(|entry| (entry.entry, entry.pinned, entry.access_count))
+      .collect::<Vec<_>>(),
+    vec![
+      (1, None, 5),
+      (2, None, 1),
+      (3, Some(1), 2),
+      (4, Some(0), 1),
+      (0xA, None, 9),
+      (0xB, None, 3),
+    ]
+  );
+}
+
+#[test]
+fn dest_list_version_0_is_malformed() {
+  let mut data = vec![0; 32];
+  data[4] = 1;
+
+  assert!(malformed(parse_dest_list(&data)));
+}
+
+#[test]
+fn compound_streams() {
+  let data = automatic();
+  let compound = Compound::parse(&data).unwrap();
+
+  assert_eq!(
+    compound.streams().collect::<Vec<_>>(),
+    vec!["DestList", "1", "2", "3", "b"]
+  );
+  assert_eq!(compound.stream("b").unwrap(), b"not a link");
+  assert!(malformed(compound.stream("a")));
+}
+
+#[test]
+fn link_with_link_info() {
+  assert_eq!(
+    link("document"),
+    Link {
+      target: "C:\\Users\\Public\\Documents\\report.txt".into(),
+      working_dir: "C:\\Users\\Public\\Documents".into(),
+      icon_location: "C:\\Windows\\System32\\imageres.dll".into(),
+      icon_index: -102,
+      ..Default::default()
+    }
+  );
+}
+
+#[test]
+fn link_with_title() {
+  let link = link("task");
+
+  assert_eq!(link.target, "C:\\Program Files\\Contoso\\editor.exe");
+  assert_eq!(link.arguments, "--new-window");
+  assert_eq!(link.title, "New window");
+  assert!(!link.separator);
+}
+
+#[test]
+fn separator_link() {
+  let link = link("separator");
+
+  assert!(link.separator);
+  assert_eq!(link.target, "");
+}
+
+#[test]
+fn link_with_only_an_id_list() {
+  let link = link("id_list");
+
+  assert_eq!(link.target, "C:\\Program Files\\Contoso\\editor.exe");
+  assert_eq!(link.arguments, "--private");
+  assert_eq!(link.title, "New private window");
+}
+
+#[test]
+fn link_under_an_environment_variable() {
+  let link = link("environment");
+
+  assert_eq!(link.target, "%windir%\\notepad.exe");
+  assert_eq!(JumpItem::from(link).title, "Notepad");
+}
+
+#[test]
+fn items_are_titled_after_their_file_without_a_title() {
+  let item = JumpItem::from(link("document"));
+
+  assert_eq!(item.title, "report.txt");
+  assert_eq!(item.path, "C:\\Users\\Public\\Documents\\report.txt");
+}
+
+#[test]
+fn custom_categories() {
+  let categories = parse_custom(&custom()).unwrap();
+
+  assert_eq!(categories.len(), 3);
+  let CustomCategory::Custom { name, links } = &categories[0] else {
+    panic!("expected a custom category, got {:?}", categories[0]);
+  };
+  assert_eq!(name, "Projects");
+  assert_eq!(
+    links
+      .iter()
+      .map(|link| link.target.as_str())
+      .collect::<Vec<_>>(),
+    vec!["C:\\Projects\\a.txt", "C:\\Projects\\b.txt"]
+  );
+  assert_eq!(
+    categories[1],
+    CustomCategory::Known(KnownCategory::Frequent)
+  );
+  assert_eq!(
+    categories[2],
+    CustomCategory::Tasks(vec![
+      link("task"),
+      link("separator"),
+      link("id_list"),
+      link("environment"),
+    ])
+  );
+}
+
+#[test]
+fn automatic_destinations_fall_back_to_the_dest_list_path() {
+  let destinations = parse_automatic(&automatic()).unwrap();
+
+  assert_eq!(
+    destinations
+      .iter()
+      .map(|destination| destination.item.path.as_str())
+      .collect::<Vec<_>>(),
+    vec![
+      "C:\\Users\\Public\\Documents\\old.txt",
+      "C:\\Users\\Public\\Documents\\new.txt",
+      "C:\\Users\\Public\\Documents\\report.txt",
+      // No link stream
+      "C:\\Users\\Public\\Documents\\notes.txt",
+      "C:\\Users\\Public\\Documents\\often.txt",
+      // A stream that is not a link
+      "C:\\Users\\Public\\Documents\\broken.txt",
+    ]
+  );
+  assert_eq!(destinations[1].item.arguments, "--flag");
+  assert_eq!(destinations[2].item.icon_index, -102);
+}
+
+#[test]
+fn jump_list_with_custom_categories() {
+  let jump_list = read_jump_list(&fixtures().join("Recent"), APP_ID, 10).unwrap();
+
+  assert_eq!(
+    jump_list
+      .categories
+      .iter()
+      .map(|category| (category.kind, category.name.as_str()))
+      .collect::<Vec<_>>(),
+    vec![
+      (CategoryKind::Pinned, "Pinned"),
+      (CategoryKind::Custom, "Projects"),
+      (CategoryKind::Frequent, "Frequent"),
+      (CategoryKind::Tasks, "Tasks"),
+    ]
+  );
+  // Pinned by position, frequent by access count
+  assert_eq!(
+    titles(&jump_list.categories[0]),
+    vec!["notes.txt", "report.txt"]
+  );
+  assert_eq!(titles(&jump_list.categories[1]), vec!["a.txt", "b.txt"]);
+  assert_eq!(
+    titles(&jump_list.categories[2]),
+    vec!["often.txt", "old.txt", "broken.txt", "new.txt"]
+  );
+  assert_eq!(
+    titles(&jump_list.categories[3]),
+    vec!["New window", "", "New private window", "Notepad"]
+  );
+  assert!(jump_list.categories[3].items[1].separator);
+}
+
+#[test]
+fn jump_list_without_custom_categories_shows_recent() {
+  let destinations = parse_automatic(&automatic()).unwrap();
+  let jump_list = build_jump_list(destinations, None, 2);
+
+  assert_eq!(jump_list.categories.len(), 2);
+  assert_eq!(jump_list.categories[1].kind, CategoryKind::Recent);
+  // Most recently accessed first, cut to `max_items`
+  assert_eq!(
+    titles(&jump_list.categories[1]),
+    vec!["new.txt", "often.txt"]
+  );
+}
+
+#[test]
+fn jump_list_cuts_custom_categories() {
+  let jump_list = build_jump_list(Vec::new(), Some(parse_custom(&custom()).unwrap()), 1);
+
+  assert_eq!(
+    jump_list
+      .categories
+      .iter()
+      .map(|category| (category.kind, category.items.len()))
+      .collect::<Vec<_>>(),
+    // Frequent has nothing and is dropped, tasks are never cut
+    vec![(CategoryKind::Custom, 1), (CategoryKind::Tasks, 4)]
+  );
+}
+
+#[test]
+fn missing_files_make_an_empty_jump_list() {
+  let jump_list = read_jump_list(&fixtures().join("Recent"), "Contoso.Other", 10).unwrap();
+
+  assert_eq!(jump_list, JumpList::default());
+}
+
+#[test]
+fn truncated_files_are_malformed() {
+  let files = [
+    automatic(),
+    custom(),
+    fixture("windows7.automaticDestinations-ms"),
+    fixture("links/document.lnk"),
+    fixture("links/id_list.lnk"),
+    fixture("links/environment.lnk"),
+  ];
+
+  for (index, data) in files.iter().enumerate() {
+    for length in 0..data.len() {
+      let data = &data[..length];
+      let parsed = match index {
+        0 | 2 => parse_automatic(data).map(|_| ()),
+        1 => parse_custom(data).map(|_| ()),
+        _ => parse_link(data).map(|_| ()),
+      };
+      assert!(malformed(parsed), "file {} cut at {}", index, length);
+    }
+  }
+}
+
+#[test]
+fn garbage_does_not_panic() {
+  // Deterministic noise, every byte of the fixtures replaced in turn
+  let mut state = 0x2545_F491_4F6C_DD1Du64;
+  let mut noise = || {
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    state as u8
+  };
+
+  for data in [automatic(), custom(), fixture("links/task.lnk")] {
+    for index in 0..data.len() {
+      let mut data = data.clone();
+      data[index] = noise();
+      let _ = parse_automatic(&data);
+      let _ = parse_custom(&data);
+      let _ = parse_link(&data);
+    }
+  }
+
+  let noise = (0..4096).map(|_| noise()).collect::<Vec<_>>();
+  assert!(malformed(parse_automatic(&noise)));
+  assert!(malformed(parse_link(&noise)));
+}
+
+#[test]
+fn fat_larger_than_the_file_is_malformed() {
+  let mut data = automatic();
+  data[44..48].copy_from_slice(&u32::MAX.to_le_bytes());
+
+  assert!(matches!(
+    Compound::parse(&data),
+    Err(JumpListError::Malformed("compound file FAT size"))
+  ));
+}
+
+#[test]
+fn difat_past_the_end_is_malformed() {
+  let mut data = automatic();
+  // More FAT sectors than the header lists, continued in a sector that
+  // doesn't exist
+  data.resize(512 * 120, 0);
+  data[44..48].copy_from_slice(&111u32.to_le_bytes());
+  data[68..72].copy_from_slice(&5000u32.to_le_bytes());
+
+  assert!(matches!(
+    Compound::parse(&data),
+    Err(JumpListError::Malformed("compound file sector"))
+  ));
+}
@@ -0,0 +1,85 @@
+// Points for each matched character, and bonuses for matches that read as
+// intended: right after the previous one, at the start of a word, or at the
+// very start of the text
+const MATCH: i32 = 16;
+const CONSECUTIVE: i32 = 16;
+const WORD_START: i32 = 24;
+const FIRST: i32 = 32;
+// Per skipped character, leading ones are capped so long names aren't buried
+const GAP: i32 = 1;
+const MAX_LEADING_GAP: i32 = 8;
+
+fn is_word_start(text: &[char], index: usize) -> bool {
+  let Some(previous) = index.checked_sub(1).map(|previous| text[previous]) else {
+    return true;
+  };
+  let current = text[index];
+
+  !previous.is_alphanumeric()
+    || (previous.is_lowercase() && current.is_uppercase())
+    || (!previous.is_numeric() && current.is_numeric())
+}
+
+/// Scores `text` for `query` typed as a case-insensitive subsequence, higher
+/// is better and `None` means some character of `query` is missing
+///
+/// An empty query matches everything with a score of 0.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+  let query = query
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .flat_map(char::to_lowercase)
+    .collect::<Vec<_>>();
+  if query.is_empty() {
+    return Some(0);
+  }
+
+  let original = text.chars().collect::<Vec<_>>();
+  let lowercase = original
+    .iter()
+    .map(|c| c.to_lowercase().next().unwrap_or(*c))
+    .collect::<Vec<_>>();
+  if query.len() > lowercase.len() {
+    return None;
+  }
+
+  let bonus = |index: usize| {
+    MATCH
+      + if index == 0 { FIRST } else { 0 }
+      + if is_word_start(&original, index) {
+        WORD_START
+      } else {
+        0
+      }
+  };
+
+  // `best[j]` is the best score with the current query character matched at
+  // `j`, every alignment is tried so an early weak match can't shadow a
+  // better one later on
+  let mut best = lowercase
+    .iter()
+    .enumerate()
+    .map(|(j, c)| (*c == query[0]).then(|| bonus(j) - (j as i32 * GAP).min(MAX_LEADING_GAP)))
+    .collect::<Vec<_>>();
+
+  for character in &query[1..] {
+    let mut next = vec![None; lowercase.len()];
+    for j in 0..lowercase.len() {
+      if lowercase[j] != *character {
+        continue;
+      }
+
+      next[j] = (0..j)
+        .filter_map(|k| {
+          let score = best[k]?;
+          let gap = (j - k - 1) as i32;
+          Some(score + if gap == 0 { CONSECUTIVE } else { -gap * GAP })
+        })
+        .max()
+        .map(|score| score + bonus(j));
+    }
+    best = next;
+  }
+
+  best.into_iter().flatten().max()
+}
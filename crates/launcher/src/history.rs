@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+// A launch counts half as much after a week
+const HALF_LIFE_SECS: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+/// How often and how recently an app was launched
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+  pub count: u32,
+  // Seconds since the Unix epoch
+  pub last: u64,
+}
+
+/// Launches per app id, ranks apps the user opens often or recently first
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct LaunchHistory {
+  pub apps: HashMap<String, Usage>,
+}
+
+impl LaunchHistory {
+  /// Reads the history at `path`, a missing or unreadable file is an empty
+  /// history
+  pub fn load(path: &Path) -> Self {
+    fs::read_to_string(path)
+      .ok()
+      .and_then(|json| serde_json::from_str(&json).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string(self)?)
+  }
+
+  pub fn record(&mut self, id: &str, now: u64) {
+    let usage = self.apps.entry(id.to_string()).or_default();
+    usage.count = usage.count.saturating_add(1);
+    usage.last = now;
+  }
+
+  /// Frequency weighted by recency, 0 for apps never launched
+  ///
+  /// Grows with the log of the count so a handful of launches matter but
+  /// hundreds don't drown out what is typed.
+  pub fn frecency(&self, id: &str, now: u64) -> f64 {
+    self.apps.get(id).map_or(0.0, |usage| {
+      let age = now.saturating_sub(usage.last) as f64;
+      (1.0 + usage.count as f64).ln() * 0.5f64.powf(age / HALF_LIFE_SECS)
+    })
+  }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use jumplist::parse_link;
+use serde::Deserialize;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+// Shortcuts to anything but these, like readmes and help files, aren't apps
+const PROGRAM_EXTENSIONS: [&str; 6] = ["exe", "com", "bat", "cmd", "msc", "cpl"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AppKind {
+  // A Start Menu `.lnk`
+  Shortcut,
+  // An app from an MSIX/AppX package
+  Packaged,
+  // An executable in `PATH`
+  Executable,
+}
+
+/// An app the launcher can find
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AppEntry {
+  // Key of the launch history, stable across rescans
+  pub id: String,
+  pub name: String,
+  pub kind: AppKind,
+  // Opened to launch the app: the shortcut, `shell:AppsFolder\<AUMID>` or
+  // the executable
+  pub path: String,
+  // Program the app runs when known, duplicates of it are merged
+  pub target: String,
+  // File the icon is read from
+  pub icon: String,
+}
+
+fn extension(path: &str) -> Option<String> {
+  Path::new(path)
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .map(|extension| extension.to_lowercase())
+}
+
+fn file_stem(path: &Path) -> String {
+  path
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default()
+}
+
+impl AppEntry {
+  /// An app from the shortcut at `path`, whose contents are `data`
+  pub fn shortcut(path: &Path, data: &[u8]) -> Self {
+    let link = parse_link(data).map(|(link, _)| link).unwrap_or_default();
+    let path_text = path.to_string_lossy().to_string();
+    // `path,index`, the index is dropped since icons are read per file
+    let icon_location = link
+      .icon_location
+      .split(',')
+      .next()
+      .unwrap_or_default()
+      .to_string();
+
+    AppEntry {
+      id: format!("shortcut:{}", path_text.to_lowercase()),
+      name: file_stem(path),
+      kind: AppKind::Shortcut,
+      icon: if icon_location.is_empty() {
+        link.target.clone()
+      } else {
+        icon_location
+      },
+      path: path_text,
+      target: link.target,
+    }
+  }
+
+  pub fn packaged(app_user_model_id: &str, name: &str) -> Self {
+    AppEntry {
+      id: format!("packaged:{}", app_user_model_id),
+      name: name.to_string(),
+      kind: AppKind::Packaged,
+      path: format!("shell:AppsFolder\\{}", app_user_model_id),
+      target: String::new(),
+      icon: String::new(),
+    }
+  }
+
+  pub fn executable(path: &Path) -> Self {
+    let path_text = path.to_string_lossy().to_string();
+
+    AppEntry {
+      id: format!("executable:{}", path_text.to_lowercase()),
+      name: file_stem(path),
+      kind: AppKind::Executable,
+      path: path_text.clone(),
+      target: path_text.clone(),
+      icon: path_text,
+    }
+  }
+}
+
+/// Apps from the `.lnk` files under `dirs`, uninstallers and shortcuts to
+/// documents are left out
+pub fn scan_shortcuts(dirs: &[PathBuf]) -> Vec<AppEntry> {
+  dirs
+    .iter()
+    .flat_map(|dir| WalkDir::new(dir).into_iter().filter_map(|entry| entry.ok()))
+    .filter(|entry| {
+      entry.file_type().is_file()
+        && extension(&entry.path().to_string_lossy()).as_deref() == Some("lnk")
+    })
+    .filter_map(|entry| {
+      let data = fs::read(entry.path()).ok()?;
+      Some(AppEntry::shortcut(entry.path(), &data))
+    })
+    .filter(|app| {
+      let document = extension(&app.target)
+        .is_some_and(|extension| !PROGRAM_EXTENSIONS.contains(&extension.as_str()));
+      !document && !app.name.to_lowercase().contains("uninstall")
+    })
+    .collect()
+}
+
+/// Executables directly inside `dirs` with one of `extensions`, e.g. the
+/// directories in `PATH` and the extensions in `PATHEXT`
+pub fn scan_executables(dirs: &[PathBuf], extensions: &[String]) -> Vec<AppEntry> {
+  dirs
+    .iter()
+    .filter_map(|dir| fs::read_dir(dir).ok())
+    .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+    .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+    .filter(|entry| {
+      extension(&entry.path().to_string_lossy()).is_some_and(|extension| {
+        extensions.iter().any(|allowed| {
+          allowed
+            .trim_start_matches('.')
+            .eq_ignore_ascii_case(&extension)
+        })
+      })
+    })
+    .map(|entry| AppEntry::executable(&entry.path()))
+    .collect()
+}
+
+/// The apps the launcher searches
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppIndex {
+  apps: Vec<AppEntry>,
+}
+
+impl AppIndex {
+  /// Indexes `apps`, earlier ones win over later duplicates: the same id, the
+  /// same name and target, or an executable another app already runs
+  pub fn new(apps: impl IntoIterator<Item = AppEntry>) -> Self {
+    let mut ids = HashSet::new();
+    let mut named_targets = HashSet::new();
+    let mut targets = HashSet::new();
+
+    let apps = apps
+      .into_iter()
+      .filter(|app| {
+        let target = app.target.to_lowercase();
+        let duplicate = !ids.insert(app.id.clone())
+          || (!target.is_empty()
+            && !named_targets.insert((app.name.to_lowercase(), target.clone())))
+          || (app.kind == AppKind::Executable && targets.contains(&target));
+
+        if !target.is_empty() {
+          targets.insert(target);
+        }
+        !duplicate
+      })
+      .collect();
+
+    AppIndex { apps }
+  }
+
+  pub fn apps(&self) -> &[AppEntry] {
+    &self.apps
+  }
+
+  pub fn get(&self, id: &str) -> Option<&AppEntry> {
+    self.apps.iter().find(|app| app.id == id)
+  }
+}
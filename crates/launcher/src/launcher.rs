@@ -0,0 +1,88 @@
+mod fuzzy;
+mod history;
+mod index;
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use serde::Serialize;
+
+pub use fuzzy::*;
+pub use history::*;
+pub use index::*;
+
+// Frecency is on a log scale, this puts a few recent launches on par with a
+// word start in the fuzzy score
+const FRECENCY_WEIGHT: f64 = 24.0;
+// Matching the executable's name instead of the display name, e.g. `code`
+// for Visual Studio Code, scores lower
+const TARGET_MATCH_PENALTY: i32 = 16;
+// Bare executables rank below installed apps of the same name
+const EXECUTABLE_PENALTY: i32 = 8;
+
+/// An app found for a query and the score it was ranked by
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SearchResult {
+  #[serde(flatten)]
+  pub app: AppEntry,
+  pub score: f64,
+}
+
+fn target_name(app: &AppEntry) -> Option<String> {
+  Path::new(&app.target)
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .filter(|stem| !stem.is_empty())
+}
+
+/// Ranks the apps of `index` matching `query` by fuzzy score and how often
+/// and recently they were launched, up to `limit` of them
+///
+/// An empty query lists the apps launched most, `now` is in seconds since the
+/// Unix epoch.
+pub fn search(
+  index: &AppIndex,
+  history: &LaunchHistory,
+  query: &str,
+  now: u64,
+  limit: usize,
+) -> Vec<SearchResult> {
+  let empty = query.trim().is_empty();
+
+  let mut results = index
+    .apps()
+    .iter()
+    .filter_map(|app| {
+      let frecency = history.frecency(&app.id, now);
+      if empty && frecency == 0.0 {
+        return None;
+      }
+
+      let by_target = target_name(app)
+        .and_then(|name| fuzzy_score(query, &name))
+        .map(|score| score - TARGET_MATCH_PENALTY);
+      let score = fuzzy_score(query, &app.name).max(by_target)?
+        - if app.kind == AppKind::Executable {
+          EXECUTABLE_PENALTY
+        } else {
+          0
+        };
+
+      Some(SearchResult {
+        app: app.clone(),
+        score: score as f64 + frecency * FRECENCY_WEIGHT,
+      })
+    })
+    .collect::<Vec<_>>();
+
+  // Shorter names first among equals, they are closer to what was typed
+  results.sort_by(|a, b| {
+    b.score
+      .partial_cmp(&a.score)
+      .unwrap_or(Ordering::Equal)
+      .then_with(|| a.app.name.len().cmp(&b.app.name.len()))
+      .then_with(|| a.app.name.cmp(&b.app.name))
+  });
+  results.truncate(limit);
+  results
+}
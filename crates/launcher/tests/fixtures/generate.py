@@ -0,0 +1,63 @@
+"""Writes the launcher fixtures next to this file.
+
+Two Start Menus of shell links ([MS-SHLLINK]) with only a target and an icon
+location, and a directory standing in for one in `PATH`. Run
+`python3 generate.py` after changing them.
+"""
+
+import os
+import struct
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+LINK_CLSID = bytes.fromhex("0114020000000000c000000000000046")
+
+
+def u16(value):
+    return struct.pack("<H", value)
+
+
+def u32(value):
+    return struct.pack("<I", value)
+
+
+def link(target, icon=None):
+    flags = 0x2 | 0x80
+    base = target.encode("latin1") + b"\0"
+    fields = [0, 0x1C, 1, 0x1C, 0x1C, 0, 0x1C + len(base)]
+    info = b"".join(u32(field) for field in fields) + base + b"\0"
+    body = u32(len(info)) + info[4:]
+    if icon is not None:
+        flags |= 0x40
+        body += u16(len(icon)) + icon.encode("utf-16le")
+    body += u32(0)
+
+    header = u32(0x4C) + LINK_CLSID + u32(flags) + b"\0" * 52
+    assert len(header) == 0x4C
+    return header + body
+
+
+def write(path, data=b""):
+    path = os.path.join(HERE, path)
+    os.makedirs(os.path.dirname(path), exist_ok=True)
+    with open(path, "wb") as file:
+        file.write(data)
+
+
+EDITOR = "C:\\Program Files\\Contoso\\editor.exe"
+
+write("start/all/Contoso Editor.lnk", link(EDITOR, EDITOR + ",0"))
+write("start/all/Contoso/Readme.lnk", link("C:\\Program Files\\Contoso\\readme.txt"))
+write("start/all/Contoso/Uninstall Contoso.lnk", link("C:\\Program Files\\Contoso\\uninstall.exe"))
+write("start/all/Command Prompt.lnk", link("C:\\Windows\\System32\\cmd.exe"))
+write("start/all/Computer Management.lnk", link("C:\\Windows\\System32\\compmgmt.msc"))
+write("start/all/Contoso/license.txt", b"Not a shortcut")
+
+# The same shortcut installed for the user too, and another one to the editor
+write("start/user/Contoso Editor.lnk", link(EDITOR))
+write("start/user/Contoso Editor (Beta).lnk", link(EDITOR))
+
+write("path/tool.exe")
+write("path/Build.CMD")
+write("path/notes.txt")
+write("path/nested/inner.exe")
@@ -0,0 +1,392 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use launcher::*;
+
+const DAY: u64 = 24 * 60 * 60;
+const NOW: u64 = 1_700_000_000;
+
+// Files written by `tests/fixtures/generate.py`
+fn fixtures() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn start_menus() -> Vec<PathBuf> {
+  vec![
+    fixtures().join("start").join("all"),
+    fixtures().join("start").join("user"),
+  ]
+}
+
+fn names(apps: &[AppEntry]) -> Vec<&str> {
+  let mut names = apps.iter().map(|app| app.name.as_str()).collect::<Vec<_>>();
+  names.sort();
+  names
+}
+
+fn result_names(results: &[SearchResult]) -> Vec<&str> {
+  results
+    .iter()
+    .map(|result| result.app.name.as_str())
+    .collect()
+}
+
+fn packaged(name: &str) -> AppEntry {
+  AppEntry::packaged(&format!("Contoso.{}_8wekyb3d8bbwe!App", name), name)
+}
+
+fn launched(apps: &[(&AppEntry, u32, u64)]) -> LaunchHistory {
+  let mut history = LaunchHistory::default();
+  for (app, count, last) in apps {
+    history.apps.insert(
+      app.id.clone(),
+      Usage {
+        count: *count,
+        last: *last,
+      },
+    );
+  }
+  history
+}
+
+#[test]
+fn shortcuts_leave_out_documents_and_uninstallers() {
+  let apps = scan_shortcuts(&start_menus());
+
+  assert_eq!(
+    names(&apps),
+    vec![
+      "Command Prompt",
+      "Computer Management",
+      "Contoso Editor",
+      "Contoso Editor",
+      "Contoso Editor (Beta)",
+    ]
+  );
+}
+
+#[test]
+fn shortcut_entries() {
+  let path = fixtures().join("start/all/Contoso Editor.lnk");
+  let app = AppEntry::shortcut(&path, &std::fs::read(&path).unwrap());
+
+  assert_eq!(app.kind, AppKind::Shortcut);
+  assert_eq!(app.name, "Contoso Editor");
+  assert_eq!(app.target, "C:\\Program Files\\Contoso\\editor.exe");
+  // The icon index is dropped
+  assert_eq!(app.icon, "C:\\Program Files\\Contoso\\editor.exe");
+  assert_eq!(app.path, path.to_string_lossy());
+  assert_eq!(
+    app.id,
+    format!("shortcut:{}", path.to_string_lossy().to_lowercase())
+  );
+}
+
+#[test]
+fn shortcuts_without_an_icon_use_their_target() {
+  let path = fixtures().join("start/all/Command Prompt.lnk");
+  let app = AppEntry::shortcut(&path, &std::fs::read(&path).unwrap());
+
+  assert_eq!(app.icon, "C:\\Windows\\System32\\cmd.exe");
+}
+
+#[test]
+fn unreadable_shortcuts_keep_their_name() {
+  let app = AppEntry::shortcut(Path::new("Broken.lnk"), b"not a link");
+
+  assert_eq!(app.name, "Broken");
+  assert_eq!(app.target, "");
+}
+
+#[test]
+fn executables_match_extensions_case_insensitively() {
+  let apps = scan_executables(
+    &[fixtures().join("path")],
+    &["EXE".to_string(), ".cmd".to_string()],
+  );
+
+  // Nothing is read from subdirectories
+  assert_eq!(names(&apps), vec!["Build", "tool"]);
+  assert!(apps.iter().all(|app| app.kind == AppKind::Executable));
+}
+
+#[test]
+fn missing_directories_are_skipped() {
+  let missing = fixtures().join("missing");
+
+  assert!(scan_shortcuts(std::slice::from_ref(&missing)).is_empty());
+  assert!(scan_executables(&[missing], &["exe".to_string()]).is_empty());
+}
+
+#[test]
+fn index_drops_duplicate_names_and_targets() {
+  let index = AppIndex::new(scan_shortcuts(&start_menus()));
+
+  // The user's copy of the all users shortcut goes, the other name stays
+  assert_eq!(
+    names(index.apps()),
+    vec![
+      "Command Prompt",
+      "Computer Management",
+      "Contoso Editor",
+      "Contoso Editor (Beta)",
+    ]
+  );
+  let editor = index
+    .apps()
+    .iter()
+    .find(|app| app.name == "Contoso Editor")
+    .unwrap();
+  assert!(editor.path.contains("all"));
+}
+
+#[test]
+fn index_drops_executables_other_apps_run() {
+  let tool = fixtures().join("path").join("tool.exe");
+  let shortcut = AppEntry {
+    target: tool.to_string_lossy().to_uppercase(),
+    ..packaged("Tool")
+  };
+
+  let index = AppIndex::new([
+    shortcut.clone(),
+    AppEntry::executable(&tool),
+    AppEntry::executable(Path::new("other.exe")),
+  ]);
+
+  assert_eq!(names(index.apps()), vec!["Tool", "other"]);
+}
+
+#[test]
+fn index_keeps_the_first_of_an_id() {
+  let first = packaged("Mail");
+  let second = AppEntry {
+    name: "Outlook".into(),
+    ..first.clone()
+  };
+
+  let index = AppIndex::new([first.clone(), second]);
+
+  assert_eq!(index.apps(), std::slice::from_ref(&first));
+  assert_eq!(index.get(&first.id), Some(&first));
+  assert_eq!(index.get("packaged:missing"), None);
+}
+
+#[test]
+fn fuzzy_needs_every_character_in_order() {
+  assert_eq!(fuzzy_score("dc", "Code"), None);
+  assert_eq!(fuzzy_score("codes", "Code"), None);
+  assert!(fuzzy_score("cde", "Code").is_some());
+}
+
+#[test]
+fn fuzzy_empty_query_matches_everything() {
+  assert_eq!(fuzzy_score("", "Code"), Some(0));
+  assert_eq!(fuzzy_score("  ", ""), Some(0));
+}
+
+#[test]
+fn fuzzy_ignores_case_and_spaces_in_the_query() {
+  assert_eq!(fuzzy_score("CODE", "code"), fuzzy_score("code", "code"));
+  assert_eq!(
+    fuzzy_score("vs code", "VSCode"),
+    fuzzy_score("vscode", "VSCode")
+  );
+}
+
+#[test]
+fn fuzzy_consecutive_matches_score_higher() {
+  let consecutive = fuzzy_score("ed", "Editor").unwrap();
+  let apart = fuzzy_score("et", "Editor").unwrap();
+
+  // `t` is two characters past `e`, `d` right after it
+  assert!(consecutive > apart);
+  assert!(fuzzy_score("note", "Notepad") > fuzzy_score("note", "Notable"));
+}
+
+#[test]
+fn fuzzy_word_starts_score_higher() {
+  // `c` starts a word after a space, after a lowercase letter in camel
+  // case, and not in the middle of one
+  let spaced = fuzzy_score("c", "Vs Code").unwrap();
+  let camel = fuzzy_score("c", "VsCode").unwrap();
+  let inside = fuzzy_score("c", "Vscode").unwrap();
+
+  assert!(spaced > inside);
+  assert!(camel > inside);
+  assert!(fuzzy_score("vsc", "Visual Studio Code") > fuzzy_score("vsc", "Divisc"));
+}
+
+#[test]
+fn fuzzy_prefers_matches_at_the_start() {
+  assert!(fuzzy_score("e", "Editor") > fuzzy_score("e", "Mail Editor"));
+  // Long leading gaps cost no more than short ones past the cap
+  assert_eq!(
+    fuzzy_score("e", "Mailbox Editor"),
+    fuzzy_score("e", "Mail and Contacts Editor")
+  );
+}
+
+#[test]
+fn fuzzy_finds_the_best_alignment() {
+  // The first `c` is a weak match, the second starts a word
+  let late = fuzzy_score("code", "Bcx Code").unwrap();
+  let only = fuzzy_score("code", "Code").unwrap();
+
+  assert!(late > fuzzy_score("code", "Bcxode").unwrap());
+  assert!(late <= only);
+}
+
+#[test]
+fn search_ranks_by_fuzzy_score() {
+  let index = AppIndex::new([
+    packaged("Calculator"),
+    packaged("Clock"),
+    packaged("Camera Roll"),
+  ]);
+  let results = search(&index, &LaunchHistory::default(), "cr", NOW, 8);
+
+  // The `r` of Roll starts a word, the one of Calculator ends it
+  assert_eq!(result_names(&results), vec!["Camera Roll", "Calculator"]);
+}
+
+#[test]
+fn search_puts_frecent_apps_first() {
+  let calendar = packaged("Calendar");
+  let calculator = packaged("Calculator");
+  let index = AppIndex::new([calendar.clone(), calculator.clone()]);
+  let history = launched(&[(&calendar, 20, NOW - DAY)]);
+
+  let results = search(&index, &history, "cal", NOW, 8);
+
+  assert_eq!(result_names(&results), vec!["Calendar", "Calculator"]);
+  assert!(results[0].score > results[1].score);
+}
+
+#[test]
+fn search_frecency_fades() {
+  let mail = packaged("Mail");
+  let maps = packaged("Maps");
+  let index = AppIndex::new([mail.clone(), maps.clone()]);
+  // Launched as often, but a long time ago
+  let history = launched(&[(&mail, 5, NOW - 70 * DAY), (&maps, 5, NOW - DAY)]);
+
+  let results = search(&index, &history, "ma", NOW, 8);
+
+  assert_eq!(result_names(&results), vec!["Maps", "Mail"]);
+}
+
+#[test]
+fn search_with_an_empty_query_lists_launched_apps() {
+  let mail = packaged("Mail");
+  let maps = packaged("Maps");
+  let index = AppIndex::new([packaged("Calendar"), mail.clone(), maps.clone()]);
+  let history = launched(&[(&mail, 1, NOW), (&maps, 9, NOW)]);
+
+  let results = search(&index, &history, " ", NOW, 8);
+
+  assert_eq!(result_names(&results), vec!["Maps", "Mail"]);
+  assert!(search(&index, &LaunchHistory::default(), "", NOW, 8).is_empty());
+}
+
+#[test]
+fn search_breaks_ties_by_length_then_name() {
+  let index = AppIndex::new([
+    packaged("Paint Pro"),
+    packaged("Paint 3D"),
+    packaged("Paint"),
+    packaged("Paint Box"),
+  ]);
+
+  let results = search(&index, &LaunchHistory::default(), "paint", NOW, 8);
+
+  assert!(results
+    .iter()
+    .all(|result| result.score == results[0].score));
+  assert_eq!(
+    result_names(&results),
+    vec!["Paint", "Paint 3D", "Paint Box", "Paint Pro"]
+  );
+}
+
+#[test]
+fn search_matches_targets_below_names() {
+  let studio = AppEntry {
+    target: "C:\\Program Files\\Microsoft Visual Studio\\devenv.exe".into(),
+    ..packaged("Visual Studio")
+  };
+  let devenv = packaged("devenv");
+  let index = AppIndex::new([studio, devenv]);
+
+  let results = search(&index, &LaunchHistory::default(), "devenv", NOW, 8);
+
+  assert_eq!(result_names(&results), vec!["devenv", "Visual Studio"]);
+}
+
+#[test]
+fn search_ranks_executables_below_apps() {
+  let index = AppIndex::new([
+    AppEntry::executable(Path::new("notepad.exe")),
+    packaged("notepad"),
+  ]);
+
+  let results = search(&index, &LaunchHistory::default(), "notepad", NOW, 8);
+
+  assert_eq!(
+    results
+      .iter()
+      .map(|result| result.app.kind)
+      .collect::<Vec<_>>(),
+    vec![AppKind::Packaged, AppKind::Executable]
+  );
+}
+
+#[test]
+fn search_stops_at_the_limit() {
+  let index = AppIndex::new(["Mail", "Maps", "Math"].map(packaged));
+
+  assert_eq!(
+    search(&index, &LaunchHistory::default(), "ma", NOW, 2).len(),
+    2
+  );
+}
+
+#[test]
+fn history_round_trips() {
+  let path = std::env::temp_dir()
+    .join(format!("launcher-test-{}", std::process::id()))
+    .join("launcher.json");
+  let mut history = LaunchHistory::default();
+  history.record("packaged:mail", NOW);
+  history.record("packaged:mail", NOW + 1);
+
+  history.save(&path).unwrap();
+  let loaded = LaunchHistory::load(&path);
+  std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+  assert_eq!(loaded, history);
+  assert_eq!(
+    loaded.apps["packaged:mail"],
+    Usage {
+      count: 2,
+      last: NOW + 1
+    }
+  );
+  assert_eq!(
+    LaunchHistory::load(&fixtures().join("missing.json")),
+    LaunchHistory::default()
+  );
+}
+
+#[test]
+fn frecency_halves_every_week() {
+  let history = launched(&[(&packaged("Mail"), 3, NOW)]);
+  let id = packaged("Mail").id;
+
+  let fresh = history.frecency(&id, NOW);
+  let week_old = history.frecency(&id, NOW + 7 * DAY);
+
+  assert!((fresh - 4f64.ln()).abs() < 1e-9);
+  assert!((week_old - fresh / 2.0).abs() < 1e-9);
+  assert_eq!(history.frecency("packaged:other", NOW), 0.0);
+}
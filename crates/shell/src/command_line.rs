@@ -0,0 +1,224 @@
+use std::iter;
+
+/// Quotes `argument` so `CommandLineToArgvW` and the C runtime read it back
+/// unchanged
+pub fn quote_argument(argument: &str) -> String {
+  if !argument.is_empty() && !argument.contains([' ', '\t', '\n', '\x0B', '"']) {
+    return argument.to_string();
+  }
+
+  let mut quoted = String::from('"');
+  let mut backslashes = 0;
+  for c in argument.chars() {
+    if c == '\\' {
+      backslashes += 1;
+      continue;
+    }
+
+    // Backslashes are only special before a quote
+    let escaped = if c == '"' {
+      backslashes * 2 + 1
+    } else {
+      backslashes
+    };
+    quoted.extend(iter::repeat_n('\\', escaped));
+    quoted.push(c);
+    backslashes = 0;
+  }
+
+  // Doubled so the closing quote isn't escaped
+  quoted.extend(iter::repeat_n('\\', backslashes * 2));
+  quoted.push('"');
+  quoted
+}
+
+/// Command line running `program` with `arguments`
+pub fn command_line(program: &str, arguments: &[String]) -> String {
+  iter::once(program)
+    .chain(arguments.iter().map(String::as_str))
+    .map(quote_argument)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Replaces `%VARIABLE%`s with what `lookup` returns for them, the way
+/// `ExpandEnvironmentStringsW` does
+///
+/// Unknown variables are kept with their percent signs, and the closing one
+/// may still open the next variable, e.g. `%UNSET%PATH%`.
+pub fn expand_variables(text: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+  let mut expanded = String::with_capacity(text.len());
+  let mut rest = text;
+
+  while let Some(start) = rest.find('%') {
+    expanded.push_str(&rest[..start]);
+    let after = &rest[start + 1..];
+
+    let Some(end) = after.find('%') else {
+      rest = &rest[start..];
+      break;
+    };
+    match lookup(&after[..end]).filter(|_| end > 0) {
+      Some(value) => {
+        expanded.push_str(&value);
+        rest = &after[end + 1..];
+      }
+      None => {
+        expanded.push('%');
+        expanded.push_str(&after[..end]);
+        rest = &after[end..];
+      }
+    }
+  }
+
+  expanded.push_str(rest);
+  expanded
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Splits `command_line` by the rules of `CommandLineToArgvW`, the program
+  // name ends at the next quote or whitespace without any escaping
+  fn argv(command_line: &str) -> Vec<String> {
+    let mut chars = command_line.chars().peekable();
+    let mut arguments = Vec::new();
+
+    let mut program = String::new();
+    if chars.next_if_eq(&'"').is_some() {
+      program.extend(chars.by_ref().take_while(|c| *c != '"'));
+    } else {
+      while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+        program.push(c);
+      }
+    }
+    arguments.push(program);
+
+    loop {
+      while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+      if chars.peek().is_none() {
+        return arguments;
+      }
+
+      let mut argument = String::new();
+      let mut quoted = false;
+      while let Some(c) = chars.next() {
+        match c {
+          '\\' => {
+            let mut backslashes = 1;
+            while chars.next_if_eq(&'\\').is_some() {
+              backslashes += 1;
+            }
+            if chars.next_if_eq(&'"').is_some() {
+              argument.extend(iter::repeat_n('\\', backslashes / 2));
+              if backslashes % 2 == 1 {
+                argument.push('"');
+              } else {
+                quoted = !quoted;
+              }
+            } else {
+              argument.extend(iter::repeat_n('\\', backslashes));
+            }
+          }
+          // Two quotes inside quotes are a literal one
+          '"' if quoted && chars.next_if_eq(&'"').is_some() => argument.push('"'),
+          '"' => quoted = !quoted,
+          ' ' | '\t' if !quoted => break,
+          c => argument.push(c),
+        }
+      }
+      arguments.push(argument);
+    }
+  }
+
+  fn variables(name: &str) -> Option<String> {
+    [("HOME", r"C:\Users\me"), ("empty", "")]
+      .iter()
+      .find(|(variable, _)| variable.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.to_string())
+  }
+
+  #[test]
+  fn plain_arguments_are_kept() {
+    assert_eq!(quote_argument("simple"), "simple");
+    assert_eq!(quote_argument(r"C:\dir\file.txt"), r"C:\dir\file.txt");
+    assert_eq!(quote_argument(r"trailing\"), r"trailing\");
+  }
+
+  #[test]
+  fn quoting_escapes_only_what_it_has_to() {
+    assert_eq!(quote_argument(""), r#""""#);
+    assert_eq!(quote_argument("a b"), r#""a b""#);
+    assert_eq!(quote_argument(r"a b\"), r#""a b\\""#);
+    assert_eq!(quote_argument(r"a\b c"), r#""a\b c""#);
+    assert_eq!(quote_argument(r#"say "hi""#), r#""say \"hi\"""#);
+    assert_eq!(quote_argument(r#"a\"b"#), r#""a\\\"b""#);
+  }
+
+  #[test]
+  fn arguments_read_back_unchanged() {
+    let arguments = [
+      "",
+      " ",
+      "a b",
+      "tab\there",
+      r"trailing\",
+      r"trailing\\",
+      r"C:\Program Files\",
+      r"C:\Program Files\\",
+      r#"""#,
+      r#"embedded "quotes" inside"#,
+      r#"\""#,
+      r#"\\"\\"#,
+      r#"a\\\"b c"#,
+      r"\\server\share\dir name",
+      "multiple   spaces",
+    ];
+
+    for argument in arguments {
+      let line = command_line("app.exe", &[argument.to_string(), "next".into()]);
+      assert_eq!(argv(&line), ["app.exe", argument, "next"], "{}", line);
+    }
+
+    let all = arguments.map(String::from);
+    assert_eq!(argv(&command_line("app.exe", &all))[1..], all);
+  }
+
+  #[test]
+  fn programs_with_spaces_are_quoted() {
+    let line = command_line(r"C:\Program Files\app.exe", &["--flag".into()]);
+
+    assert_eq!(line, r#""C:\Program Files\app.exe" --flag"#);
+    assert_eq!(argv(&line), [r"C:\Program Files\app.exe", "--flag"]);
+  }
+
+  #[test]
+  fn known_variables_are_expanded() {
+    assert_eq!(
+      expand_variables(r"%HOME%\app.exe", variables),
+      r"C:\Users\me\app.exe"
+    );
+    assert_eq!(
+      expand_variables("%home%%Home%", variables),
+      r"C:\Users\meC:\Users\me"
+    );
+    assert_eq!(expand_variables("[%empty%]", variables), "[]");
+    assert_eq!(expand_variables("no variables", variables), "no variables");
+  }
+
+  #[test]
+  fn unknown_variables_are_left_intact() {
+    assert_eq!(
+      expand_variables(r"%UNSET%\app.exe", variables),
+      r"%UNSET%\app.exe"
+    );
+    assert_eq!(
+      expand_variables(r"%UNSET%HOME%", variables),
+      r"%UNSETC:\Users\me"
+    );
+    assert_eq!(expand_variables("100%", variables), "100%");
+    assert_eq!(expand_variables("%%", variables), "%%");
+    assert_eq!(expand_variables("50% of %HOME", variables), "50% of %HOME");
+  }
+}
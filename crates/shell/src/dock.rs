@@ -0,0 +1,13 @@
+mod autohide;
+#[cfg(test)]
+pub(crate) mod fake_clock;
+mod layout;
+mod model;
+mod titles;
+mod trace;
+
+pub use autohide::*;
+pub use layout::*;
+pub use model::*;
+pub use titles::*;
+pub use trace::*;
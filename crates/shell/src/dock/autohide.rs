@@ -0,0 +1,405 @@
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::settings::DockMode;
+use crate::settings::DockSettings;
+
+/// Where the pointer entered or left, the dock counts as hovered while the
+/// pointer is over either of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Area {
+  Dock,
+  Hitbox,
+}
+
+/// Inputs of the auto-hide state machine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DockEvent {
+  PointerEntered(Area),
+  PointerLeft(Area),
+  // A window now overlaps (or stopped overlapping) the dock rect
+  OverlapChanged(bool),
+  FullscreenChanged(bool),
+  // The dock hotkey flips the visibility right away, the mode takes over
+  // again once the pointer enters or leaves the dock
+  Toggled,
+}
+
+pub trait Clock {
+  fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now(&self) -> Instant {
+    Instant::now()
+  }
+}
+
+/// Decides when the dock is shown or hidden
+///
+/// Events and [`AutoHide::tick`] return `Some(visible)` whenever the dock
+/// must change its visibility, delayed transitions are applied by `tick`
+/// once [`AutoHide::deadline`] has passed.
+pub struct AutoHide<C: Clock = SystemClock> {
+  mode: DockMode,
+  show_delay: Duration,
+  hide_delay: Duration,
+  clock: C,
+  visible: bool,
+  over_dock: bool,
+  over_hitbox: bool,
+  overlapped: bool,
+  fullscreen: bool,
+  // Visibility asked for with the hotkey, overrides the mode
+  forced: Option<bool>,
+  // Target visibility and when to switch to it
+  pending: Option<(bool, Instant)>,
+}
+
+impl<C: Clock> AutoHide<C> {
+  pub fn new(settings: &DockSettings, visible: bool, clock: C) -> Self {
+    Self {
+      mode: settings.mode,
+      show_delay: Duration::from_millis(settings.show_delay_ms),
+      hide_delay: Duration::from_millis(settings.hide_delay_ms),
+      clock,
+      visible,
+      over_dock: false,
+      over_hitbox: false,
+      overlapped: false,
+      fullscreen: false,
+      forced: None,
+      pending: None,
+    }
+  }
+
+  pub fn is_visible(&self) -> bool {
+    self.visible
+  }
+
+  pub fn deadline(&self) -> Option<Instant> {
+    self.pending.map(|(_, deadline)| deadline)
+  }
+
+  pub fn handle(&mut self, event: DockEvent) -> Option<bool> {
+    if let DockEvent::PointerEntered(_) | DockEvent::PointerLeft(_) = event {
+      self.forced = None;
+    }
+
+    match event {
+      DockEvent::PointerEntered(Area::Dock) => self.over_dock = true,
+      DockEvent::PointerEntered(Area::Hitbox) => self.over_hitbox = true,
+      DockEvent::PointerLeft(Area::Dock) => self.over_dock = false,
+      DockEvent::PointerLeft(Area::Hitbox) => self.over_hitbox = false,
+      DockEvent::OverlapChanged(overlapped) => self.overlapped = overlapped,
+      DockEvent::FullscreenChanged(fullscreen) => self.fullscreen = fullscreen,
+      DockEvent::Toggled => {
+        let visible = !self.visible;
+        self.forced = Some(visible);
+        self.pending = None;
+        return self.set_visible(visible);
+      }
+    }
+
+    self.evaluate()
+  }
+
+  /// Applies the pending transition if its delay has elapsed
+  pub fn tick(&mut self) -> Option<bool> {
+    match self.pending {
+      Some((visible, deadline)) if self.clock.now() >= deadline => {
+        self.pending = None;
+        self.set_visible(visible)
+      }
+      _ => None,
+    }
+  }
+
+  /// Schedules the transition towards the visibility the current mode wants
+  pub fn evaluate(&mut self) -> Option<bool> {
+    let desired = self.desired();
+    if desired == self.visible {
+      self.pending = None;
+      return None;
+    }
+
+    if matches!(self.pending, Some((visible, _)) if visible == desired) {
+      return self.tick();
+    }
+
+    let delay = if desired {
+      self.show_delay
+    } else {
+      self.hide_delay
+    };
+
+    self.pending = Some((desired, self.clock.now() + delay));
+    self.tick()
+  }
+
+  fn desired(&self) -> bool {
+    if let Some(forced) = self.forced {
+      return forced;
+    }
+
+    let hovered = self.over_dock || self.over_hitbox;
+
+    match self.mode {
+      DockMode::AlwaysVisible => true,
+      DockMode::AutoHide => hovered,
+      DockMode::IntelligentHide => hovered || !self.overlapped,
+      DockMode::HideInFullscreen | DockMode::ReserveSpace => hovered || !self.fullscreen,
+    }
+  }
+
+  fn set_visible(&mut self, visible: bool) -> Option<bool> {
+    if self.visible == visible {
+      return None;
+    }
+
+    self.visible = visible;
+    Some(visible)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dock::fake_clock::FakeClock;
+
+  const SHOW_DELAY: Duration = Duration::from_millis(100);
+  const HIDE_DELAY: Duration = Duration::from_millis(1000);
+
+  fn dock(mode: DockMode, visible: bool) -> (AutoHide<FakeClock>, FakeClock) {
+    let settings = DockSettings {
+      mode,
+      show_delay_ms: SHOW_DELAY.as_millis() as u64,
+      hide_delay_ms: HIDE_DELAY.as_millis() as u64,
+      ..Default::default()
+    };
+    let clock = FakeClock::new();
+    (AutoHide::new(&settings, visible, clock.clone()), clock)
+  }
+
+  #[test]
+  fn always_visible_ignores_the_pointer() {
+    let (mut auto_hide, clock) = dock(DockMode::AlwaysVisible, true);
+
+    assert_eq!(
+      auto_hide.handle(DockEvent::PointerEntered(Area::Dock)),
+      None
+    );
+    assert_eq!(auto_hide.handle(DockEvent::PointerLeft(Area::Dock)), None);
+    assert_eq!(auto_hide.handle(DockEvent::OverlapChanged(true)), None);
+    assert_eq!(auto_hide.handle(DockEvent::FullscreenChanged(true)), None);
+    assert_eq!(auto_hide.deadline(), None);
+
+    clock.advance(HIDE_DELAY * 10);
+    assert_eq!(auto_hide.tick(), None);
+    assert!(auto_hide.is_visible());
+  }
+
+  #[test]
+  fn auto_hide_hides_after_the_hide_delay() {
+    let (mut auto_hide, clock) = dock(DockMode::AutoHide, true);
+    let start = clock.now();
+
+    assert_eq!(auto_hide.handle(DockEvent::PointerLeft(Area::Dock)), None);
+    assert_eq!(auto_hide.deadline(), Some(start + HIDE_DELAY));
+
+    clock.advance(HIDE_DELAY - Duration::from_millis(1));
+    assert_eq!(auto_hide.tick(), None);
+    assert!(auto_hide.is_visible());
+
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(auto_hide.tick(), Some(false));
+    assert!(!auto_hide.is_visible());
+    assert_eq!(auto_hide.deadline(), None);
+    assert_eq!(auto_hide.tick(), None);
+  }
+
+  #[test]
+  fn auto_hide_shows_after_the_show_delay() {
+    let (mut auto_hide, clock) = dock(DockMode::AutoHide, false);
+
+    assert_eq!(
+      auto_hide.handle(DockEvent::PointerEntered(Area::Hitbox)),
+      None
+    );
+    assert_eq!(auto_hide.deadline(), Some(clock.now() + SHOW_DELAY));
+
+    clock.advance(SHOW_DELAY);
+    assert_eq!(auto_hide.tick(), Some(true));
+  }
+
+  #[test]
+  fn no_delay_applies_right_away() {
+    let settings = DockSettings {
+      mode: DockMode::AutoHide,
+      show_delay_ms: 0,
+      hide_delay_ms: 0,
+      ..Default::default()
+    };
+    let mut auto_hide = AutoHide::new(&settings, false, FakeClock::new());
+
+    assert_eq!(
+      auto_hide.handle(DockEvent::PointerEntered(Area::Dock)),
+      Some(true)
+    );
+    assert_eq!(
+      auto_hide.handle(DockEvent::PointerLeft(Area::Dock)),
+      Some(false)
+    );
+  }
+
+  #[test]
+  fn returning_pointer_cancels_the_pending_hide() {
+    let (mut auto_hide, clock) = dock(DockMode::AutoHide, true);
+
+    auto_hide.handle(DockEvent::PointerLeft(Area::Dock));
+    clock.advance(HIDE_DELAY / 2);
+    assert_eq!(
+      auto_hide.handle(DockEvent::PointerEntered(Area::Dock)),
+      None
+    );
+    assert_eq!(auto_hide.deadline(), None);
+
+    clock.advance(HIDE_DELAY);
+    assert_eq!(auto_hide.tick(), None);
+    assert!(auto_hide.is_visible());
+  }
+
+  #[test]
+  fn leaving_again_restarts_the_delay() {
+    let (mut auto_hide, clock) = dock(DockMode::AutoHide, true);
+
+    auto_hide.handle(DockEvent::PointerLeft(Area::Dock));
+    clock.advance(HIDE_DELAY / 2);
+    auto_hide.handle(DockEvent::PointerEntered(Area::Dock));
+    auto_hide.handle(DockEvent::PointerLeft(Area::Dock));
+
+    assert_eq!(auto_hide.deadline(), Some(clock.now() + HIDE_DELAY));
+  }
+
+  #[test]
+  fn hitbox_and_dock_both_count_as_hovered() {
+    let (mut auto_hide, _) = dock(DockMode::AutoHide, true);
+
+    auto_hide.handle(DockEvent::PointerEntered(Area::Hitbox));
+    auto_hide.handle(DockEvent::PointerEntered(Area::Dock));
+    assert_eq!(auto_hide.handle(DockEvent::PointerLeft(Area::Hitbox)), None);
+    assert_eq!(auto_hide.deadline(), None);
+
+    auto_hide.handle(DockEvent::PointerLeft(Area::Dock));
+    assert!(auto_hide.deadline().is_some());
+  }
+
+  #[test]
+  fn intelligent_hide_follows_overlapping_windows() {
+    let (mut auto_hide, clock) = dock(DockMode::IntelligentHide, true);
+
+    assert_eq!(auto_hide.handle(DockEvent::FullscreenChanged(true)), None);
+    assert_eq!(auto_hide.deadline(), None);
+
+    assert_eq!(auto_hide.handle(DockEvent::OverlapChanged(true)), None);
+    clock.advance(HIDE_DELAY);
+    assert_eq!(auto_hide.tick(), Some(false));
+
+    // Hovering shows it over the window
+    auto_hide.handle(DockEvent::PointerEntered(Area::Hitbox));
+    clock.advance(SHOW_DELAY);
+    assert_eq!(auto_hide.tick(), Some(true));
+    auto_hide.handle(DockEvent::PointerLeft(Area::Hitbox));
+    clock.advance(HIDE_DELAY);
+    assert_eq!(auto_hide.tick(), Some(false));
+
+    auto_hide.handle(DockEvent::OverlapChanged(false));
+    clock.advance(SHOW_DELAY);
+    assert_eq!(auto_hide.tick(), Some(true));
+  }
+
+  #[test]
+  fn overlap_going_away_cancels_the_pending_hide() {
+    let (mut auto_hide, clock) = dock(DockMode::IntelligentHide, true);
+
+    auto_hide.handle(DockEvent::OverlapChanged(true));
+    clock.advance(HIDE_DELAY / 2);
+    assert_eq!(auto_hide.handle(DockEvent::OverlapChanged(false)), None);
+    assert_eq!(auto_hide.deadline(), None);
+
+    clock.advance(HIDE_DELAY);
+    assert_eq!(auto_hide.tick(), None);
+    assert!(auto_hide.is_visible());
+  }
+
+  #[test]
+  fn hide_in_fullscreen_follows_fullscreen_apps() {
+    for mode in [DockMode::HideInFullscreen, DockMode::ReserveSpace] {
+      let (mut auto_hide, clock) = dock(mode, true);
+
+      assert_eq!(auto_hide.handle(DockEvent::OverlapChanged(true)), None);
+      assert_eq!(auto_hide.deadline(), None);
+
+      auto_hide.handle(DockEvent::FullscreenChanged(true));
+      clock.advance(HIDE_DELAY);
+      assert_eq!(auto_hide.tick(), Some(false));
+
+      auto_hide.handle(DockEvent::PointerEntered(Area::Hitbox));
+      clock.advance(SHOW_DELAY);
+      assert_eq!(auto_hide.tick(), Some(true));
+      auto_hide.handle(DockEvent::PointerLeft(Area::Hitbox));
+      clock.advance(HIDE_DELAY);
+      assert_eq!(auto_hide.tick(), Some(false));
+
+      auto_hide.handle(DockEvent::FullscreenChanged(false));
+      clock.advance(SHOW_DELAY);
+      assert_eq!(auto_hide.tick(), Some(true));
+    }
+  }
+
+  #[test]
+  fn toggled_overrides_the_mode() {
+    let (mut auto_hide, clock) = dock(DockMode::AlwaysVisible, true);
+
+    assert_eq!(auto_hide.handle(DockEvent::Toggled), Some(false));
+    assert_eq!(auto_hide.handle(DockEvent::OverlapChanged(true)), None);
+    clock.advance(HIDE_DELAY);
+    assert_eq!(auto_hide.tick(), None);
+    assert!(!auto_hide.is_visible());
+
+    assert_eq!(auto_hide.handle(DockEvent::Toggled), Some(true));
+  }
+
+  #[test]
+  fn toggled_cancels_the_pending_transition() {
+    let (mut auto_hide, clock) = dock(DockMode::AutoHide, true);
+
+    auto_hide.handle(DockEvent::PointerLeft(Area::Dock));
+    assert_eq!(auto_hide.handle(DockEvent::Toggled), Some(false));
+    assert_eq!(auto_hide.deadline(), None);
+
+    assert_eq!(auto_hide.handle(DockEvent::Toggled), Some(true));
+    clock.advance(HIDE_DELAY);
+    assert_eq!(auto_hide.tick(), None);
+    assert!(auto_hide.is_visible());
+  }
+
+  #[test]
+  fn pointer_ends_the_override() {
+    let (mut auto_hide, clock) = dock(DockMode::AutoHide, false);
+
+    assert_eq!(auto_hide.handle(DockEvent::Toggled), Some(true));
+    auto_hide.handle(DockEvent::PointerEntered(Area::Dock));
+    auto_hide.handle(DockEvent::PointerLeft(Area::Dock));
+    clock.advance(HIDE_DELAY);
+    assert_eq!(auto_hide.tick(), Some(false));
+
+    let (mut auto_hide, clock) = dock(DockMode::AlwaysVisible, true);
+    assert_eq!(auto_hide.handle(DockEvent::Toggled), Some(false));
+    auto_hide.handle(DockEvent::PointerEntered(Area::Hitbox));
+    clock.advance(SHOW_DELAY);
+    assert_eq!(auto_hide.tick(), Some(true));
+  }
+}
@@ -0,0 +1,26 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::Instant;
+
+use super::autohide::Clock;
+
+/// A clock that only moves when told to, clones share the same time
+#[derive(Clone)]
+pub struct FakeClock(Rc<Cell<Instant>>);
+
+impl FakeClock {
+  pub fn new() -> Self {
+    Self(Rc::new(Cell::new(Instant::now())))
+  }
+
+  pub fn advance(&self, duration: Duration) {
+    self.0.set(self.0.get() + duration);
+  }
+}
+
+impl Clock for FakeClock {
+  fn now(&self) -> Instant {
+    self.0.get()
+  }
+}
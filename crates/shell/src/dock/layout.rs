@@ -0,0 +1,355 @@
+use serde::Serialize;
+
+use crate::settings::DockAlignment;
+use crate::settings::DockEdge;
+use crate::settings::DockOverflow;
+use crate::settings::DockSettings;
+
+// Tiles are never shrunk below this size, the dock scrolls instead
+const MIN_TILE_SIZE: i32 = 16;
+// Thickness of the strip that reveals a hidden dock
+const HITBOX_THICKNESS: i32 = 2;
+
+/// A screen rectangle in physical pixels
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bounds {
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32,
+}
+
+/// Where the dock window goes and how its tiles are laid out inside it
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DockLayout {
+  pub bounds: Bounds,
+  pub hitbox: Bounds,
+  // Strip kept free of maximized windows when the dock reserves space
+  pub reserved: Bounds,
+  pub edge: DockEdge,
+  pub vertical: bool,
+  pub tile_size: i32,
+  pub spacing: i32,
+  pub padding: i32,
+  // Length of the tiles and padding, longer than the window when scrolling
+  pub content_length: i32,
+  pub scrollable: bool,
+}
+
+impl Bounds {
+  pub fn right(&self) -> i32 {
+    self.x + self.width
+  }
+
+  pub fn bottom(&self) -> i32 {
+    self.y + self.height
+  }
+
+  pub fn intersects(&self, other: &Bounds) -> bool {
+    self.x < other.right()
+      && other.x < self.right()
+      && self.y < other.bottom()
+      && other.y < self.bottom()
+  }
+}
+
+/// Lays out `count` tiles along the configured edge of `screen`
+///
+/// `margin` is the gap between the dock and the screen edges. It and the
+/// lengths in `settings` are logical pixels, multiplied by `scale` to get the
+/// physical pixels of `screen`. Apps that don't fit either shrink the tiles
+/// down to [`MIN_TILE_SIZE`] or make the dock scroll, depending on
+/// `settings.overflow`.
+pub fn compute_layout(
+  settings: &DockSettings,
+  margin: i32,
+  count: usize,
+  screen: Bounds,
+  scale: f64,
+) -> DockLayout {
+  let vertical = matches!(settings.edge, DockEdge::Left | DockEdge::Right);
+  let (screen_length, screen_thickness) = if vertical {
+    (screen.height, screen.width)
+  } else {
+    (screen.width, screen.height)
+  };
+
+  let physical = |length: i32| (length as f64 * scale).round() as i32;
+  let count = count as i32;
+  let margin = physical(margin);
+  let padding = physical(settings.padding.max(0));
+  let spacing = physical(settings.spacing.max(0));
+  let available = (screen_length - 2 * margin).max(0);
+  let content = |tile_size: i32| match count {
+    0 => 2 * padding,
+    count => count * tile_size + (count - 1) * spacing + 2 * padding,
+  };
+
+  let mut tile_size = physical(settings.tile_size).max(1);
+  if settings.overflow == DockOverflow::Shrink && count > 0 && content(tile_size) > available {
+    let fitting = (available - 2 * padding - (count - 1) * spacing) / count;
+    tile_size = fitting.max(physical(MIN_TILE_SIZE).min(tile_size));
+  }
+
+  let content_length = content(tile_size);
+  let length = content_length.min(available);
+  let thickness = (tile_size + 2 * padding).min(screen_thickness);
+
+  let along = match settings.alignment {
+    DockAlignment::Start => margin,
+    DockAlignment::Center => (screen_length - length) / 2,
+    DockAlignment::End => screen_length - margin - length,
+  };
+  let across = match settings.edge {
+    DockEdge::Top | DockEdge::Left => margin,
+    DockEdge::Bottom | DockEdge::Right => screen_thickness - margin - thickness,
+  };
+  let hitbox_across = match settings.edge {
+    DockEdge::Top | DockEdge::Left => 0,
+    DockEdge::Bottom | DockEdge::Right => screen_thickness - HITBOX_THICKNESS,
+  };
+
+  let reserved_thickness = (thickness + 2 * margin).min(screen_thickness);
+  let reserved_across = match settings.edge {
+    DockEdge::Top | DockEdge::Left => 0,
+    DockEdge::Bottom | DockEdge::Right => screen_thickness - reserved_thickness,
+  };
+
+  let place = |along: i32, across: i32, length: i32, thickness: i32| {
+    if vertical {
+      Bounds {
+        x: screen.x + across,
+        y: screen.y + along,
+        width: thickness,
+        height: length,
+      }
+    } else {
+      Bounds {
+        x: screen.x + along,
+        y: screen.y + across,
+        width: length,
+        height: thickness,
+      }
+    }
+  };
+
+  DockLayout {
+    bounds: place(along, across, length, thickness),
+    hitbox: place(0, hitbox_across, screen_length, HITBOX_THICKNESS),
+    reserved: place(0, reserved_across, screen_length, reserved_thickness),
+    edge: settings.edge,
+    vertical,
+    tile_size,
+    spacing,
+    padding,
+    content_length,
+    scrollable: content_length > length,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // A 1920x1080 monitor below a 30px menubar
+  const SCREEN: Bounds = Bounds {
+    x: 0,
+    y: 30,
+    width: 1920,
+    height: 1050,
+  };
+  const MARGIN: i32 = 5;
+
+  // 40px tiles, 4px apart, with 5px of padding
+  fn settings(edge: DockEdge, alignment: DockAlignment) -> DockSettings {
+    DockSettings {
+      edge,
+      alignment,
+      tile_size: 40,
+      spacing: 4,
+      padding: 5,
+      ..Default::default()
+    }
+  }
+
+  fn layout(settings: &DockSettings, count: usize) -> DockLayout {
+    compute_layout(settings, MARGIN, count, SCREEN, 1.0)
+  }
+
+  fn bounds(x: i32, y: i32, width: i32, height: i32) -> Bounds {
+    Bounds {
+      x,
+      y,
+      width,
+      height,
+    }
+  }
+
+  #[test]
+  fn bottom() {
+    let layout = layout(&settings(DockEdge::Bottom, DockAlignment::Center), 3);
+
+    // 3 * 40 + 2 * 4 + 2 * 5 long and 40 + 2 * 5 thick
+    assert_eq!(layout.bounds, bounds(891, 1025, 138, 50));
+    assert_eq!(layout.hitbox, bounds(0, 1078, 1920, 2));
+    assert_eq!(layout.reserved, bounds(0, 1020, 1920, 60));
+    assert_eq!(layout.edge, DockEdge::Bottom);
+    assert!(!layout.vertical);
+    assert_eq!(layout.tile_size, 40);
+    assert_eq!(layout.spacing, 4);
+    assert_eq!(layout.padding, 5);
+    assert_eq!(layout.content_length, 138);
+    assert!(!layout.scrollable);
+  }
+
+  #[test]
+  fn top() {
+    let layout = layout(&settings(DockEdge::Top, DockAlignment::Center), 3);
+
+    assert_eq!(layout.bounds, bounds(891, 35, 138, 50));
+    assert_eq!(layout.hitbox, bounds(0, 30, 1920, 2));
+    assert_eq!(layout.reserved, bounds(0, 30, 1920, 60));
+    assert!(!layout.vertical);
+  }
+
+  #[test]
+  fn left() {
+    let layout = layout(&settings(DockEdge::Left, DockAlignment::Center), 3);
+
+    assert_eq!(layout.bounds, bounds(5, 486, 50, 138));
+    assert_eq!(layout.hitbox, bounds(0, 30, 2, 1050));
+    assert_eq!(layout.reserved, bounds(0, 30, 60, 1050));
+    assert_eq!(layout.edge, DockEdge::Left);
+    assert!(layout.vertical);
+    assert_eq!(layout.content_length, 138);
+  }
+
+  #[test]
+  fn right() {
+    let layout = layout(&settings(DockEdge::Right, DockAlignment::Center), 3);
+
+    assert_eq!(layout.bounds, bounds(1865, 486, 50, 138));
+    assert_eq!(layout.hitbox, bounds(1918, 30, 2, 1050));
+    assert_eq!(layout.reserved, bounds(1860, 30, 60, 1050));
+    assert!(layout.vertical);
+  }
+
+  #[test]
+  fn horizontal_alignments() {
+    let start = layout(&settings(DockEdge::Bottom, DockAlignment::Start), 3);
+    let end = layout(&settings(DockEdge::Bottom, DockAlignment::End), 3);
+
+    assert_eq!(start.bounds, bounds(5, 1025, 138, 50));
+    assert_eq!(end.bounds, bounds(1777, 1025, 138, 50));
+  }
+
+  #[test]
+  fn vertical_alignments() {
+    let start = layout(&settings(DockEdge::Left, DockAlignment::Start), 3);
+    let end = layout(&settings(DockEdge::Right, DockAlignment::End), 3);
+
+    assert_eq!(start.bounds, bounds(5, 35, 50, 138));
+    assert_eq!(end.bounds, bounds(1865, 937, 50, 138));
+  }
+
+  #[test]
+  fn empty_dock_keeps_its_padding() {
+    let layout = layout(&settings(DockEdge::Bottom, DockAlignment::Center), 0);
+
+    assert_eq!(layout.bounds, bounds(955, 1025, 10, 50));
+    assert_eq!(layout.content_length, 10);
+  }
+
+  #[test]
+  fn lengths_are_scaled_to_physical_pixels() {
+    let settings = settings(DockEdge::Bottom, DockAlignment::Center);
+    let layout = compute_layout(&settings, MARGIN, 3, SCREEN, 1.5);
+
+    // 7.5px of padding and margin round up
+    assert_eq!(layout.tile_size, 60);
+    assert_eq!(layout.spacing, 6);
+    assert_eq!(layout.padding, 8);
+    assert_eq!(layout.content_length, 3 * 60 + 2 * 6 + 2 * 8);
+    assert_eq!(layout.bounds, bounds(856, 30 + 1050 - 8 - 76, 208, 76));
+    assert_eq!(layout.reserved, bounds(0, 30 + 1050 - 92, 1920, 92));
+    // The hitbox is a fixed physical strip
+    assert_eq!(layout.hitbox, bounds(0, 1078, 1920, 2));
+
+    let layout = compute_layout(&settings, MARGIN, 3, SCREEN, 1.25);
+    assert_eq!(layout.tile_size, 50);
+    assert_eq!(layout.spacing, 5);
+    assert_eq!(layout.padding, 6);
+    assert_eq!(layout.bounds, bounds(874, 30 + 1050 - 6 - 62, 172, 62));
+  }
+
+  #[test]
+  fn overflow_scrolls() {
+    let layout = layout(&settings(DockEdge::Bottom, DockAlignment::Center), 50);
+
+    // 50 * 40 + 49 * 4 + 2 * 5 doesn't fit in 1920 - 2 * 5
+    assert_eq!(layout.tile_size, 40);
+    assert_eq!(layout.content_length, 2206);
+    assert_eq!(layout.bounds, bounds(5, 1025, 1910, 50));
+    assert!(layout.scrollable);
+  }
+
+  #[test]
+  fn overflow_shrinks_the_tiles() {
+    let settings = DockSettings {
+      overflow: DockOverflow::Shrink,
+      ..settings(DockEdge::Bottom, DockAlignment::Center)
+    };
+    let layout = layout(&settings, 50);
+
+    assert_eq!(layout.tile_size, 34);
+    assert_eq!(layout.content_length, 1906);
+    assert_eq!(layout.bounds, bounds(7, 1031, 1906, 44));
+    assert!(!layout.scrollable);
+  }
+
+  #[test]
+  fn shrinking_stops_at_the_minimum_and_scrolls() {
+    let settings = DockSettings {
+      overflow: DockOverflow::Shrink,
+      ..settings(DockEdge::Left, DockAlignment::Center)
+    };
+    let layout = layout(&settings, 100);
+
+    assert_eq!(layout.tile_size, MIN_TILE_SIZE);
+    assert_eq!(layout.content_length, 100 * 16 + 99 * 4 + 2 * 5);
+    assert_eq!(layout.bounds, bounds(5, 35, 26, 1040));
+    assert!(layout.scrollable);
+  }
+
+  #[test]
+  fn tiles_fitting_are_not_shrunk() {
+    let settings = DockSettings {
+      overflow: DockOverflow::Shrink,
+      ..settings(DockEdge::Bottom, DockAlignment::Center)
+    };
+
+    assert_eq!(layout(&settings, 3).tile_size, 40);
+  }
+
+  #[test]
+  fn small_tiles_are_not_grown_to_the_minimum() {
+    let settings = DockSettings {
+      overflow: DockOverflow::Shrink,
+      tile_size: 10,
+      ..settings(DockEdge::Bottom, DockAlignment::Center)
+    };
+
+    assert_eq!(layout(&settings, 500).tile_size, 10);
+  }
+
+  #[test]
+  fn bounds_intersect() {
+    let dock = bounds(891, 1025, 138, 50);
+
+    assert!(dock.intersects(&bounds(0, 0, 1000, 1040)));
+    assert!(!dock.intersects(&bounds(0, 0, 1920, 1025)));
+    assert!(!dock.intersects(&bounds(1029, 1025, 100, 50)));
+    assert_eq!(dock.right(), 1029);
+    assert_eq!(dock.bottom(), 1075);
+  }
+}
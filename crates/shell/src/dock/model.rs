@@ -0,0 +1,702 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A window shown as a dock tile
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Window {
+  pub hwnd: isize,
+  pub path: String,
+  pub title: String,
+  // Icons are not part of traces, see `replay`
+  #[serde(default)]
+  pub buffer: Vec<u8>,
+  // Flashed by its app and not activated since
+  #[serde(default)]
+  pub attention: bool,
+  #[serde(default)]
+  pub button: ButtonState,
+}
+
+/// Progress shown on a taskbar button, see `ITaskbarList3::SetProgressState`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressState {
+  #[default]
+  None,
+  Indeterminate,
+  Normal,
+  Error,
+  Paused,
+}
+
+/// What an app set on its taskbar button through `ITaskbarList3`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ButtonState {
+  pub progress_state: ProgressState,
+  // From 0 to 1
+  pub progress: f32,
+  // PNG, empty without an overlay
+  pub overlay: Vec<u8>,
+}
+
+/// One `ITaskbarList3` call forwarded to the taskbar
+#[derive(Clone, Debug, PartialEq)]
+pub enum ButtonUpdate {
+  // Out of `PROGRESS_MAX`
+  ProgressValue(u32),
+  ProgressState(ProgressState),
+  Overlay(Vec<u8>),
+}
+
+/// Taskbar button change of a dock window, sent to the dock as `window-button`
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct ButtonChange {
+  pub hwnd: isize,
+  pub button: ButtonState,
+}
+
+/// New title of a dock window, sent to the dock as `window-titles`
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct TitleUpdate {
+  pub hwnd: isize,
+  pub title: String,
+}
+
+/// Attention change of a dock window, sent to the dock as `window-attention`
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct AttentionUpdate {
+  pub hwnd: isize,
+  pub attention: bool,
+}
+
+/// WinEvents and shell hook notifications the dock listens to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+  Create,
+  Destroy,
+  Show,
+  Hide,
+  NameChange,
+  LocationChange,
+  Foreground,
+  MinimizeStart,
+  MinimizeEnd,
+  // `HSHELL_FLASH`, the app asks for attention
+  Flash,
+}
+
+/// Attributes of the window an event is about, read when the event is handled
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct WindowInfo {
+  pub hwnd: isize,
+  pub class: String,
+  pub title: String,
+  pub exe: String,
+  pub style: u32,
+  pub ex_style: u32,
+  pub parent: isize,
+  // Whether the window belongs in the dock, see `util::is_real_window`
+  pub real: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WindowEvent {
+  pub kind: EventKind,
+  pub window: WindowInfo,
+}
+
+/// What applying a batch of events changed
+#[derive(Default, Debug, PartialEq)]
+pub struct Changes {
+  // Tiles were added, removed or swapped
+  pub apps: bool,
+  // Tiles added without an icon yet
+  pub added: Vec<isize>,
+  pub titles: Vec<TitleUpdate>,
+  pub attention: Vec<AttentionUpdate>,
+  pub buttons: Vec<ButtonChange>,
+  // A dock window moved, was minimized or restored, or was focused
+  pub moved: bool,
+  // Explorer showed a taskbar again
+  pub taskbar_shown: bool,
+}
+
+/// Progress values are scaled to this by the shell, whatever the app's total
+pub const PROGRESS_MAX: u32 = 0xFFFE;
+
+impl EventKind {
+  // Only the last one of a burst matters, earlier ones are superseded
+  pub fn coalesces(&self) -> bool {
+    matches!(
+      self,
+      EventKind::NameChange | EventKind::LocationChange | EventKind::Flash
+    )
+  }
+}
+
+/// Drops the events of `events` superseded by a later one of the same kind and
+/// window, keeping the order of the rest
+pub fn coalesce(events: Vec<(EventKind, isize)>) -> Vec<(EventKind, isize)> {
+  let mut seen = HashSet::new();
+  let mut kept = Vec::with_capacity(events.len());
+
+  // Walking backwards the first event of a kind and window is the last one
+  for event in events.into_iter().rev() {
+    if event.0.coalesces() && !seen.insert(event) {
+      continue;
+    }
+    kept.push(event);
+  }
+
+  kept.reverse();
+  kept
+}
+
+/// Applies `event` to the dock windows, recording what changed in `changes`
+pub fn reduce(apps: &mut Vec<Window>, event: &WindowEvent, changes: &mut Changes) {
+  let info = &event.window;
+  let known = apps.iter().position(|app| app.hwnd == info.hwnd);
+
+  match (event.kind, known) {
+    (EventKind::Create | EventKind::Show, known) => {
+      if is_taskbar_class(&info.class) {
+        changes.taskbar_shown = true;
+      }
+
+      if known.is_none() && info.real {
+        add(apps, info, changes);
+      }
+    }
+    (EventKind::Destroy, Some(index)) => {
+      apps.remove(index);
+      changes.apps = true;
+    }
+    (EventKind::NameChange, Some(index)) if apps[index].title != info.title => {
+      apps[index].title = info.title.clone();
+      changes.titles.retain(|update| update.hwnd != info.hwnd);
+      changes.titles.push(TitleUpdate {
+        hwnd: info.hwnd,
+        title: info.title.clone(),
+      });
+    }
+    // Some windows only get a title once they are shown
+    (EventKind::NameChange, None) if info.real => add(apps, info, changes),
+    // The tile follows the owner when a window hides behind it, unless the
+    // owner already has its own
+    (EventKind::Hide, Some(index)) => {
+      if info.parent != 0 {
+        if apps.iter().any(|app| app.hwnd == info.parent) {
+          apps.remove(index);
+        } else {
+          apps[index].hwnd = info.parent;
+        }
+        changes.apps = true;
+      } else if !info.real {
+        apps.remove(index);
+        changes.apps = true;
+      }
+    }
+    (EventKind::Flash, Some(index)) => set_attention(&mut apps[index], true, changes),
+    // Activating a window answers its request
+    (EventKind::Foreground, Some(index)) => {
+      set_attention(&mut apps[index], false, changes);
+      changes.moved = true;
+    }
+    (EventKind::LocationChange | EventKind::MinimizeStart | EventKind::MinimizeEnd, Some(_)) => {
+      changes.moved = true
+    }
+    _ => {}
+  }
+}
+
+/// Whether `class` is the window class of a primary or secondary taskbar
+pub fn is_taskbar_class(class: &str) -> bool {
+  class == "Shell_TrayWnd" || class == "Shell_SecondaryTrayWnd"
+}
+
+fn add(apps: &mut Vec<Window>, info: &WindowInfo, changes: &mut Changes) {
+  apps.push(Window {
+    hwnd: info.hwnd,
+    path: info.exe.clone(),
+    title: info.title.clone(),
+    buffer: Vec::new(),
+    attention: false,
+    button: ButtonState::default(),
+  });
+  changes.apps = true;
+  changes.added.push(info.hwnd);
+}
+
+fn set_attention(app: &mut Window, attention: bool, changes: &mut Changes) {
+  if app.attention == attention {
+    return;
+  }
+
+  app.attention = attention;
+  changes.attention.retain(|update| update.hwnd != app.hwnd);
+  changes.attention.push(AttentionUpdate {
+    hwnd: app.hwnd,
+    attention,
+  });
+}
+
+/// Applies a taskbar button update of `hwnd`, windows without a tile are
+/// ignored
+pub fn update_button(
+  apps: &mut [Window],
+  hwnd: isize,
+  update: ButtonUpdate,
+  changes: &mut Changes,
+) {
+  let Some(app) = apps.iter_mut().find(|app| app.hwnd == hwnd) else {
+    return;
+  };
+
+  let mut button = app.button.clone();
+  match update {
+    ButtonUpdate::ProgressValue(value) => {
+      button.progress = value.min(PROGRESS_MAX) as f32 / PROGRESS_MAX as f32;
+      // Setting a value shows the bar, as on the taskbar
+      if button.progress_state == ProgressState::None
+        || button.progress_state == ProgressState::Indeterminate
+      {
+        button.progress_state = ProgressState::Normal;
+      }
+    }
+    ButtonUpdate::ProgressState(state) => {
+      button.progress_state = state;
+      if state == ProgressState::None {
+        button.progress = 0.0;
+      }
+    }
+    ButtonUpdate::Overlay(overlay) => button.overlay = overlay,
+  }
+
+  if app.button == button {
+    return;
+  }
+
+  app.button = button.clone();
+  changes.buttons.retain(|change| change.hwnd != hwnd);
+  changes.buttons.push(ButtonChange { hwnd, button });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const NOTEPAD: &str = "C:\\Windows\\System32\\notepad.exe";
+
+  fn info(hwnd: isize, title: &str) -> WindowInfo {
+    WindowInfo {
+      hwnd,
+      class: "Notepad".into(),
+      title: title.into(),
+      exe: NOTEPAD.into(),
+      real: true,
+      ..Default::default()
+    }
+  }
+
+  fn event(kind: EventKind, window: WindowInfo) -> WindowEvent {
+    WindowEvent { kind, window }
+  }
+
+  fn apply(apps: &mut Vec<Window>, events: &[WindowEvent]) -> Changes {
+    let mut changes = Changes::default();
+    for event in events {
+      reduce(apps, event, &mut changes);
+    }
+    changes
+  }
+
+  fn hwnds(apps: &[Window]) -> Vec<isize> {
+    apps.iter().map(|app| app.hwnd).collect()
+  }
+
+  #[test]
+  fn coalesce_keeps_the_last_of_a_burst() {
+    let events = vec![
+      (EventKind::NameChange, 1),
+      (EventKind::LocationChange, 1),
+      (EventKind::NameChange, 1),
+      (EventKind::Create, 2),
+      (EventKind::Flash, 2),
+      (EventKind::LocationChange, 1),
+      (EventKind::Flash, 2),
+    ];
+
+    assert_eq!(
+      coalesce(events),
+      vec![
+        (EventKind::NameChange, 1),
+        (EventKind::Create, 2),
+        (EventKind::LocationChange, 1),
+        (EventKind::Flash, 2),
+      ]
+    );
+  }
+
+  #[test]
+  fn coalesce_keeps_every_other_event() {
+    let events = vec![
+      (EventKind::Create, 1),
+      (EventKind::Show, 1),
+      (EventKind::Hide, 1),
+      (EventKind::Show, 1),
+      (EventKind::Foreground, 1),
+      (EventKind::MinimizeStart, 1),
+      (EventKind::MinimizeEnd, 1),
+      (EventKind::Foreground, 1),
+      (EventKind::Destroy, 1),
+    ];
+
+    assert_eq!(coalesce(events.clone()), events);
+  }
+
+  #[test]
+  fn coalesce_keeps_windows_apart() {
+    let events = vec![
+      (EventKind::NameChange, 1),
+      (EventKind::NameChange, 2),
+      (EventKind::NameChange, 1),
+    ];
+
+    assert_eq!(
+      coalesce(events),
+      vec![(EventKind::NameChange, 2), (EventKind::NameChange, 1)]
+    );
+  }
+
+  #[test]
+  fn coalesce_large_bursts() {
+    let events = (0..100_000)
+      .map(|index| (EventKind::LocationChange, index % 100))
+      .collect::<Vec<_>>();
+
+    let last = (0..100)
+      .map(|hwnd| (EventKind::LocationChange, hwnd))
+      .collect::<Vec<_>>();
+
+    assert_eq!(coalesce(events), last);
+  }
+
+  #[test]
+  fn real_windows_get_a_tile_once() {
+    let mut apps = Vec::new();
+    let changes = apply(
+      &mut apps,
+      &[
+        event(EventKind::Create, info(1, "notes.txt - Notepad")),
+        event(EventKind::Show, info(1, "notes.txt - Notepad")),
+      ],
+    );
+
+    assert_eq!(
+      apps,
+      vec![Window {
+        hwnd: 1,
+        path: NOTEPAD.into(),
+        title: "notes.txt - Notepad".into(),
+        buffer: Vec::new(),
+        attention: false,
+        button: ButtonState::default(),
+      }]
+    );
+    assert!(changes.apps);
+    assert_eq!(changes.added, vec![1]);
+  }
+
+  #[test]
+  fn other_windows_are_ignored() {
+    let mut apps = Vec::new();
+    let tooltip = WindowInfo {
+      real: false,
+      ..info(1, "")
+    };
+    let changes = apply(
+      &mut apps,
+      &[
+        event(EventKind::Show, tooltip.clone()),
+        event(EventKind::NameChange, tooltip),
+      ],
+    );
+
+    assert!(apps.is_empty());
+    assert_eq!(changes, Changes::default());
+  }
+
+  #[test]
+  fn shown_taskbars_are_reported() {
+    let mut apps = Vec::new();
+    let taskbar = WindowInfo {
+      class: "Shell_SecondaryTrayWnd".into(),
+      real: false,
+      ..info(1, "")
+    };
+    let changes = apply(&mut apps, &[event(EventKind::Show, taskbar)]);
+
+    assert!(apps.is_empty());
+    assert!(changes.taskbar_shown);
+  }
+
+  #[test]
+  fn destroyed_windows_lose_their_tile() {
+    let mut apps = Vec::new();
+    apply(
+      &mut apps,
+      &[
+        event(EventKind::Show, info(1, "a")),
+        event(EventKind::Show, info(2, "b")),
+      ],
+    );
+
+    let changes = apply(&mut apps, &[event(EventKind::Destroy, info(1, ""))]);
+    assert_eq!(hwnds(&apps), vec![2]);
+    assert!(changes.apps);
+
+    let changes = apply(&mut apps, &[event(EventKind::Destroy, info(3, ""))]);
+    assert_eq!(hwnds(&apps), vec![2]);
+    assert!(!changes.apps);
+  }
+
+  #[test]
+  fn name_changes_update_titles_once_per_window() {
+    let mut apps = Vec::new();
+    apply(&mut apps, &[event(EventKind::Show, info(1, "a"))]);
+
+    let changes = apply(
+      &mut apps,
+      &[
+        event(EventKind::NameChange, info(1, "b")),
+        event(EventKind::NameChange, info(1, "c")),
+      ],
+    );
+    assert_eq!(apps[0].title, "c");
+    assert_eq!(
+      changes.titles,
+      vec![TitleUpdate {
+        hwnd: 1,
+        title: "c".into(),
+      }]
+    );
+    assert!(!changes.apps);
+
+    let changes = apply(&mut apps, &[event(EventKind::NameChange, info(1, "c"))]);
+    assert!(changes.titles.is_empty());
+  }
+
+  #[test]
+  fn name_change_adds_windows_titled_late() {
+    let mut apps = Vec::new();
+    let changes = apply(&mut apps, &[event(EventKind::NameChange, info(1, "a"))]);
+
+    assert_eq!(hwnds(&apps), vec![1]);
+    assert_eq!(changes.added, vec![1]);
+  }
+
+  #[test]
+  fn hiding_behind_an_owner_without_a_tile_hands_it_over() {
+    let mut apps = Vec::new();
+    apply(&mut apps, &[event(EventKind::Show, info(1, "Splash"))]);
+
+    let hidden = WindowInfo {
+      parent: 2,
+      real: false,
+      ..info(1, "Splash")
+    };
+    let changes = apply(&mut apps, &[event(EventKind::Hide, hidden)]);
+
+    assert_eq!(hwnds(&apps), vec![2]);
+    assert!(changes.apps);
+  }
+
+  #[test]
+  fn hiding_behind_an_owner_with_a_tile_removes_it() {
+    let mut apps = Vec::new();
+    apply(
+      &mut apps,
+      &[
+        event(EventKind::Show, info(1, "notes.txt - Notepad")),
+        event(EventKind::Show, info(2, "Save As")),
+      ],
+    );
+
+    let hidden = WindowInfo {
+      parent: 1,
+      real: false,
+      ..info(2, "Save As")
+    };
+    let changes = apply(&mut apps, &[event(EventKind::Hide, hidden)]);
+
+    assert_eq!(hwnds(&apps), vec![1]);
+    assert_eq!(apps[0].title, "notes.txt - Notepad");
+    assert!(changes.apps);
+  }
+
+  #[test]
+  fn hidden_windows_lose_their_tile() {
+    let mut apps = Vec::new();
+    apply(
+      &mut apps,
+      &[
+        event(EventKind::Show, info(1, "a")),
+        event(EventKind::Show, info(2, "b")),
+      ],
+    );
+
+    let hidden = WindowInfo {
+      real: false,
+      ..info(1, "a")
+    };
+    let changes = apply(
+      &mut apps,
+      &[
+        event(EventKind::Hide, hidden),
+        // Still a dock window, e.g. hidden for a moment while restyled
+        event(EventKind::Hide, info(2, "b")),
+      ],
+    );
+
+    assert_eq!(hwnds(&apps), vec![2]);
+    assert!(changes.apps);
+  }
+
+  #[test]
+  fn attention_lasts_until_activated() {
+    let mut apps = Vec::new();
+    apply(
+      &mut apps,
+      &[
+        event(EventKind::Show, info(1, "a")),
+        event(EventKind::Show, info(2, "b")),
+      ],
+    );
+
+    let changes = apply(
+      &mut apps,
+      &[
+        event(EventKind::Flash, info(1, "")),
+        event(EventKind::Flash, info(1, "")),
+        event(EventKind::Flash, info(3, "")),
+      ],
+    );
+    assert!(apps[0].attention);
+    assert_eq!(
+      changes.attention,
+      vec![AttentionUpdate {
+        hwnd: 1,
+        attention: true,
+      }]
+    );
+
+    let changes = apply(
+      &mut apps,
+      &[
+        event(EventKind::Foreground, info(2, "")),
+        event(EventKind::Foreground, info(1, "")),
+      ],
+    );
+    assert!(!apps[0].attention);
+    assert_eq!(
+      changes.attention,
+      vec![AttentionUpdate {
+        hwnd: 1,
+        attention: false,
+      }]
+    );
+    assert!(changes.moved);
+  }
+
+  #[test]
+  fn only_dock_windows_move() {
+    let mut apps = Vec::new();
+    apply(&mut apps, &[event(EventKind::Show, info(1, "a"))]);
+
+    for kind in [
+      EventKind::LocationChange,
+      EventKind::MinimizeStart,
+      EventKind::MinimizeEnd,
+    ] {
+      assert!(apply(&mut apps, &[event(kind, info(1, ""))]).moved);
+      assert!(!apply(&mut apps, &[event(kind, info(2, ""))]).moved);
+    }
+  }
+
+  #[test]
+  fn progress_values_show_the_bar() {
+    let mut apps = Vec::new();
+    apply(&mut apps, &[event(EventKind::Show, info(1, "a"))]);
+
+    let mut changes = Changes::default();
+    update_button(
+      &mut apps,
+      1,
+      ButtonUpdate::ProgressValue(PROGRESS_MAX / 4),
+      &mut changes,
+    );
+    update_button(
+      &mut apps,
+      1,
+      ButtonUpdate::ProgressValue(PROGRESS_MAX / 2),
+      &mut changes,
+    );
+
+    assert_eq!(apps[0].button.progress_state, ProgressState::Normal);
+    assert_eq!(apps[0].button.progress, 0.5);
+    assert_eq!(changes.buttons.len(), 1);
+    assert_eq!(changes.buttons[0].button, apps[0].button);
+  }
+
+  #[test]
+  fn clearing_the_progress_state_resets_the_value() {
+    let mut apps = Vec::new();
+    apply(&mut apps, &[event(EventKind::Show, info(1, "a"))]);
+
+    let mut changes = Changes::default();
+    update_button(
+      &mut apps,
+      1,
+      ButtonUpdate::ProgressState(ProgressState::Paused),
+      &mut changes,
+    );
+    update_button(
+      &mut apps,
+      1,
+      ButtonUpdate::ProgressValue(PROGRESS_MAX),
+      &mut changes,
+    );
+    assert_eq!(apps[0].button.progress_state, ProgressState::Paused);
+    assert_eq!(apps[0].button.progress, 1.0);
+
+    update_button(
+      &mut apps,
+      1,
+      ButtonUpdate::ProgressState(ProgressState::None),
+      &mut changes,
+    );
+    assert_eq!(apps[0].button, ButtonState::default());
+  }
+
+  #[test]
+  fn button_updates_without_a_change_are_dropped() {
+    let mut apps = Vec::new();
+    apply(&mut apps, &[event(EventKind::Show, info(1, "a"))]);
+
+    let mut changes = Changes::default();
+    update_button(
+      &mut apps,
+      1,
+      ButtonUpdate::Overlay(Vec::new()),
+      &mut changes,
+    );
+    update_button(&mut apps, 2, ButtonUpdate::Overlay(vec![1]), &mut changes);
+
+    assert!(changes.buttons.is_empty());
+  }
+}
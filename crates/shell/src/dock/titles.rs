@@ -0,0 +1,158 @@
+use std::time::Duration;
+use std::time::Instant;
+
+use super::autohide::{Clock, SystemClock};
+use super::model::EventKind;
+
+/// Holds back name changes so each window's title is read at most once per
+/// delay, browsers rename their window on every tab switch and page load
+///
+/// A window's first name change starts its delay, the ones arriving before
+/// it ends are merged into it. [`TitleDebounce::due`] hands them back once
+/// [`TitleDebounce::deadline`] has passed.
+pub struct TitleDebounce<C: Clock = SystemClock> {
+  clock: C,
+  delay: Duration,
+  // Windows with a name change held back and when it is due, oldest first
+  pending: Vec<(isize, Instant)>,
+}
+
+impl<C: Clock> TitleDebounce<C> {
+  pub fn new(delay: Duration, clock: C) -> Self {
+    Self {
+      clock,
+      delay,
+      pending: Vec::new(),
+    }
+  }
+
+  pub fn deadline(&self) -> Option<Instant> {
+    self.pending.iter().map(|(_, deadline)| *deadline).min()
+  }
+
+  /// Takes the name changes out of `events`, keeping the order of the rest
+  pub fn defer(&mut self, mut events: Vec<(EventKind, isize)>) -> Vec<(EventKind, isize)> {
+    let now = self.clock.now();
+
+    events.retain(|&(kind, hwnd)| {
+      if kind != EventKind::NameChange {
+        return true;
+      }
+
+      if !self.pending.iter().any(|(pending, _)| *pending == hwnd) {
+        self.pending.push((hwnd, now + self.delay));
+      }
+      false
+    });
+
+    events
+  }
+
+  /// Name changes whose delay has passed, in the order they first arrived
+  pub fn due(&mut self) -> Vec<(EventKind, isize)> {
+    let now = self.clock.now();
+    let mut due = Vec::new();
+
+    self.pending.retain(|&(hwnd, deadline)| {
+      if deadline > now {
+        return true;
+      }
+
+      due.push((EventKind::NameChange, hwnd));
+      false
+    });
+
+    due
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dock::fake_clock::FakeClock;
+
+  const DELAY: Duration = Duration::from_millis(250);
+
+  fn debounce() -> (TitleDebounce<FakeClock>, FakeClock) {
+    let clock = FakeClock::new();
+    (TitleDebounce::new(DELAY, clock.clone()), clock)
+  }
+
+  #[test]
+  fn other_events_go_through() {
+    let (mut titles, _) = debounce();
+    let events = vec![
+      (EventKind::Create, 1),
+      (EventKind::NameChange, 1),
+      (EventKind::Show, 1),
+    ];
+
+    assert_eq!(
+      titles.defer(events),
+      vec![(EventKind::Create, 1), (EventKind::Show, 1)]
+    );
+    assert_eq!(titles.due(), vec![]);
+  }
+
+  #[test]
+  fn name_changes_wait_for_the_delay() {
+    let (mut titles, clock) = debounce();
+
+    titles.defer(vec![(EventKind::NameChange, 1)]);
+    assert_eq!(titles.deadline(), Some(clock.now() + DELAY));
+
+    clock.advance(DELAY - Duration::from_millis(1));
+    assert_eq!(titles.due(), vec![]);
+
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(titles.due(), vec![(EventKind::NameChange, 1)]);
+    assert_eq!(titles.deadline(), None);
+    assert_eq!(titles.due(), vec![]);
+  }
+
+  #[test]
+  fn bursts_are_read_once() {
+    let (mut titles, clock) = debounce();
+
+    titles.defer(vec![(EventKind::NameChange, 1), (EventKind::NameChange, 1)]);
+    clock.advance(DELAY / 2);
+    titles.defer(vec![(EventKind::NameChange, 1)]);
+    // The delay runs from the first change of the burst
+    clock.advance(DELAY / 2);
+
+    assert_eq!(titles.due(), vec![(EventKind::NameChange, 1)]);
+  }
+
+  #[test]
+  fn windows_have_their_own_delay() {
+    let (mut titles, clock) = debounce();
+
+    titles.defer(vec![(EventKind::NameChange, 1)]);
+    clock.advance(DELAY / 2);
+    titles.defer(vec![(EventKind::NameChange, 2), (EventKind::NameChange, 3)]);
+    assert_eq!(titles.deadline(), Some(clock.now() + DELAY / 2));
+
+    clock.advance(DELAY / 2);
+    assert_eq!(titles.due(), vec![(EventKind::NameChange, 1)]);
+    assert_eq!(titles.deadline(), Some(clock.now() + DELAY / 2));
+
+    clock.advance(DELAY);
+    assert_eq!(
+      titles.due(),
+      vec![(EventKind::NameChange, 2), (EventKind::NameChange, 3)]
+    );
+  }
+
+  #[test]
+  fn changes_after_the_delay_start_a_new_one() {
+    let (mut titles, clock) = debounce();
+
+    titles.defer(vec![(EventKind::NameChange, 1)]);
+    clock.advance(DELAY);
+    titles.due();
+    titles.defer(vec![(EventKind::NameChange, 1)]);
+
+    assert_eq!(titles.due(), vec![]);
+    assert_eq!(titles.deadline(), Some(clock.now() + DELAY));
+  }
+}
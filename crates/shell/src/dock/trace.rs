@@ -0,0 +1,111 @@
+use std::fmt;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+
+use super::model::{reduce, Changes, Window, WindowEvent};
+
+#[derive(Debug)]
+pub enum TraceError {
+  // The trace could not be read or written
+  Io(io::Error),
+  // A line is not a window event
+  Json(serde_json::Error),
+}
+
+impl fmt::Display for TraceError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TraceError::Io(err) => write!(f, "{}", err),
+      TraceError::Json(err) => write!(f, "{}", err),
+    }
+  }
+}
+
+impl std::error::Error for TraceError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      TraceError::Io(err) => Some(err),
+      TraceError::Json(err) => Some(err),
+    }
+  }
+}
+
+impl From<io::Error> for TraceError {
+  fn from(err: io::Error) -> Self {
+    TraceError::Io(err)
+  }
+}
+
+impl From<serde_json::Error> for TraceError {
+  fn from(err: serde_json::Error) -> Self {
+    TraceError::Json(err)
+  }
+}
+
+/// Appends window events to a trace, one JSON object per line
+pub struct Recorder<W: Write> {
+  writer: W,
+}
+
+impl Recorder<File> {
+  /// Opens `path` for appending, creating it if needed
+  pub fn open(path: impl AsRef<Path>) -> Result<Self, TraceError> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(Self::new(file))
+  }
+}
+
+impl<W: Write> Recorder<W> {
+  pub fn new(writer: W) -> Self {
+    Self { writer }
+  }
+
+  pub fn record(&mut self, event: &WindowEvent) -> Result<(), TraceError> {
+    let line = serde_json::to_string(event)?;
+    // Written line by line so a crash keeps everything up to it
+    writeln!(self.writer, "{}", line)?;
+    self.writer.flush()?;
+    Ok(())
+  }
+}
+
+/// Reads the events of a trace, blank lines are skipped
+pub fn read_trace(reader: impl BufRead) -> Result<Vec<WindowEvent>, TraceError> {
+  let mut events = Vec::new();
+
+  for line in reader.lines() {
+    let line = line?;
+    if line.trim().is_empty() {
+      continue;
+    }
+    events.push(serde_json::from_str(&line)?);
+  }
+
+  Ok(events)
+}
+
+/// Feeds `events` to the dock model, starting without windows, and returns the
+/// resulting apps
+///
+/// Icons are never extracted, every app has an empty `buffer`.
+pub fn replay(events: &[WindowEvent]) -> Vec<Window> {
+  let mut apps = Vec::new();
+  let mut changes = Changes::default();
+
+  for event in events {
+    reduce(&mut apps, event, &mut changes);
+  }
+
+  apps
+}
+
+/// Replays the trace at `path`, see [`replay`]
+pub fn replay_file(path: impl AsRef<Path>) -> Result<Vec<Window>, TraceError> {
+  let events = read_trace(BufReader::new(File::open(path)?))?;
+  Ok(replay(&events))
+}
@@ -0,0 +1,334 @@
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::dock::{Bounds, Clock, SystemClock};
+
+// A new state has to hold this long before it is reported, games and video
+// players often resize or switch modes a few times on their way in and out
+const ENTER_DELAY: Duration = Duration::from_millis(250);
+const LEAVE_DELAY: Duration = Duration::from_millis(400);
+
+/// What the system says about the foreground window at one moment
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Signals {
+  // `SHQueryUserNotificationState` reports a fullscreen Direct3D app, a
+  // presentation or another fullscreen app
+  pub notification_state: bool,
+  // The foreground window covers its whole monitor
+  pub covers_monitor: bool,
+  // The foreground window is cloaked, e.g. on another virtual desktop
+  pub cloaked: bool,
+  // Last `ABN_FULLSCREENAPP` sent by the shell
+  pub shell: bool,
+}
+
+impl Signals {
+  /// Whether these signals alone point to a fullscreen app
+  ///
+  /// Any of the sources is enough, but a cloaked window is never shown and
+  /// can't be fullscreen.
+  pub fn is_fullscreen(&self) -> bool {
+    !self.cloaked && (self.covers_monitor || self.notification_state || self.shell)
+  }
+}
+
+/// Whether `window` covers all of `monitor`
+pub fn covers(window: Bounds, monitor: Bounds) -> bool {
+  monitor.width > 0
+    && monitor.height > 0
+    && window.x <= monitor.x
+    && window.y <= monitor.y
+    && window.right() >= monitor.right()
+    && window.bottom() >= monitor.bottom()
+}
+
+/// Turns sampled [`Signals`] into a steady fullscreen state
+///
+/// [`FullscreenDetector::observe`] and [`FullscreenDetector::tick`] return
+/// `Some(fullscreen)` when the reported state changes, after the new state
+/// was seen for its whole delay.
+pub struct FullscreenDetector<C: Clock = SystemClock> {
+  clock: C,
+  fullscreen: bool,
+  // State seen since and when it will be reported
+  pending: Option<(bool, Instant)>,
+}
+
+impl<C: Clock> FullscreenDetector<C> {
+  pub fn new(clock: C) -> Self {
+    Self {
+      clock,
+      fullscreen: false,
+      pending: None,
+    }
+  }
+
+  pub fn is_fullscreen(&self) -> bool {
+    self.fullscreen
+  }
+
+  pub fn deadline(&self) -> Option<Instant> {
+    self.pending.map(|(_, deadline)| deadline)
+  }
+
+  /// Feeds a new sample, a state flipping back before its delay is dropped
+  pub fn observe(&mut self, signals: Signals) -> Option<bool> {
+    let fullscreen = signals.is_fullscreen();
+    if fullscreen == self.fullscreen {
+      self.pending = None;
+      return None;
+    }
+
+    if !matches!(self.pending, Some((pending, _)) if pending == fullscreen) {
+      let delay = if fullscreen { ENTER_DELAY } else { LEAVE_DELAY };
+      self.pending = Some((fullscreen, self.clock.now() + delay));
+    }
+
+    self.tick()
+  }
+
+  /// Reports the pending state once its delay has elapsed
+  pub fn tick(&mut self) -> Option<bool> {
+    match self.pending {
+      Some((fullscreen, deadline)) if self.clock.now() >= deadline => {
+        self.pending = None;
+        self.fullscreen = fullscreen;
+        Some(fullscreen)
+      }
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dock::fake_clock::FakeClock;
+
+  const MS: Duration = Duration::from_millis(1);
+
+  const FULLSCREEN: Signals = Signals {
+    notification_state: false,
+    covers_monitor: true,
+    cloaked: false,
+    shell: false,
+  };
+  const WINDOWED: Signals = Signals {
+    notification_state: false,
+    covers_monitor: false,
+    cloaked: false,
+    shell: false,
+  };
+
+  const MONITOR: Bounds = Bounds {
+    x: 1920,
+    y: -200,
+    width: 2560,
+    height: 1440,
+  };
+
+  fn detector() -> (FullscreenDetector<FakeClock>, FakeClock) {
+    let clock = FakeClock::new();
+    (FullscreenDetector::new(clock.clone()), clock)
+  }
+
+  // A detector that already reported fullscreen
+  fn entered() -> (FullscreenDetector<FakeClock>, FakeClock) {
+    let (mut detector, clock) = detector();
+    detector.observe(FULLSCREEN);
+    clock.advance(ENTER_DELAY);
+    assert_eq!(detector.tick(), Some(true));
+    (detector, clock)
+  }
+
+  #[test]
+  fn enters_after_the_enter_delay() {
+    let (mut detector, clock) = detector();
+
+    assert_eq!(detector.observe(FULLSCREEN), None);
+    assert_eq!(detector.deadline(), Some(clock.now() + ENTER_DELAY));
+
+    clock.advance(ENTER_DELAY - MS);
+    assert_eq!(detector.tick(), None);
+    assert!(!detector.is_fullscreen());
+
+    clock.advance(MS);
+    assert_eq!(detector.tick(), Some(true));
+    assert!(detector.is_fullscreen());
+    assert_eq!(detector.deadline(), None);
+    assert_eq!(detector.tick(), None);
+  }
+
+  #[test]
+  fn leaves_after_the_leave_delay() {
+    let (mut detector, clock) = entered();
+
+    assert_eq!(detector.observe(WINDOWED), None);
+    assert_eq!(detector.deadline(), Some(clock.now() + LEAVE_DELAY));
+
+    clock.advance(LEAVE_DELAY - MS);
+    assert_eq!(detector.tick(), None);
+    assert!(detector.is_fullscreen());
+
+    clock.advance(MS);
+    // A sample arriving after the deadline reports it too
+    assert_eq!(detector.observe(WINDOWED), Some(false));
+    assert!(!detector.is_fullscreen());
+  }
+
+  #[test]
+  fn flipping_back_before_the_deadline_is_dropped() {
+    let (mut detector, clock) = detector();
+
+    detector.observe(FULLSCREEN);
+    clock.advance(ENTER_DELAY / 2);
+    assert_eq!(detector.observe(WINDOWED), None);
+    assert_eq!(detector.deadline(), None);
+
+    clock.advance(ENTER_DELAY);
+    assert_eq!(detector.tick(), None);
+    assert!(!detector.is_fullscreen());
+  }
+
+  #[test]
+  fn flipping_back_restarts_the_delay() {
+    let (mut detector, clock) = detector();
+
+    detector.observe(FULLSCREEN);
+    clock.advance(ENTER_DELAY / 2);
+    detector.observe(WINDOWED);
+    detector.observe(FULLSCREEN);
+
+    assert_eq!(detector.deadline(), Some(clock.now() + ENTER_DELAY));
+  }
+
+  #[test]
+  fn repeated_samples_keep_the_deadline() {
+    let (mut detector, clock) = detector();
+    detector.observe(FULLSCREEN);
+    let deadline = detector.deadline();
+
+    for _ in 0..4 {
+      clock.advance(ENTER_DELAY / 5);
+      assert_eq!(detector.observe(FULLSCREEN), None);
+      assert_eq!(detector.deadline(), deadline);
+    }
+
+    clock.advance(ENTER_DELAY / 5);
+    assert_eq!(detector.observe(FULLSCREEN), Some(true));
+  }
+
+  #[test]
+  fn any_signal_is_enough() {
+    for signals in [
+      Signals {
+        notification_state: true,
+        ..WINDOWED
+      },
+      Signals {
+        shell: true,
+        ..WINDOWED
+      },
+      FULLSCREEN,
+    ] {
+      assert!(signals.is_fullscreen(), "{:?}", signals);
+    }
+    assert!(!WINDOWED.is_fullscreen());
+  }
+
+  #[test]
+  fn cloaked_windows_are_never_fullscreen() {
+    let cloaked = Signals {
+      notification_state: true,
+      covers_monitor: true,
+      cloaked: true,
+      shell: true,
+    };
+    assert!(!cloaked.is_fullscreen());
+
+    let (mut detector, clock) = detector();
+    detector.observe(cloaked);
+    clock.advance(ENTER_DELAY * 2);
+    assert_eq!(detector.observe(cloaked), None);
+    assert!(!detector.is_fullscreen());
+
+    // A fullscreen window moved to another virtual desktop leaves
+    let (mut detector, clock) = entered();
+    detector.observe(cloaked);
+    clock.advance(LEAVE_DELAY);
+    assert_eq!(detector.tick(), Some(false));
+  }
+
+  #[test]
+  fn covers_an_offset_monitor() {
+    assert!(covers(MONITOR, MONITOR));
+    // Borderless windows often spill over by their frame
+    assert!(covers(
+      Bounds {
+        x: MONITOR.x - 8,
+        y: MONITOR.y - 8,
+        width: MONITOR.width + 16,
+        height: MONITOR.height + 16,
+      },
+      MONITOR
+    ));
+  }
+
+  #[test]
+  fn missing_any_edge_does_not_cover() {
+    let short = [
+      Bounds {
+        x: MONITOR.x + 1,
+        ..MONITOR
+      },
+      Bounds {
+        y: MONITOR.y + 1,
+        ..MONITOR
+      },
+      Bounds {
+        width: MONITOR.width - 1,
+        ..MONITOR
+      },
+      Bounds {
+        height: MONITOR.height - 1,
+        ..MONITOR
+      },
+      // The same size on the primary monitor
+      Bounds {
+        x: 0,
+        y: 0,
+        ..MONITOR
+      },
+    ];
+
+    for window in short {
+      assert!(!covers(window, MONITOR), "{:?}", window);
+    }
+  }
+
+  #[test]
+  fn zero_sized_monitors_are_never_covered() {
+    let window = Bounds {
+      x: -100,
+      y: -100,
+      width: 10_000,
+      height: 10_000,
+    };
+
+    assert!(!covers(window, Bounds::default()));
+    assert!(!covers(
+      window,
+      Bounds {
+        width: 0,
+        ..MONITOR
+      }
+    ));
+    assert!(!covers(
+      window,
+      Bounds {
+        height: 0,
+        ..MONITOR
+      }
+    ));
+  }
+}
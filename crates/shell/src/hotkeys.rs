@@ -0,0 +1,5 @@
+mod accelerator;
+mod binding;
+
+pub use accelerator::*;
+pub use binding::*;
@@ -0,0 +1,435 @@
+use std::fmt;
+use std::str::FromStr;
+
+// `MOD_*` flags of `RegisterHotKey`
+const MOD_ALT: u32 = 0x1;
+const MOD_CONTROL: u32 = 0x2;
+const MOD_SHIFT: u32 = 0x4;
+const MOD_WIN: u32 = 0x8;
+
+// Names accepted for each virtual-key code, the first one is displayed
+const KEYS: [(&[&str], u32); 31] = [
+  (&["Space"], 0x20),
+  (&["Tab"], 0x09),
+  (&["Enter", "Return"], 0x0D),
+  (&["Esc", "Escape"], 0x1B),
+  (&["Backspace"], 0x08),
+  (&["Delete", "Del"], 0x2E),
+  (&["Insert", "Ins"], 0x2D),
+  (&["Home"], 0x24),
+  (&["End"], 0x23),
+  (&["PageUp", "PgUp"], 0x21),
+  (&["PageDown", "PgDn"], 0x22),
+  (&["Left"], 0x25),
+  (&["Up"], 0x26),
+  (&["Right"], 0x27),
+  (&["Down"], 0x28),
+  (&["PrintScreen", "PrtSc"], 0x2C),
+  (&["Pause"], 0x13),
+  (&["Plus", "="], 0xBB),
+  (&["Minus", "-"], 0xBD),
+  (&[","], 0xBC),
+  (&["."], 0xBE),
+  (&[";"], 0xBA),
+  (&["/"], 0xBF),
+  (&["`"], 0xC0),
+  (&["["], 0xDB),
+  (&["\\"], 0xDC),
+  (&["]"], 0xDD),
+  (&["'"], 0xDE),
+  (&["VolumeMute"], 0xAD),
+  (&["VolumeDown"], 0xAE),
+  (&["VolumeUp"], 0xAF),
+];
+
+/// Modifier keys held with an accelerator's key
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+  pub ctrl: bool,
+  pub alt: bool,
+  pub shift: bool,
+  pub win: bool,
+}
+
+impl Modifiers {
+  /// `MOD_*` flags as `RegisterHotKey` takes them
+  pub fn flags(&self) -> u32 {
+    [
+      (self.alt, MOD_ALT),
+      (self.ctrl, MOD_CONTROL),
+      (self.shift, MOD_SHIFT),
+      (self.win, MOD_WIN),
+    ]
+    .iter()
+    .filter(|(held, _)| *held)
+    .fold(0, |flags, (_, flag)| flags | flag)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.flags() == 0
+  }
+
+  // Sets the modifier named `name`, `None` if it isn't one
+  fn add(&mut self, name: &str) -> Option<bool> {
+    let held = match name.to_ascii_lowercase().as_str() {
+      "ctrl" | "control" => &mut self.ctrl,
+      "alt" => &mut self.alt,
+      "shift" => &mut self.shift,
+      "win" | "super" | "meta" => &mut self.win,
+      _ => return None,
+    };
+
+    Some(!std::mem::replace(held, true))
+  }
+}
+
+impl fmt::Display for Modifiers {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let names = [
+      (self.ctrl, "Ctrl"),
+      (self.alt, "Alt"),
+      (self.shift, "Shift"),
+      (self.win, "Win"),
+    ]
+    .iter()
+    .filter(|(held, _)| *held)
+    .map(|(_, name)| *name)
+    .collect::<Vec<_>>();
+
+    write!(f, "{}", names.join("+"))
+  }
+}
+
+impl FromStr for Modifiers {
+  type Err = AcceleratorError;
+
+  /// Parses modifiers alone, e.g. `Ctrl+Alt`
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let mut modifiers = Modifiers::default();
+    for part in split(text)? {
+      match modifiers.add(part) {
+        Some(true) => {}
+        Some(false) => return Err(AcceleratorError::Repeated(part.to_string())),
+        None => return Err(AcceleratorError::UnknownModifier(part.to_string())),
+      }
+    }
+
+    Ok(modifiers)
+  }
+}
+
+/// A key and the modifiers held with it, e.g. `Win+Space`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+  pub modifiers: Modifiers,
+  // Virtual-key code
+  pub key: u32,
+}
+
+impl Accelerator {
+  pub fn new(modifiers: Modifiers, key: u32) -> Self {
+    Self { modifiers, key }
+  }
+}
+
+impl fmt::Display for Accelerator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if !self.modifiers.is_empty() {
+      write!(f, "{}+", self.modifiers)?;
+    }
+    write!(f, "{}", key_name(self.key))
+  }
+}
+
+impl FromStr for Accelerator {
+  type Err = AcceleratorError;
+
+  /// Parses `+` separated modifiers followed by a key, names are case
+  /// insensitive and `Plus` stands for the `+` key
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let parts = split(text)?;
+    let (key, modifiers) = parts.split_last().ok_or(AcceleratorError::Empty)?;
+
+    let mut held = Modifiers::default();
+    for part in modifiers {
+      match held.add(part) {
+        Some(true) => {}
+        Some(false) => return Err(AcceleratorError::Repeated(part.to_string())),
+        None if key_code(part).is_some() => {
+          return Err(AcceleratorError::MultipleKeys(text.to_string()))
+        }
+        None => return Err(AcceleratorError::UnknownModifier(part.to_string())),
+      }
+    }
+
+    match key_code(key) {
+      Some(code) => Ok(Accelerator::new(held, code)),
+      None if Modifiers::default().add(key).is_some() => {
+        Err(AcceleratorError::MissingKey(text.to_string()))
+      }
+      None => Err(AcceleratorError::UnknownKey(key.to_string())),
+    }
+  }
+}
+
+/// Why an accelerator couldn't be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AcceleratorError {
+  Empty,
+  UnknownKey(String),
+  UnknownModifier(String),
+  // The same modifier was given twice
+  Repeated(String),
+  // Only modifiers, e.g. `Ctrl+Alt`, or a `+` with nothing next to it
+  MissingKey(String),
+  MultipleKeys(String),
+}
+
+impl fmt::Display for AcceleratorError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AcceleratorError::Empty => write!(f, "Empty accelerator"),
+      AcceleratorError::UnknownKey(key) => write!(f, "Unknown key: {}", key),
+      AcceleratorError::UnknownModifier(modifier) => write!(f, "Unknown modifier: {}", modifier),
+      AcceleratorError::Repeated(modifier) => write!(f, "Repeated modifier: {}", modifier),
+      AcceleratorError::MissingKey(text) => write!(f, "No key after the modifiers: {}", text),
+      AcceleratorError::MultipleKeys(text) => write!(f, "More than one key: {}", text),
+    }
+  }
+}
+
+impl std::error::Error for AcceleratorError {}
+
+// Parts between `+`, a trailing `+` is the key itself as in `Ctrl++`
+fn split(text: &str) -> Result<Vec<&str>, AcceleratorError> {
+  let text = text.trim();
+  if text.is_empty() {
+    return Err(AcceleratorError::Empty);
+  }
+
+  let (text, plus) = match text.strip_suffix("++") {
+    Some(rest) => (rest, true),
+    None if text == "+" => ("", true),
+    None => (text, false),
+  };
+
+  let mut parts = if text.is_empty() {
+    Vec::new()
+  } else {
+    text.split('+').map(str::trim).collect::<Vec<_>>()
+  };
+  if parts.iter().any(|part| part.is_empty()) {
+    return Err(AcceleratorError::MissingKey(text.to_string()));
+  }
+  if plus {
+    parts.push("Plus");
+  }
+
+  Ok(parts)
+}
+
+/// Virtual-key code of the key named `name`
+pub fn key_code(name: &str) -> Option<u32> {
+  let upper = name.to_ascii_uppercase();
+  let mut chars = upper.chars();
+
+  match (chars.next(), chars.next()) {
+    // Letters and digits are their own codes
+    (Some(c), None) if c.is_ascii_alphanumeric() => return Some(c as u32),
+    _ => {}
+  }
+
+  if let Some(number) = upper.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()) {
+    return (1..=24).contains(&number).then_some(0x6F + number);
+  }
+  if let Some(digit) = upper
+    .strip_prefix("NUMPAD")
+    .and_then(|n| n.parse::<u32>().ok())
+  {
+    return (digit <= 9).then_some(0x60 + digit);
+  }
+
+  KEYS
+    .iter()
+    .find(|(names, _)| names.iter().any(|key| key.eq_ignore_ascii_case(name)))
+    .map(|(_, code)| *code)
+}
+
+/// Name of the virtual-key code `code`, as [`key_code`] reads it back
+pub fn key_name(code: u32) -> String {
+  match code {
+    0x30..=0x39 | 0x41..=0x5A => char::from_u32(code).unwrap_or('?').to_string(),
+    0x60..=0x69 => format!("Numpad{}", code - 0x60),
+    0x70..=0x87 => format!("F{}", code - 0x6F),
+    _ => KEYS.iter().find(|(_, key)| *key == code).map_or_else(
+      || format!("0x{:02X}", code),
+      |(names, _)| names[0].to_string(),
+    ),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(text: &str) -> Result<Accelerator, AcceleratorError> {
+    text.parse()
+  }
+
+  fn modifiers(ctrl: bool, alt: bool, shift: bool, win: bool) -> Modifiers {
+    Modifiers {
+      ctrl,
+      alt,
+      shift,
+      win,
+    }
+  }
+
+  #[test]
+  fn parses_modifiers_and_a_key() {
+    assert_eq!(
+      parse("Win+Alt+Space"),
+      Ok(Accelerator::new(modifiers(false, true, false, true), 0x20))
+    );
+    assert_eq!(
+      parse(" ctrl + SHIFT + tab "),
+      Ok(Accelerator::new(modifiers(true, false, true, false), 0x09))
+    );
+    assert_eq!(
+      parse("Q"),
+      Ok(Accelerator::new(Modifiers::default(), 'Q' as u32))
+    );
+  }
+
+  #[test]
+  fn a_trailing_plus_is_the_plus_key() {
+    let plus = key_code("Plus").unwrap();
+
+    assert_eq!(
+      parse("Ctrl++"),
+      Ok(Accelerator::new(modifiers(true, false, false, false), plus))
+    );
+    assert_eq!(parse("Ctrl++"), parse("Ctrl+Plus"));
+    assert_eq!(parse("+"), Ok(Accelerator::new(Modifiers::default(), plus)));
+  }
+
+  #[test]
+  fn a_plus_with_nothing_after_it_is_missing_its_key() {
+    assert_eq!(
+      parse("Ctrl+"),
+      Err(AcceleratorError::MissingKey("Ctrl+".into()))
+    );
+    assert_eq!(
+      parse("Ctrl++A"),
+      Err(AcceleratorError::MissingKey("Ctrl++A".into()))
+    );
+  }
+
+  #[test]
+  fn modifiers_alone_are_missing_their_key() {
+    assert_eq!(
+      parse("Ctrl+Alt"),
+      Err(AcceleratorError::MissingKey("Ctrl+Alt".into()))
+    );
+    assert_eq!(
+      parse("Win"),
+      Err(AcceleratorError::MissingKey("Win".into()))
+    );
+  }
+
+  #[test]
+  fn repeated_modifiers_are_rejected() {
+    assert_eq!(
+      parse("Ctrl+control+A"),
+      Err(AcceleratorError::Repeated("control".into()))
+    );
+    assert_eq!(
+      "Alt+Alt".parse::<Modifiers>(),
+      Err(AcceleratorError::Repeated("Alt".into()))
+    );
+  }
+
+  #[test]
+  fn other_mistakes_are_named() {
+    assert_eq!(parse(""), Err(AcceleratorError::Empty));
+    assert_eq!(parse("   "), Err(AcceleratorError::Empty));
+    assert_eq!(
+      parse("A+B"),
+      Err(AcceleratorError::MultipleKeys("A+B".into()))
+    );
+    assert_eq!(
+      parse("Hyper+A"),
+      Err(AcceleratorError::UnknownModifier("Hyper".into()))
+    );
+    assert_eq!(
+      parse("Ctrl+Nope"),
+      Err(AcceleratorError::UnknownKey("Nope".into()))
+    );
+  }
+
+  #[test]
+  fn function_and_numpad_keys_stop_at_their_last_key() {
+    assert_eq!(key_code("F1"), Some(0x70));
+    assert_eq!(key_code("f24"), Some(0x87));
+    assert_eq!(key_code("F0"), None);
+    assert_eq!(key_code("F25"), None);
+    assert_eq!(
+      parse("Alt+F25"),
+      Err(AcceleratorError::UnknownKey("F25".into()))
+    );
+
+    assert_eq!(key_code("Numpad0"), Some(0x60));
+    assert_eq!(key_code("numpad9"), Some(0x69));
+    assert_eq!(key_code("Numpad10"), None);
+  }
+
+  #[test]
+  fn key_names_read_back_as_their_codes() {
+    for code in 0..=0xFF {
+      if let Some(read) = key_code(&key_name(code)) {
+        assert_eq!(read, code, "{}", key_name(code));
+      }
+    }
+
+    for (names, code) in KEYS {
+      for name in names {
+        assert_eq!(key_code(name), Some(code), "{}", name);
+      }
+      assert_eq!(key_name(code), names[0]);
+    }
+    for code in ('0' as u32..='9' as u32).chain('A' as u32..='Z' as u32) {
+      assert_eq!(key_code(&key_name(code)), Some(code));
+    }
+    for code in (0x60..=0x69).chain(0x70..=0x87) {
+      assert_eq!(key_code(&key_name(code)), Some(code));
+    }
+    assert_eq!(key_name(0x07), "0x07");
+  }
+
+  #[test]
+  fn displayed_accelerators_parse_back() {
+    for text in [
+      "Win+Alt+Space",
+      "Ctrl++",
+      "+",
+      "Shift+Numpad3",
+      "Ctrl+Alt+Shift+Win+F12",
+    ] {
+      let accelerator = parse(text).unwrap();
+      assert_eq!(parse(&accelerator.to_string()), Ok(accelerator), "{}", text);
+    }
+
+    assert_eq!(parse("win+ctrl+a").unwrap().to_string(), "Ctrl+Win+A");
+  }
+
+  #[test]
+  fn flags_combine_the_held_modifiers() {
+    assert_eq!(Modifiers::default().flags(), 0);
+    assert!(Modifiers::default().is_empty());
+    assert_eq!(modifiers(true, true, true, true).flags(), 0xF);
+    assert_eq!(
+      modifiers(false, true, false, true).flags(),
+      MOD_ALT | MOD_WIN
+    );
+  }
+}
@@ -0,0 +1,323 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::settings::HotkeySettings;
+
+use super::accelerator::{Accelerator, Modifiers};
+
+// Dock items reachable with the number row
+const DOCK_ITEMS: u32 = 9;
+
+/// What a hotkey does
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "action", content = "index", rename_all = "snake_case")]
+pub enum HotkeyAction {
+  ToggleLauncher,
+  ToggleDock,
+  // Index into the dock's apps, from 0
+  FocusDockItem(usize),
+  // Opens the window switcher, or moves to its next window while it is open
+  ShowSwitcher,
+}
+
+impl fmt::Display for HotkeyAction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HotkeyAction::ToggleLauncher => write!(f, "launcher"),
+      HotkeyAction::ToggleDock => write!(f, "toggle_dock"),
+      HotkeyAction::FocusDockItem(index) => write!(f, "focus_dock_item {}", index + 1),
+      HotkeyAction::ShowSwitcher => write!(f, "switcher"),
+    }
+  }
+}
+
+/// An accelerator and the action it triggers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Binding {
+  pub accelerator: Accelerator,
+  pub action: HotkeyAction,
+}
+
+/// A hotkey from the settings that isn't active, sent to the frontend as
+/// `{ "error": "...", ... }`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "error", rename_all = "snake_case")]
+pub enum HotkeyError {
+  // The setting couldn't be parsed
+  Invalid {
+    setting: &'static str,
+    text: String,
+    reason: String,
+  },
+  // Two actions share an accelerator, the first one keeps it
+  Conflict {
+    accelerator: String,
+    kept: HotkeyAction,
+    dropped: HotkeyAction,
+  },
+  // Another program or the system already registered it
+  Unavailable {
+    accelerator: String,
+    action: HotkeyAction,
+    reason: String,
+  },
+}
+
+impl fmt::Display for HotkeyError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HotkeyError::Invalid {
+        setting,
+        text,
+        reason,
+      } => write!(f, "Invalid hotkey {} = {:?}: {}", setting, text, reason),
+      HotkeyError::Conflict {
+        accelerator,
+        kept,
+        dropped,
+      } => write!(
+        f,
+        "{} is bound to both {} and {}, {} is ignored",
+        accelerator, kept, dropped, dropped
+      ),
+      HotkeyError::Unavailable {
+        accelerator,
+        action,
+        reason,
+      } => write!(
+        f,
+        "Failed to register {} for {}: {}",
+        accelerator, action, reason
+      ),
+    }
+  }
+}
+
+impl std::error::Error for HotkeyError {}
+
+/// Parses the hotkeys in `settings` into bindings
+///
+/// Settings that don't parse are skipped, and an accelerator requested twice
+/// stays with the first action in the order launcher, switcher, dock, dock
+/// items. Both are returned as errors next to the bindings that remain.
+pub fn bindings(settings: &HotkeySettings) -> (Vec<Binding>, Vec<HotkeyError>) {
+  let mut errors = Vec::new();
+  let mut requested = Vec::new();
+
+  let enabled = |text: &Option<String>| text.clone().filter(|text| !text.trim().is_empty());
+  let mut invalid = |setting, text: String, reason: String| {
+    errors.push(HotkeyError::Invalid {
+      setting,
+      text,
+      reason,
+    })
+  };
+
+  for (setting, text, action) in [
+    ("launcher", &settings.launcher, HotkeyAction::ToggleLauncher),
+    ("switcher", &settings.switcher, HotkeyAction::ShowSwitcher),
+    (
+      "toggle_dock",
+      &settings.toggle_dock,
+      HotkeyAction::ToggleDock,
+    ),
+  ] {
+    if let Some(text) = enabled(text) {
+      match text.parse::<Accelerator>() {
+        Ok(accelerator) => requested.push(Binding {
+          accelerator,
+          action,
+        }),
+        Err(err) => invalid(setting, text, err.to_string()),
+      }
+    }
+  }
+
+  if let Some(text) = enabled(&settings.focus_dock_item) {
+    match text.parse::<Modifiers>() {
+      // Bare digits would be taken from every other program
+      Ok(modifiers) if modifiers.is_empty() => invalid(
+        "focus_dock_item",
+        text,
+        "Needs at least one modifier".into(),
+      ),
+      Ok(modifiers) => requested.extend((1..=DOCK_ITEMS).map(|digit| Binding {
+        accelerator: Accelerator::new(modifiers, '0' as u32 + digit),
+        action: HotkeyAction::FocusDockItem(digit as usize - 1),
+      })),
+      Err(err) => invalid("focus_dock_item", text, err.to_string()),
+    }
+  }
+
+  let mut bindings: Vec<Binding> = Vec::new();
+  for binding in requested {
+    match bindings
+      .iter()
+      .find(|bound| bound.accelerator == binding.accelerator)
+    {
+      Some(bound) => errors.push(HotkeyError::Conflict {
+        accelerator: binding.accelerator.to_string(),
+        kept: bound.action,
+        dropped: binding.action,
+      }),
+      None => bindings.push(binding),
+    }
+  }
+
+  (bindings, errors)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn settings(
+    launcher: Option<&str>,
+    switcher: Option<&str>,
+    toggle_dock: Option<&str>,
+    focus_dock_item: Option<&str>,
+  ) -> HotkeySettings {
+    HotkeySettings {
+      launcher: launcher.map(String::from),
+      switcher: switcher.map(String::from),
+      toggle_dock: toggle_dock.map(String::from),
+      focus_dock_item: focus_dock_item.map(String::from),
+    }
+  }
+
+  fn actions(bindings: &[Binding]) -> Vec<HotkeyAction> {
+    bindings.iter().map(|binding| binding.action).collect()
+  }
+
+  fn conflict(accelerator: &str, kept: HotkeyAction, dropped: HotkeyAction) -> HotkeyError {
+    HotkeyError::Conflict {
+      accelerator: accelerator.into(),
+      kept,
+      dropped,
+    }
+  }
+
+  #[test]
+  fn defaults_bind_without_errors() {
+    let (bindings, errors) = bindings(&HotkeySettings::default());
+
+    assert_eq!(errors, vec![]);
+    assert_eq!(bindings.len(), 2 + DOCK_ITEMS as usize);
+  }
+
+  #[test]
+  fn dock_items_are_bound_to_the_number_row() {
+    let (bindings, errors) = bindings(&settings(None, None, None, Some("Ctrl+Shift")));
+
+    assert_eq!(errors, vec![]);
+    assert_eq!(
+      actions(&bindings),
+      (0..9).map(HotkeyAction::FocusDockItem).collect::<Vec<_>>()
+    );
+    assert_eq!(bindings[0].accelerator.to_string(), "Ctrl+Shift+1");
+    assert_eq!(bindings[8].accelerator.to_string(), "Ctrl+Shift+9");
+  }
+
+  #[test]
+  fn shared_accelerators_stay_with_the_first_action() {
+    let (bindings, errors) = bindings(&settings(
+      Some("Alt+1"),
+      Some("Alt+1"),
+      Some("Alt+1"),
+      Some("Alt"),
+    ));
+
+    assert_eq!(bindings[0].action, HotkeyAction::ToggleLauncher);
+    assert_eq!(
+      errors,
+      vec![
+        conflict(
+          "Alt+1",
+          HotkeyAction::ToggleLauncher,
+          HotkeyAction::ShowSwitcher
+        ),
+        conflict(
+          "Alt+1",
+          HotkeyAction::ToggleLauncher,
+          HotkeyAction::ToggleDock
+        ),
+        conflict(
+          "Alt+1",
+          HotkeyAction::ToggleLauncher,
+          HotkeyAction::FocusDockItem(0)
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn conflicts_follow_launcher_switcher_dock_then_dock_items() {
+    let (_, errors) = bindings(&settings(None, Some("Win+D"), Some("Win+D"), None));
+    assert_eq!(
+      errors,
+      vec![conflict(
+        "Win+D",
+        HotkeyAction::ShowSwitcher,
+        HotkeyAction::ToggleDock
+      )]
+    );
+
+    let (bindings, errors) = bindings(&settings(None, None, Some("Alt+3"), Some("Alt")));
+    assert_eq!(
+      errors,
+      vec![conflict(
+        "Alt+3",
+        HotkeyAction::ToggleDock,
+        HotkeyAction::FocusDockItem(2)
+      )]
+    );
+    assert_eq!(bindings[0].action, HotkeyAction::ToggleDock);
+    assert!(!actions(&bindings).contains(&HotkeyAction::FocusDockItem(2)));
+    assert_eq!(bindings.len(), DOCK_ITEMS as usize);
+  }
+
+  #[test]
+  fn invalid_settings_are_skipped() {
+    let (bindings, errors) = bindings(&settings(Some("Win+Nope"), None, Some("Win+Alt+D"), None));
+
+    assert_eq!(actions(&bindings), vec![HotkeyAction::ToggleDock]);
+    assert_eq!(
+      errors,
+      vec![HotkeyError::Invalid {
+        setting: "launcher",
+        text: "Win+Nope".into(),
+        reason: "Unknown key: Nope".into(),
+      }]
+    );
+  }
+
+  #[test]
+  fn focus_dock_item_needs_modifiers_only() {
+    for text in ["1", "Alt+1"] {
+      let (bindings, errors) = bindings(&settings(None, None, None, Some(text)));
+
+      assert_eq!(bindings, vec![]);
+      assert!(
+        matches!(
+          &errors[..],
+          [HotkeyError::Invalid {
+            setting: "focus_dock_item",
+            ..
+          }]
+        ),
+        "{}: {:?}",
+        text,
+        errors
+      );
+    }
+  }
+
+  #[test]
+  fn blank_settings_are_disabled() {
+    let (bindings, errors) = bindings(&settings(Some(""), Some("  "), None, Some(" ")));
+
+    assert_eq!(bindings, vec![]);
+    assert_eq!(errors, vec![]);
+  }
+}
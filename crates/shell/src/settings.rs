@@ -0,0 +1,114 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DockMode {
+  AlwaysVisible,
+  #[default]
+  AutoHide,
+  // Only hide when a window overlaps the dock
+  IntelligentHide,
+  HideInFullscreen,
+  // Always visible, maximized windows are kept out of the dock's edge
+  ReserveSpace,
+}
+
+// Screen edge the dock is attached to
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DockEdge {
+  #[default]
+  Bottom,
+  Top,
+  Left,
+  Right,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DockAlignment {
+  Start,
+  #[default]
+  Center,
+  End,
+}
+
+// What happens when the apps don't fit along the edge
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DockOverflow {
+  #[default]
+  Scroll,
+  Shrink,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct DockSettings {
+  pub mode: DockMode,
+  pub show_delay_ms: u64,
+  pub hide_delay_ms: u64,
+  // Window events arriving within this delay are handled as one batch
+  pub coalesce_ms: u64,
+  pub edge: DockEdge,
+  pub alignment: DockAlignment,
+  pub overflow: DockOverflow,
+  pub tile_size: i32,
+  pub spacing: i32,
+  pub padding: i32,
+  // Height of the window previews shown over a hovered tile, 0 disables them
+  pub thumbnail_height: i32,
+  // Entries kept in each automatic and custom category of a jump list
+  pub jump_list_items: usize,
+  // Resolved window events are appended to this file as JSON lines, see
+  // `--replay` to turn a recording back into the dock's app list
+  pub trace_file: Option<String>,
+}
+
+impl Default for DockSettings {
+  fn default() -> Self {
+    Self {
+      mode: DockMode::default(),
+      show_delay_ms: 0,
+      hide_delay_ms: 3000,
+      coalesce_ms: 50,
+      edge: DockEdge::default(),
+      alignment: DockAlignment::default(),
+      overflow: DockOverflow::default(),
+      tile_size: 40,
+      spacing: 4,
+      padding: 5,
+      thumbnail_height: 160,
+      jump_list_items: 10,
+      trace_file: None,
+    }
+  }
+}
+
+// Global shortcuts written as `+` separated modifiers and a key, e.g.
+// `Win+Space` or `Win+Alt+D`, null disables one
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct HotkeySettings {
+  pub launcher: Option<String>,
+  // Window switcher in most recently used order, e.g. `Alt+Tab` to replace
+  // the system's. Releasing its modifiers switches to the selected window.
+  pub switcher: Option<String>,
+  pub toggle_dock: Option<String>,
+  // Modifiers held with 1-9 to focus the dock's Nth app, e.g. `Alt`
+  pub focus_dock_item: Option<String>,
+}
+
+impl Default for HotkeySettings {
+  fn default() -> Self {
+    Self {
+      // Alt+Space opens the window menu and Win+Space switches the input
+      // language, Ctrl+Alt is AltGr on some layouts so it is avoided too
+      launcher: Some("Win+Alt+Space".into()),
+      switcher: None,
+      toggle_dock: Some("Win+Alt+D".into()),
+      focus_dock_item: Some("Alt".into()),
+    }
+  }
+}
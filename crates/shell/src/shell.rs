@@ -0,0 +1,7 @@
+pub mod command_line;
+pub mod dock;
+pub mod fullscreen;
+pub mod hotkeys;
+pub mod settings;
+pub mod switcher;
+pub mod thumbnails;
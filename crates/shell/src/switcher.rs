@@ -0,0 +1,3 @@
+mod history;
+
+pub use history::*;
@@ -0,0 +1,169 @@
+// Windows remembered at most, the oldest are forgotten first
+const CAPACITY: usize = 256;
+
+/// Most recently used order of windows and of the apps they belong to,
+/// built from foreground changes
+///
+/// Apps are identified by their executable path, windows without one are
+/// only tracked as windows.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FocusHistory {
+  // Most recent first, with the app of each window
+  windows: Vec<(isize, String)>,
+  // Most recent first
+  apps: Vec<String>,
+}
+
+impl FocusHistory {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Moves `hwnd` and its `app` to the front
+  pub fn focused(&mut self, hwnd: isize, app: &str) {
+    self.windows.retain(|(window, _)| *window != hwnd);
+    self.windows.insert(0, (hwnd, app.to_string()));
+    self.windows.truncate(CAPACITY);
+
+    if !app.is_empty() {
+      self.apps.retain(|known| known != app);
+      self.apps.insert(0, app.to_string());
+    }
+    self.prune_apps();
+  }
+
+  /// Forgets every window not in `alive`
+  pub fn retain(&mut self, alive: &[isize]) {
+    self.windows.retain(|(window, _)| alive.contains(window));
+    self.prune_apps();
+  }
+
+  /// Windows, most recently focused first
+  pub fn windows(&self) -> Vec<isize> {
+    self.windows.iter().map(|(window, _)| *window).collect()
+  }
+
+  /// Apps, most recently focused first
+  pub fn apps(&self) -> &[String] {
+    &self.apps
+  }
+
+  /// Windows of `app`, most recently focused first
+  pub fn app_windows(&self, app: &str) -> Vec<isize> {
+    self
+      .windows
+      .iter()
+      .filter(|(_, known)| known == app)
+      .map(|(window, _)| *window)
+      .collect()
+  }
+
+  /// Sorts `items` most recently focused first, by the window `hwnd` returns
+  ///
+  /// Windows never focused keep their order after the others.
+  pub fn order<T>(&self, mut items: Vec<T>, hwnd: impl Fn(&T) -> isize) -> Vec<T> {
+    items.sort_by_key(|item| self.rank(hwnd(item)));
+    items
+  }
+
+  // Position in the history, unknown windows rank last
+  fn rank(&self, hwnd: isize) -> usize {
+    self
+      .windows
+      .iter()
+      .position(|(window, _)| *window == hwnd)
+      .unwrap_or(usize::MAX)
+  }
+
+  // Apps whose windows were all forgotten are dropped
+  fn prune_apps(&mut self) {
+    let windows = &self.windows;
+    self
+      .apps
+      .retain(|app| windows.iter().any(|(_, known)| known == app));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn history(focused: &[(isize, &str)]) -> FocusHistory {
+    let mut history = FocusHistory::new();
+    for (hwnd, app) in focused {
+      history.focused(*hwnd, app);
+    }
+    history
+  }
+
+  #[test]
+  fn focused_moves_to_the_front() {
+    let mut history = history(&[(1, "a.exe"), (2, "b.exe"), (3, "a.exe")]);
+    assert_eq!(history.windows(), vec![3, 2, 1]);
+    assert_eq!(history.apps(), ["a.exe", "b.exe"]);
+
+    history.focused(2, "b.exe");
+
+    assert_eq!(history.windows(), vec![2, 3, 1]);
+    assert_eq!(history.apps(), ["b.exe", "a.exe"]);
+  }
+
+  #[test]
+  fn windows_without_an_app_are_only_tracked_as_windows() {
+    let history = history(&[(1, "a.exe"), (2, "")]);
+
+    assert_eq!(history.windows(), vec![2, 1]);
+    assert_eq!(history.apps(), ["a.exe"]);
+  }
+
+  #[test]
+  fn oldest_windows_are_forgotten_past_capacity() {
+    let mut history = history(&[(0, "old.exe")]);
+    for hwnd in 1..=CAPACITY as isize {
+      history.focused(hwnd, "new.exe");
+    }
+
+    let windows = history.windows();
+    assert_eq!(windows.len(), CAPACITY);
+    assert_eq!(windows.first(), Some(&(CAPACITY as isize)));
+    assert_eq!(windows.last(), Some(&1));
+    // Its only window was forgotten
+    assert_eq!(history.apps(), ["new.exe"]);
+  }
+
+  #[test]
+  fn retain_forgets_closed_windows_and_their_apps() {
+    let mut history = history(&[(1, "a.exe"), (2, "b.exe"), (3, "a.exe")]);
+
+    history.retain(&[1, 2]);
+    assert_eq!(history.windows(), vec![2, 1]);
+    assert_eq!(history.apps(), ["a.exe", "b.exe"]);
+
+    history.retain(&[1]);
+    assert_eq!(history.windows(), vec![1]);
+    assert_eq!(history.apps(), ["a.exe"]);
+
+    history.retain(&[]);
+    assert_eq!(history, FocusHistory::new());
+  }
+
+  #[test]
+  fn app_windows_are_most_recent_first() {
+    let history = history(&[(1, "a.exe"), (2, "b.exe"), (3, "a.exe")]);
+
+    assert_eq!(history.app_windows("a.exe"), vec![3, 1]);
+    assert_eq!(history.app_windows("b.exe"), vec![2]);
+    assert_eq!(history.app_windows("c.exe"), Vec::<isize>::new());
+  }
+
+  #[test]
+  fn order_puts_unknown_windows_last_in_their_order() {
+    let history = history(&[(1, "a.exe"), (2, "b.exe")]);
+    let items = vec![(5, "e"), (1, "a"), (4, "d"), (2, "b"), (3, "c")];
+
+    assert_eq!(
+      history.order(items, |(hwnd, _)| *hwnd),
+      vec![(2, "b"), (1, "a"), (5, "e"), (4, "d"), (3, "c")]
+    );
+  }
+}
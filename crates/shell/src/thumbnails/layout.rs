@@ -0,0 +1,347 @@
+use serde::Serialize;
+
+use crate::settings::DockEdge;
+
+use crate::dock::Bounds;
+
+// Very wide or tall windows are letterboxed past these ratios
+const MAX_ASPECT: f64 = 2.0;
+const MIN_ASPECT: f64 = 0.5;
+
+/// Sizes used to lay out thumbnails, in physical pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThumbnailMetrics {
+  // Height of a thumbnail before it is shrunk to fit the monitor
+  pub height: i32,
+  // Between thumbnails, and between the popup and the dock
+  pub spacing: i32,
+  pub padding: i32,
+}
+
+/// A thumbnail in the popup, relative to the popup
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Thumbnail {
+  // Clickable area of the thumbnail
+  pub slot: Bounds,
+  // Where the window is drawn inside `slot`, keeping its aspect ratio
+  pub preview: Bounds,
+}
+
+/// Where the thumbnail popup goes and where each thumbnail is inside it
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ThumbnailLayout {
+  pub bounds: Bounds,
+  pub thumbnails: Vec<Thumbnail>,
+}
+
+/// Lays out thumbnails of windows sized `sources` next to the tile at `anchor`
+///
+/// The popup opens on the side of the tile facing away from `edge`, centered
+/// on the tile and kept inside `monitor`. Thumbnails shrink together when they
+/// don't fit.
+pub fn layout_thumbnails(
+  sources: &[(i32, i32)],
+  anchor: Bounds,
+  edge: DockEdge,
+  monitor: Bounds,
+  metrics: ThumbnailMetrics,
+) -> ThumbnailLayout {
+  let vertical = matches!(edge, DockEdge::Left | DockEdge::Right);
+  let padding = metrics.padding.max(0);
+  let spacing = metrics.spacing.max(0);
+  let previews = sources
+    .iter()
+    .map(|&(width, height)| match (width, height) {
+      (width, height) if width > 0 && height > 0 => width as f64 / height as f64,
+      _ => 1.0,
+    })
+    .collect::<Vec<_>>();
+  let aspects = previews
+    .iter()
+    .map(|aspect| aspect.clamp(MIN_ASPECT, MAX_ASPECT))
+    .collect::<Vec<_>>();
+
+  // Thumbnails share their height in a row and their width in a column, the
+  // other side follows the aspect ratio
+  let lengths = |size: f64| -> Vec<f64> {
+    aspects
+      .iter()
+      .map(|aspect| {
+        if vertical {
+          size / aspect
+        } else {
+          size * aspect
+        }
+      })
+      .collect()
+  };
+  let count = aspects.len() as i32;
+  let gaps = (count - 1).max(0) * spacing + 2 * padding;
+
+  let (monitor_length, room) = match edge {
+    DockEdge::Bottom => (monitor.width, anchor.y - monitor.y),
+    DockEdge::Top => (monitor.width, monitor.bottom() - anchor.bottom()),
+    DockEdge::Left => (monitor.height, monitor.right() - anchor.right()),
+    DockEdge::Right => (monitor.height, anchor.x - monitor.x),
+  };
+  let max_length = (monitor_length - 2 * spacing - gaps).max(0) as f64;
+  let max_size = (room - 2 * spacing - 2 * padding).max(0) as f64;
+
+  let mut size = (metrics.height.max(1) as f64).min(max_size);
+  let total = lengths(size).iter().sum::<f64>();
+  if total > max_length && total > 0.0 {
+    size *= max_length / total;
+  }
+
+  let size = size.floor() as i32;
+  let lengths = lengths(size as f64)
+    .into_iter()
+    .map(|length| length.floor() as i32)
+    .collect::<Vec<_>>();
+  let length = lengths.iter().sum::<i32>() + gaps;
+  let thickness = size + 2 * padding;
+
+  let (width, height) = if vertical {
+    (thickness, length)
+  } else {
+    (length, thickness)
+  };
+  let center = |start: i32, span: i32, size: i32, min: i32, max: i32| {
+    (start + (span - size) / 2).clamp(min, (max - size).max(min))
+  };
+  let bounds = match edge {
+    DockEdge::Bottom | DockEdge::Top => Bounds {
+      x: center(anchor.x, anchor.width, width, monitor.x, monitor.right()),
+      y: if edge == DockEdge::Bottom {
+        anchor.y - spacing - height
+      } else {
+        anchor.bottom() + spacing
+      },
+      width,
+      height,
+    },
+    DockEdge::Left | DockEdge::Right => Bounds {
+      x: if edge == DockEdge::Left {
+        anchor.right() + spacing
+      } else {
+        anchor.x - spacing - width
+      },
+      y: center(anchor.y, anchor.height, height, monitor.y, monitor.bottom()),
+      width,
+      height,
+    },
+  };
+
+  let mut along = padding;
+  let thumbnails = previews
+    .iter()
+    .zip(lengths)
+    .map(|(aspect, length)| {
+      let slot = if vertical {
+        Bounds {
+          x: padding,
+          y: along,
+          width: size,
+          height: length,
+        }
+      } else {
+        Bounds {
+          x: along,
+          y: padding,
+          width: length,
+          height: size,
+        }
+      };
+      along += length + spacing;
+
+      Thumbnail {
+        slot,
+        preview: fit(slot, *aspect),
+      }
+    })
+    .collect();
+
+  ThumbnailLayout { bounds, thumbnails }
+}
+
+// Largest rectangle of `aspect` centered in `slot`
+fn fit(slot: Bounds, aspect: f64) -> Bounds {
+  let (width, height) = if slot.width as f64 / slot.height.max(1) as f64 > aspect {
+    ((slot.height as f64 * aspect) as i32, slot.height)
+  } else {
+    (slot.width, (slot.width as f64 / aspect) as i32)
+  };
+
+  Bounds {
+    x: slot.x + (slot.width - width) / 2,
+    y: slot.y + (slot.height - height) / 2,
+    width,
+    height,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MONITOR: Bounds = Bounds {
+    x: 0,
+    y: 0,
+    width: 1920,
+    height: 1040,
+  };
+  const METRICS: ThumbnailMetrics = ThumbnailMetrics {
+    height: 160,
+    spacing: 8,
+    padding: 10,
+  };
+  // A 16:9 window
+  const WIDE: (i32, i32) = (1600, 900);
+
+  fn bounds(x: i32, y: i32, width: i32, height: i32) -> Bounds {
+    Bounds {
+      x,
+      y,
+      width,
+      height,
+    }
+  }
+
+  // A tile of a dock at the bottom, 50px from the bottom of `MONITOR`
+  fn tile(x: i32) -> Bounds {
+    bounds(x, 990, 50, 50)
+  }
+
+  #[test]
+  fn above_a_bottom_tile() {
+    let layout = layout_thumbnails(&[WIDE], tile(900), DockEdge::Bottom, MONITOR, METRICS);
+
+    // 160 * 16 / 9 wide, centered on the tile and 8px above it
+    assert_eq!(layout.bounds, bounds(773, 802, 304, 180));
+    assert_eq!(
+      layout.thumbnails,
+      vec![Thumbnail {
+        slot: bounds(10, 10, 284, 160),
+        preview: bounds(10, 10, 284, 159),
+      }]
+    );
+  }
+
+  #[test]
+  fn below_a_top_tile() {
+    let anchor = bounds(900, 5, 50, 50);
+    let layout = layout_thumbnails(&[WIDE], anchor, DockEdge::Top, MONITOR, METRICS);
+
+    assert_eq!(layout.bounds, bounds(773, 63, 304, 180));
+  }
+
+  #[test]
+  fn beside_side_tiles() {
+    let left = bounds(5, 500, 50, 50);
+    let layout = layout_thumbnails(&[WIDE, WIDE], left, DockEdge::Left, MONITOR, METRICS);
+
+    // Stacked, sharing their width
+    assert_eq!(layout.bounds, bounds(63, 421, 180, 208));
+    assert_eq!(layout.thumbnails[0].slot, bounds(10, 10, 160, 90));
+    assert_eq!(layout.thumbnails[1].slot, bounds(10, 108, 160, 90));
+
+    let right = bounds(1865, 500, 50, 50);
+    let layout = layout_thumbnails(&[WIDE, WIDE], right, DockEdge::Right, MONITOR, METRICS);
+    assert_eq!(layout.bounds, bounds(1677, 421, 180, 208));
+  }
+
+  #[test]
+  fn thumbnails_share_a_row() {
+    let sources = [WIDE, (1000, 1000), (800, 1200)];
+    let layout = layout_thumbnails(&sources, tile(900), DockEdge::Bottom, MONITOR, METRICS);
+
+    let slots = layout
+      .thumbnails
+      .iter()
+      .map(|thumbnail| thumbnail.slot)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      slots,
+      vec![
+        bounds(10, 10, 284, 160),
+        bounds(302, 10, 160, 160),
+        bounds(470, 10, 106, 160),
+      ]
+    );
+    assert_eq!(layout.bounds.width, 10 + 284 + 8 + 160 + 8 + 106 + 10);
+  }
+
+  #[test]
+  fn extreme_aspect_ratios_are_letterboxed() {
+    let sources = [(3840, 1080), (500, 1500)];
+    let layout = layout_thumbnails(&sources, tile(900), DockEdge::Bottom, MONITOR, METRICS);
+
+    // Slots stop at 2:1 and 1:2, the window keeps its own ratio inside
+    assert_eq!(
+      layout.thumbnails,
+      vec![
+        Thumbnail {
+          slot: bounds(10, 10, 320, 160),
+          preview: bounds(10, 45, 320, 90),
+        },
+        Thumbnail {
+          slot: bounds(338, 10, 80, 160),
+          preview: bounds(351, 10, 53, 160),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn windows_without_a_size_are_square() {
+    let layout = layout_thumbnails(&[(0, 0)], tile(900), DockEdge::Bottom, MONITOR, METRICS);
+
+    assert_eq!(layout.thumbnails[0].slot, bounds(10, 10, 160, 160));
+    assert_eq!(layout.thumbnails[0].preview, bounds(10, 10, 160, 160));
+  }
+
+  #[test]
+  fn too_many_to_fit_shrink_together() {
+    let sources = [WIDE; 10];
+    let layout = layout_thumbnails(&sources, tile(900), DockEdge::Bottom, MONITOR, METRICS);
+
+    assert!(layout
+      .thumbnails
+      .iter()
+      .all(|thumbnail| thumbnail.slot.width == 179 && thumbnail.slot.height == 101));
+    assert_eq!(layout.bounds.height, 101 + 2 * 10);
+    assert!(layout.bounds.x >= MONITOR.x && layout.bounds.right() <= MONITOR.right());
+  }
+
+  #[test]
+  fn short_room_lowers_the_thumbnails() {
+    let anchor = bounds(900, 100, 50, 50);
+    let layout = layout_thumbnails(&[WIDE], anchor, DockEdge::Bottom, MONITOR, METRICS);
+
+    // 100px above the tile, less the spacing on both sides and the padding
+    assert_eq!(layout.thumbnails[0].slot.height, 64);
+    assert_eq!(layout.bounds.y, MONITOR.y + 8);
+  }
+
+  #[test]
+  fn popups_stay_on_the_monitor() {
+    let start = layout_thumbnails(&[WIDE], tile(5), DockEdge::Bottom, MONITOR, METRICS);
+    let end = layout_thumbnails(&[WIDE], tile(1865), DockEdge::Bottom, MONITOR, METRICS);
+
+    assert_eq!(start.bounds.x, 0);
+    assert_eq!(end.bounds.x, 1920 - 304);
+
+    let left = bounds(5, 1000, 50, 40);
+    let layout = layout_thumbnails(&[WIDE; 3], left, DockEdge::Left, MONITOR, METRICS);
+    assert_eq!(layout.bounds.bottom(), MONITOR.bottom());
+  }
+
+  #[test]
+  fn secondary_monitors_clamp_to_their_own_edges() {
+    let monitor = bounds(1920, -200, 1280, 1024);
+    let anchor = bounds(1925, 774, 50, 50);
+    let layout = layout_thumbnails(&[WIDE], anchor, DockEdge::Bottom, monitor, METRICS);
+
+    assert_eq!(layout.bounds, bounds(1920, 586, 304, 180));
+  }
+}
@@ -0,0 +1,71 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use shell::dock::{read_trace, replay, replay_file, Recorder, Window};
+
+// Replays `traces/<name>.jsonl` and compares the apps with the ones in
+// `traces/<name>.expected.json`, the files `--replay` and `--expect` take
+fn check(name: &str) {
+  let traces = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/traces");
+  let apps = replay_file(traces.join(format!("{}.jsonl", name))).unwrap();
+  let expected = fs::read_to_string(traces.join(format!("{}.expected.json", name))).unwrap();
+  let expected: Vec<Window> = serde_json::from_str(&expected).unwrap();
+
+  assert_eq!(apps, expected);
+}
+
+#[test]
+fn startup_and_close() {
+  check("startup_and_close");
+}
+
+#[test]
+fn late_title() {
+  check("late_title");
+}
+
+#[test]
+fn splash_hides_behind_owner() {
+  check("splash_hides_behind_owner");
+}
+
+#[test]
+fn dialog_hides_behind_known_owner() {
+  check("dialog_hides_behind_known_owner");
+}
+
+#[test]
+fn attention() {
+  check("attention");
+}
+
+#[test]
+fn recorded_events_read_back() {
+  let traces = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/traces");
+  let trace = fs::read_to_string(traces.join("startup_and_close.jsonl")).unwrap();
+  let events = read_trace(Cursor::new(trace)).unwrap();
+
+  let mut recorded = Vec::new();
+  {
+    let mut recorder = Recorder::new(&mut recorded);
+    for event in &events {
+      recorder.record(event).unwrap();
+    }
+  }
+
+  assert_eq!(read_trace(Cursor::new(recorded)).unwrap(), events);
+}
+
+#[test]
+fn blank_lines_are_skipped() {
+  let events = read_trace(Cursor::new("\n  \n")).unwrap();
+
+  assert!(events.is_empty());
+  assert!(replay(&events).is_empty());
+}
+
+#[test]
+fn garbage_is_an_error() {
+  assert!(read_trace(Cursor::new("{\"kind\":\"resize\"}\n")).is_err());
+}
@@ -0,0 +1,404 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use util::{
+  activate_window, exe_path, get_ex_styles, is_real_window, monitor_of, monitors,
+  window_thread_process_id,
+};
+
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::E_ACCESSDENIED;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::Graphics::Dwm::DwmGetWindowAttribute;
+use windows::Win32::Graphics::Dwm::DWMWA_EXTENDED_FRAME_BOUNDS;
+use windows::Win32::System::Threading::OpenProcess;
+use windows::Win32::System::Threading::TerminateProcess;
+use windows::Win32::System::Threading::PROCESS_TERMINATE;
+use windows::Win32::UI::WindowsAndMessaging::EnumWindows;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+use windows::Win32::UI::WindowsAndMessaging::IsZoomed;
+use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::HWND_NOTOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOMOVE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOZORDER;
+use windows::Win32::UI::WindowsAndMessaging::SW_MAXIMIZE;
+use windows::Win32::UI::WindowsAndMessaging::SW_MINIMIZE;
+use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
+use windows::Win32::UI::WindowsAndMessaging::WM_CLOSE;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOPMOST;
+
+use crate::process;
+use crate::process::LaunchError;
+use crate::process::LaunchRequest;
+
+/// Why a window action failed, sent to the frontend as
+/// `{ "error": "...", "message": "..." }`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "error", content = "message", rename_all = "snake_case")]
+pub enum ActionError {
+  // The window was closed in the meantime
+  WindowGone,
+  // There is no other monitor to move to
+  NoMonitor,
+  AccessDenied(String),
+  Failed(String),
+}
+
+impl From<LaunchError> for ActionError {
+  fn from(err: LaunchError) -> Self {
+    match err {
+      LaunchError::AccessDenied(message) => ActionError::AccessDenied(message),
+      err => ActionError::Failed(err.to_string()),
+    }
+  }
+}
+
+impl From<windows::core::Error> for ActionError {
+  fn from(err: windows::core::Error) -> Self {
+    if err.code() == E_ACCESSDENIED {
+      ActionError::AccessDenied(err.message().to_string())
+    } else {
+      ActionError::Failed(err.message().to_string())
+    }
+  }
+}
+
+/// What a window action did
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActionOutcome {
+  // Windows the action was applied to
+  pub windows: Vec<isize>,
+  // Process started or ended by the action
+  pub pid: Option<u32>,
+  // Always-on-top state after a toggle
+  pub topmost: Option<bool>,
+}
+
+type ActionResult = Result<ActionOutcome, ActionError>;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapSide {
+  Left,
+  Right,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorDirection {
+  Next,
+  Previous,
+}
+
+fn window(hwnd: isize) -> Result<HWND, ActionError> {
+  let hwnd = HWND(hwnd);
+  if unsafe { IsWindow(hwnd) }.as_bool() {
+    Ok(hwnd)
+  } else {
+    Err(ActionError::WindowGone)
+  }
+}
+
+fn applied(hwnd: HWND) -> ActionResult {
+  Ok(ActionOutcome {
+    windows: vec![hwnd.0],
+    ..Default::default()
+  })
+}
+
+fn window_rect(hwnd: HWND) -> Result<RECT, ActionError> {
+  let mut rect = RECT::default();
+  unsafe { GetWindowRect(hwnd, &mut rect) }?;
+  Ok(rect)
+}
+
+// Invisible resize borders around the visible frame, added back when placing
+// a window so its visible edges land on the target
+fn frame_margins(hwnd: HWND) -> RECT {
+  let mut frame = RECT::default();
+  let Ok(rect) = window_rect(hwnd) else {
+    return RECT::default();
+  };
+
+  let queried = unsafe {
+    DwmGetWindowAttribute(
+      hwnd,
+      DWMWA_EXTENDED_FRAME_BOUNDS,
+      &mut frame as *mut _ as _,
+      std::mem::size_of::<RECT>() as u32,
+    )
+  };
+  if queried.is_err() {
+    return RECT::default();
+  }
+
+  RECT {
+    left: frame.left - rect.left,
+    top: frame.top - rect.top,
+    right: rect.right - frame.right,
+    bottom: rect.bottom - frame.bottom,
+  }
+}
+
+// Places the visible frame of `hwnd` on `target`
+fn place(hwnd: HWND, target: RECT) -> Result<(), ActionError> {
+  if unsafe { IsZoomed(hwnd) }.as_bool() || unsafe { IsIconic(hwnd) }.as_bool() {
+    unsafe { ShowWindow(hwnd, SW_RESTORE) };
+  }
+
+  let margins = frame_margins(hwnd);
+  unsafe {
+    SetWindowPos(
+      hwnd,
+      HWND::default(),
+      target.left - margins.left,
+      target.top - margins.top,
+      target.right - target.left + margins.left + margins.right,
+      target.bottom - target.top + margins.top + margins.bottom,
+      SWP_NOZORDER | SWP_NOACTIVATE,
+    )
+  }?;
+
+  Ok(())
+}
+
+// Top-level windows of the executable that owns `hwnd`, `hwnd` included
+fn app_windows(hwnd: HWND) -> Vec<HWND> {
+  unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let windows = &mut *(lparam.0 as *mut Vec<HWND>);
+    if is_real_window(hwnd, false) {
+      windows.push(hwnd);
+    }
+    true.into()
+  }
+
+  let Ok(path) = exe_path(hwnd) else {
+    return vec![hwnd];
+  };
+
+  let mut windows: Vec<HWND> = Vec::new();
+  unsafe { EnumWindows(Some(collect), LPARAM(&mut windows as *mut _ as isize)) }
+    .unwrap_or_else(|err| eprintln!("Failed to enumerate windows: {}", err));
+
+  let mut windows = windows
+    .into_iter()
+    .filter(|window| *window != hwnd && exe_path(*window).is_ok_and(|other| other == path))
+    .collect::<Vec<_>>();
+  windows.insert(0, hwnd);
+  windows
+}
+
+/// Brings the window to the front, restoring it when minimized
+pub fn activate(hwnd: isize) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  activate_window(hwnd);
+  applied(hwnd)
+}
+
+/// Asks the window to close, it may still prompt the user
+#[tauri::command]
+pub fn close_window(hwnd: isize) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  unsafe { PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)) }?;
+  applied(hwnd)
+}
+
+/// Terminates the process owning the window without asking
+#[tauri::command]
+pub fn kill_window_process(hwnd: isize) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  let (pid, _) = window_thread_process_id(hwnd);
+
+  // Killing Explorer takes the shell down with the window
+  if pid == std::process::id() || exe_path(hwnd).is_ok_and(|path| path.ends_with("explorer.exe")) {
+    return Err(ActionError::AccessDenied(
+      "Refusing to kill a shell process".into(),
+    ));
+  }
+
+  unsafe {
+    let process = OpenProcess(PROCESS_TERMINATE, false, pid)?;
+    let terminated = TerminateProcess(process, 1);
+    let _ = CloseHandle(process);
+    terminated?;
+  }
+
+  Ok(ActionOutcome {
+    windows: vec![hwnd.0],
+    pid: Some(pid),
+    ..Default::default()
+  })
+}
+
+#[tauri::command]
+pub fn maximize_window(hwnd: isize) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  unsafe { ShowWindow(hwnd, SW_MAXIMIZE) };
+  applied(hwnd)
+}
+
+#[tauri::command]
+pub fn restore_window(hwnd: isize) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  unsafe { ShowWindow(hwnd, SW_RESTORE) };
+  applied(hwnd)
+}
+
+/// Minimizes every window of the app owning the window
+#[tauri::command]
+pub fn minimize_app(hwnd: isize) -> ActionResult {
+  let windows = app_windows(window(hwnd)?);
+  for window in &windows {
+    unsafe { ShowWindow(*window, SW_MINIMIZE) };
+  }
+
+  Ok(ActionOutcome {
+    windows: windows.iter().map(|window| window.0).collect(),
+    ..Default::default()
+  })
+}
+
+/// Moves the window to the next or previous monitor, left to right, keeping
+/// its relative position and maximized state
+#[tauri::command]
+pub fn move_to_monitor(hwnd: isize, direction: MonitorDirection) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  let mut monitors = monitors();
+  monitors.sort_by_key(|monitor| (monitor.bounds.left, monitor.bounds.top));
+
+  let current = monitor_of(hwnd).ok_or(ActionError::NoMonitor)?;
+  let index = monitors
+    .iter()
+    .position(|monitor| monitor.handle == current.handle)
+    .ok_or(ActionError::NoMonitor)?;
+  if monitors.len() < 2 {
+    return Err(ActionError::NoMonitor);
+  }
+
+  let target = match direction {
+    MonitorDirection::Next => monitors[(index + 1) % monitors.len()],
+    MonitorDirection::Previous => monitors[(index + monitors.len() - 1) % monitors.len()],
+  };
+
+  let maximized = unsafe { IsZoomed(hwnd) }.as_bool();
+  if maximized {
+    unsafe { ShowWindow(hwnd, SW_RESTORE) };
+  }
+
+  let (from, to) = (current.work_area, target.work_area);
+  let rect = window_rect(hwnd)?;
+  let scale = |value: i32, from_span: i32, to_span: i32| {
+    (value as i64 * to_span as i64 / from_span.max(1) as i64) as i32
+  };
+  let (from_width, from_height) = (from.right - from.left, from.bottom - from.top);
+  let (to_width, to_height) = (to.right - to.left, to.bottom - to.top);
+  let width = scale(rect.right - rect.left, from_width, to_width).min(to_width);
+  let height = scale(rect.bottom - rect.top, from_height, to_height).min(to_height);
+  let left = to.left + scale(rect.left - from.left, from_width, to_width);
+  let top = to.top + scale(rect.top - from.top, from_height, to_height);
+
+  unsafe {
+    SetWindowPos(
+      hwnd,
+      HWND::default(),
+      left.clamp(to.left, to.right - width),
+      top.clamp(to.top, to.bottom - height),
+      width,
+      height,
+      SWP_NOZORDER | SWP_NOACTIVATE,
+    )
+  }?;
+
+  if maximized {
+    unsafe { ShowWindow(hwnd, SW_MAXIMIZE) };
+  }
+
+  applied(hwnd)
+}
+
+/// Fills the left or right half of the window's monitor
+#[tauri::command]
+pub fn snap_window(hwnd: isize, side: SnapSide) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  let area = monitor_of(hwnd).ok_or(ActionError::NoMonitor)?.work_area;
+  let middle = area.left + (area.right - area.left) / 2;
+
+  place(
+    hwnd,
+    match side {
+      SnapSide::Left => RECT {
+        right: middle,
+        ..area
+      },
+      SnapSide::Right => RECT {
+        left: middle,
+        ..area
+      },
+    },
+  )?;
+
+  applied(hwnd)
+}
+
+#[tauri::command]
+pub fn toggle_always_on_top(hwnd: isize) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  let topmost = !get_ex_styles(hwnd).contains(WS_EX_TOPMOST);
+
+  unsafe {
+    SetWindowPos(
+      hwnd,
+      if topmost {
+        HWND_TOPMOST
+      } else {
+        HWND_NOTOPMOST
+      },
+      0,
+      0,
+      0,
+      0,
+      SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+    )
+  }?;
+
+  Ok(ActionOutcome {
+    windows: vec![hwnd.0],
+    topmost: Some(topmost),
+    ..Default::default()
+  })
+}
+
+/// Starts the window's executable again, most apps open a new window
+#[tauri::command]
+pub fn new_window(hwnd: isize) -> ActionResult {
+  let hwnd = window(hwnd)?;
+  let path = exe_path(hwnd).map_err(|err| ActionError::Failed(err.to_string()))?;
+  // Started from its own folder like a shortcut would, not from ours
+  let working_dir = Path::new(&path)
+    .parent()
+    .map(|parent| parent.to_string_lossy().into_owned());
+  let pid = process::launch(&LaunchRequest {
+    target: path,
+    working_dir,
+    ..Default::default()
+  })?;
+
+  Ok(ActionOutcome {
+    windows: vec![hwnd.0],
+    pid,
+    ..Default::default()
+  })
+}
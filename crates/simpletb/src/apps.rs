@@ -0,0 +1,219 @@
+use std::env;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Condvar;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::thread;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use icons::get_cached_icon;
+use launcher::{
+  scan_executables, scan_shortcuts, search, AppEntry, AppIndex, LaunchHistory, SearchResult,
+};
+use serde::Serialize;
+use util::config_dir;
+
+use windows::core::HSTRING;
+use windows::Management::Deployment::PackageManager;
+use windows::Win32::System::WinRT::RoInitialize;
+use windows::Win32::System::WinRT::RO_INIT_MULTITHREADED;
+
+use crate::process::{self, LaunchError, LaunchRequest};
+
+const DEFAULT_LIMIT: usize = 8;
+// When `PATHEXT` isn't set
+const DEFAULT_EXTENSIONS: [&str; 4] = ["exe", "com", "bat", "cmd"];
+
+static INDEX: LazyLock<Mutex<Option<AppIndex>>> = LazyLock::new(|| Mutex::new(None));
+// Notified when a scan stores its index
+static INDEX_READY: Condvar = Condvar::new();
+static SCANNING: AtomicBool = AtomicBool::new(false);
+static HISTORY: LazyLock<Mutex<LaunchHistory>> =
+  LazyLock::new(|| Mutex::new(LaunchHistory::load(&history_path())));
+
+/// A search result with its icon as PNG bytes
+#[derive(Serialize, Clone, Debug)]
+pub struct AppResult {
+  #[serde(flatten)]
+  result: SearchResult,
+  image: Vec<u8>,
+}
+
+fn history_path() -> PathBuf {
+  config_dir().join("launcher.json")
+}
+
+fn now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_secs())
+    .unwrap_or_default()
+}
+
+// The all users and the current user's Start Menu
+fn start_menu_dirs() -> Vec<PathBuf> {
+  ["ProgramData", "APPDATA"]
+    .iter()
+    .filter_map(|var| env::var_os(var))
+    .map(|dir| {
+      PathBuf::from(dir)
+        .join("Microsoft")
+        .join("Windows")
+        .join("Start Menu")
+        .join("Programs")
+    })
+    .collect()
+}
+
+fn path_dirs() -> Vec<PathBuf> {
+  env::var_os("PATH")
+    .map(|path| env::split_paths(&path).collect())
+    .unwrap_or_default()
+}
+
+fn path_extensions() -> Vec<String> {
+  env::var("PATHEXT")
+    .map(|extensions| {
+      extensions
+        .split(';')
+        .filter(|extension| !extension.is_empty())
+        .map(String::from)
+        .collect()
+    })
+    .unwrap_or_else(|_| DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect())
+}
+
+// Apps of the packages installed for the current user, frameworks have none
+fn packaged_apps() -> Vec<AppEntry> {
+  let packages =
+    PackageManager::new().and_then(|manager| manager.FindPackagesByUserSecurityId(&HSTRING::new()));
+  let Ok(packages) = packages else {
+    return Vec::new();
+  };
+
+  packages
+    .into_iter()
+    .filter(|package| !package.IsFramework().unwrap_or(true))
+    .filter_map(|package| package.GetAppListEntriesAsync().ok()?.get().ok())
+    .flat_map(|entries| entries.into_iter())
+    .filter_map(|entry| {
+      let app_user_model_id = entry.AppUserModelId().ok()?.to_string();
+      let name = entry.DisplayInfo().ok()?.DisplayName().ok()?.to_string();
+      Some(AppEntry::packaged(&app_user_model_id, &name))
+    })
+    .collect()
+}
+
+// Start Menu shortcuts win over packaged apps, which win over `PATH`
+fn build_index() -> AppIndex {
+  // WinRT needs the thread initialized, the index is built on its own thread
+  thread::spawn(|| {
+    unsafe { RoInitialize(RO_INIT_MULTITHREADED) }.unwrap_or_else(|_| ());
+
+    AppIndex::new(
+      scan_shortcuts(&start_menu_dirs())
+        .into_iter()
+        .chain(packaged_apps())
+        .chain(scan_executables(&path_dirs(), &path_extensions())),
+    )
+  })
+  .join()
+  .unwrap_or_default()
+}
+
+/// Rebuilds the app index in the background, searches use the previous one
+/// until it is done. Does nothing while a scan is already running.
+pub fn refresh() {
+  if SCANNING.swap(true, Ordering::SeqCst) {
+    return;
+  }
+
+  thread::spawn(|| {
+    let index = build_index();
+    *INDEX.lock().unwrap() = Some(index);
+    SCANNING.store(false, Ordering::SeqCst);
+    INDEX_READY.notify_all();
+  });
+}
+
+// Waits for the first scan without holding the lock, so requests made before
+// it finishes share it instead of each scanning again
+fn with_index<T>(f: impl FnOnce(&AppIndex) -> T) -> T {
+  let index = INDEX.lock().unwrap();
+  if index.is_none() {
+    refresh();
+  }
+
+  let index = INDEX_READY
+    .wait_while(index, |index| index.is_none())
+    .unwrap();
+  f(index.as_ref().unwrap())
+}
+
+#[tauri::command]
+pub async fn search_apps(query: String, limit: Option<usize>) -> Vec<AppResult> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let history = HISTORY.lock().unwrap().clone();
+    let results = with_index(|index| {
+      search(
+        index,
+        &history,
+        &query,
+        now(),
+        limit.unwrap_or(DEFAULT_LIMIT),
+      )
+    });
+
+    results
+      .into_iter()
+      .map(|result| AppResult {
+        image: get_cached_icon(&result.app.icon).unwrap_or_default(),
+        result,
+      })
+      .collect()
+  })
+  .await
+  .unwrap_or_default()
+}
+
+/// Launches the indexed app `id` and counts the launch towards its rank
+#[tauri::command]
+pub async fn launch_app(id: String) -> Result<Option<u32>, LaunchError> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let app = with_index(|index| index.get(&id).cloned())
+      .ok_or_else(|| LaunchError::NotFound(format!("No app {}", id)))?;
+
+    let pid = process::launch(&LaunchRequest {
+      target: app.path,
+      ..Default::default()
+    })?;
+
+    let mut history = HISTORY.lock().unwrap();
+    history.record(&app.id, now());
+    history
+      .save(&history_path())
+      .unwrap_or_else(|err| eprintln!("Failed to save launch history: {}", err));
+
+    Ok(pid)
+  })
+  .await
+  .map_err(|err| LaunchError::Failed(err.to_string()))?
+}
+
+#[tauri::command]
+pub fn refresh_apps() {
+  refresh();
+}
+
+#[tauri::command]
+pub fn toggle_launcher() {
+  ui::launcher::toggle();
+}
+
+#[tauri::command]
+pub fn hide_launcher() {
+  ui::launcher::hide();
+}
@@ -1,31 +1,35 @@
-use std::{env, path::PathBuf, ptr::null_mut, thread};
+use std::path::PathBuf;
 
+use icons::get_icon_variant;
+use icons::IconVariant;
 use tauri::{
   window::{Effect, EffectsBuilder},
-  Manager,
+  Manager, PhysicalPosition,
 };
-use util::{ScreenGeometry, USER_SETTINGS};
+use ui::backdrops;
+use ui::backdrops::BackdropState;
+use ui::fullscreen;
+use ui::hotkeys::{self, HotkeyError};
+use ui::switcher::{self, RecentWindows};
+use ui::theme;
+use ui::theme::Theme;
+
+use util::{monitor_at, to_physical};
 
-use windows::core::PCSTR;
-use windows::core::PSTR;
-use windows::Win32::Foundation::CloseHandle;
 use windows::Win32::Foundation::HWND;
-use windows::Win32::System::Threading::CreateProcessA;
-use windows::Win32::System::Threading::WaitForInputIdle;
-use windows::Win32::System::Threading::CREATE_NEW_CONSOLE;
-use windows::Win32::System::Threading::INFINITE;
-use windows::Win32::System::Threading::PROCESS_INFORMATION;
-use windows::Win32::System::Threading::STARTUPINFOA;
 use windows::Win32::UI::Input::KeyboardAndMouse::SetFocus;
 use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
 use windows::Win32::UI::WindowsAndMessaging::GetWindowPlacement;
-use windows::Win32::UI::WindowsAndMessaging::MoveWindow;
 use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
 use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
 use windows::Win32::UI::WindowsAndMessaging::SW_MINIMIZE;
 use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
 use windows::Win32::UI::WindowsAndMessaging::WINDOWPLACEMENT;
 
+use crate::actions::{ActionError, ActionOutcome};
+use crate::menu::*;
+use crate::process::{self, LaunchError, LaunchRequest};
+
 #[tauri::command]
 pub fn show_window(hwnd: isize) {
   unsafe {
@@ -47,10 +51,55 @@ pub fn show_window(hwnd: isize) {
   }
 }
 
+#[tauri::command]
+pub fn icon_variant(path: String, variant: String) -> Result<Vec<u8>, String> {
+  let variant: IconVariant = variant.parse()?;
+  get_icon_variant(&path, &variant).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn backdrop_state(window: tauri::WebviewWindow) -> Option<BackdropState> {
+  backdrops::state(window.label())
+}
+
+#[tauri::command]
+pub fn get_theme() -> Theme {
+  theme::current()
+}
+
+/// Hotkeys from the settings that are invalid, conflicting or taken by
+/// another program
+#[tauri::command]
+pub fn hotkey_errors() -> Vec<HotkeyError> {
+  hotkeys::errors()
+}
+
+/// Whether a fullscreen app is in front, as the dock and the menubar see it
+#[tauri::command]
+pub fn is_fullscreen() -> bool {
+  fullscreen::is_fullscreen()
+}
+
+/// The dock's windows in most recently used order
+#[tauri::command]
+pub fn focus_history() -> RecentWindows {
+  switcher::recent_windows()
+}
+
+#[tauri::command]
+pub fn switch_window(hwnd: isize) {
+  switcher::switch_to(hwnd);
+}
+
+#[tauri::command]
+pub fn hide_switcher() {
+  switcher::hide();
+}
+
 #[tauri::command]
 pub async fn open_settings(app: tauri::AppHandle) {
   if app.get_webview_window("settings").is_none() {
-    tauri::WebviewWindowBuilder::new(
+    let window = tauri::WebviewWindowBuilder::new(
       &app,
       "settings",
       tauri::WebviewUrl::App("/crates/ui/src/displays/settings/index.html".into()),
@@ -59,94 +108,118 @@ pub async fn open_settings(app: tauri::AppHandle) {
     .resizable(false)
     .inner_size(450.0, 600.0)
     .transparent(true)
-    .effects(EffectsBuilder::new().effects([Effect::Mica]).build())
     .build()
     .expect("Failed to create settings window");
+
+    let hwnd = HWND(window.hwnd().unwrap().0);
+    backdrops::register("settings", hwnd, theme::current().backdrops("settings"));
   }
 }
 
+/// Opens the context menu of `target` at the screen point `x`, `y` in
+/// physical pixels
 #[tauri::command]
-pub async fn open_context(app: tauri::AppHandle, _x: i32, _y: i32) {
+pub async fn open_context(
+  app: tauri::AppHandle,
+  target: MenuTarget,
+  x: i32,
+  y: i32,
+) -> Result<(), ActionError> {
   if let Some(window) = app.get_webview_window("context") {
-    window.close().unwrap();
+    window.close().unwrap_or_else(|_| ());
   }
 
-  let screen = ScreenGeometry::new();
+  let menu = build_menu(&target);
+  let (width, height) = menu_size(&menu.items);
+  *CURRENT_MENU.lock().unwrap() = menu;
 
   let window = tauri::WebviewWindowBuilder::new(
     &app,
     "context",
-    tauri::WebviewUrl::App("/crates/ui/src/displays/settings/index.html".into()),
+    tauri::WebviewUrl::App(PathBuf::from("/#/context")),
   )
   .title("Context")
   .resizable(false)
-  .inner_size(220.0, 120.0)
+  .decorations(false)
+  .inner_size(width as f64, height as f64)
   .transparent(true)
   .always_on_top(true)
+  .skip_taskbar(true)
+  .visible(false)
   .effects(EffectsBuilder::new().effects([Effect::Mica]).build())
   .build()
-  .expect("Failed to create context window");
+  .map_err(|err| ActionError::Failed(err.to_string()))?;
 
-  unsafe {
-    let hwnd: HWND = HWND(window.hwnd().unwrap().0);
-    thread::spawn(move || {
-      MoveWindow(
-        hwnd,
-        (screen.width / 2) - (220 / 2),
-        screen.height - 51 - USER_SETTINGS.margin_bottom - 120,
-        220,
-        120,
-        true,
-      )
-      .unwrap();
-    });
+  let hwnd = match window.hwnd() {
+    Ok(hwnd) => HWND(hwnd.0),
+    Err(err) => {
+      window.close().unwrap_or_else(|_| ());
+      return Err(ActionError::Failed(err.to_string()));
+    }
+  };
+  let size = (to_physical(hwnd, width), to_physical(hwnd, height));
+  if let Some(monitor) = monitor_at(x, y) {
+    let (x, y) = position_menu((x, y), size, monitor.work_area);
+    window
+      .set_position(PhysicalPosition::new(x, y))
+      .unwrap_or_else(|_| ());
   }
+
+  window.show().unwrap_or_else(|_| ());
+  window.set_focus().unwrap_or_else(|_| ());
+  Ok(())
 }
 
+/// Items of the open context menu
 #[tauri::command]
-pub fn execute(commandline: String, mut applicationname: String) {
-  unsafe {
-    if applicationname.contains("%USERPROFILE%") {
-      let user_profile = env::var("HOME").unwrap_or_default();
-      applicationname = applicationname.replace("%USERPROFILE%", &user_profile);
-      if !PathBuf::from(&applicationname).exists() {
-        println!("File does not exist: {}", applicationname);
-        return;
-      }
-    }
+pub fn context_menu() -> Vec<MenuItem> {
+  CURRENT_MENU.lock().unwrap().items.clone()
+}
 
-    println!("Executing: {} {}", applicationname, commandline);
+/// Closes the context menu and runs the action of the item `id`
+#[tauri::command]
+pub async fn select_context_item(
+  app: tauri::AppHandle,
+  id: usize,
+) -> Result<ActionOutcome, ActionError> {
+  let action = std::mem::take(&mut *CURRENT_MENU.lock().unwrap())
+    .action(id)
+    .cloned();
+  close_context(app.clone());
 
-    let mut startup_info = STARTUPINFOA::default();
-    let mut process_info = PROCESS_INFORMATION::default();
+  let Some(action) = action else {
+    return Err(ActionError::Failed(format!("No menu item {}", id)));
+  };
+  // Launches can wait on the shell, e.g. for an elevation prompt
+  tauri::async_runtime::spawn_blocking(move || dispatch(&app, action))
+    .await
+    .map_err(|err| ActionError::Failed(err.to_string()))?
+}
 
-    if CreateProcessA(
-      if applicationname.is_empty() {
-        PCSTR(null_mut())
-      } else {
-        PCSTR(applicationname.as_ptr() as *const u8)
-      },
-      if commandline.is_empty() {
-        PSTR(null_mut())
-      } else {
-        PSTR(commandline.as_ptr() as *mut u8)
-      },
-      None,
-      None,
-      false,
-      CREATE_NEW_CONSOLE,
-      None,
-      None,
-      &mut startup_info,
-      &mut process_info,
-    )
-    .is_ok()
-    {
-      WaitForInputIdle(process_info.hProcess, INFINITE);
-      CloseHandle(process_info.hProcess).unwrap_or_else(|_| println!("Failed to close process"));
-      CloseHandle(process_info.hThread).unwrap_or_else(|_| println!("Failed to close thread"));
-    } else {
-      println!("Failed to create process");
-    }
+#[tauri::command]
+pub fn close_context(app: tauri::AppHandle) {
+  if let Some(window) = app.get_webview_window("context") {
+    window.close().unwrap_or_else(|_| ());
   }
 }
+
+/// Runs a command line or opens `applicationname`, see [`process::execute`]
+#[tauri::command]
+pub async fn execute(
+  commandline: String,
+  applicationname: String,
+) -> Result<Option<u32>, LaunchError> {
+  tauri::async_runtime::spawn_blocking(move || {
+    process::execute(&applicationname, &commandline, None)
+  })
+  .await
+  .map_err(|err| LaunchError::Failed(err.to_string()))?
+}
+
+/// Opens a program, document or URL, returning the id of the started process
+#[tauri::command]
+pub async fn launch(request: LaunchRequest) -> Result<Option<u32>, LaunchError> {
+  tauri::async_runtime::spawn_blocking(move || process::launch(&request))
+    .await
+    .map_err(|err| LaunchError::Failed(err.to_string()))?
+}
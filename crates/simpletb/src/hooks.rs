@@ -8,9 +8,7 @@ use regex::Regex;
 
 use tauri::Emitter;
 
-use icons::get_icon;
-use util::is_cursor_visible;
-use util::ScreenGeometry;
+use icons::get_cached_icon;
 use util::APP_HANDLE;
 
 use windows::Win32::Foundation::HWND;
@@ -23,7 +21,6 @@ use windows::Win32::UI::WindowsAndMessaging::WINEVENT_SKIPOWNPROCESS;
 
 static PREV_WINDOW: LazyLock<Mutex<ActiveWindow>> =
   LazyLock::new(|| Mutex::new(ActiveWindow::default()));
-static IS_FULLSCREEN: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
 
 #[derive(Clone, serde::Serialize)]
 struct Payload {
@@ -85,27 +82,19 @@ unsafe extern "system" fn win_event_hook_callback(
           .parse::<isize>()
           .unwrap();
 
-        let (width, height) = (active_window.position.width, active_window.position.height);
-        let screen = ScreenGeometry::new();
-        if width == screen.width as f64
-          && height == screen.height as f64
-          && !is_cursor_visible()
-          && !*IS_FULLSCREEN.lock().unwrap()
-        {
-          println!("{} {:?}", active_window.app_name, is_cursor_visible());
-          app_handle.emit("app-fullscreen", ()).unwrap_or_else(|_| ());
-        } else {
-          if active_window.app_name != "Windows Explorer" {
-            app_handle
-              .emit("app-not-fullscreen", ())
-              .unwrap_or_else(|_| ());
-          }
+        // Focus moving inside a window doesn't change the window order
+        if _event_id == EVENT_SYSTEM_FOREGROUND {
+          ui::switcher::focused(
+            active_window_hwnd,
+            &active_window.process_path.to_string_lossy(),
+          );
         }
 
         if active_window.app_name != PREV_WINDOW.lock().unwrap().app_name.as_str()
           && active_window.app_name != env!("CARGO_PKG_DESCRIPTION")
         {
-          let icon = get_icon(&active_window.process_path.to_str().ok_or("").unwrap()).unwrap();
+          let icon =
+            get_cached_icon(&active_window.process_path.to_str().ok_or("").unwrap()).unwrap();
 
           *PREV_WINDOW.lock().unwrap() = active_window.clone();
           app_handle
@@ -0,0 +1,117 @@
+use std::env;
+
+use jumplist::{
+  default_app_id, read_jump_list, JumpItem, JumpList, PROGRAM_FILES_X64, PROGRAM_FILES_X86, SYSTEM,
+  SYSTEM_X86, WINDOWS,
+};
+use util::{exe_path, recent_dir, USER_SETTINGS};
+
+use windows::core::HSTRING;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Storage::EnhancedStorage::PKEY_AppUserModel_ID;
+use windows::Win32::System::Com::CoTaskMemFree;
+use windows::Win32::System::Com::StructuredStorage::PropVariantClear;
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::Win32::UI::Shell::PropertiesSystem::PropVariantToStringAlloc;
+use windows::Win32::UI::Shell::PropertiesSystem::SHGetPropertyStoreForWindow;
+use windows::Win32::UI::Shell::SHLoadIndirectString;
+
+use crate::process::{self, LaunchError};
+
+// Longest indirect string (`@shell32.dll,-123`) resolved for titles
+const MAX_INDIRECT_LENGTH: usize = 512;
+
+/// AppUserModelID the taskbar groups `hwnd` under, the one the window sets or
+/// the one Windows derives from its executable
+pub fn window_app_id(hwnd: HWND) -> Option<String> {
+  explicit_app_id(hwnd).or_else(|| {
+    let path = exe_path(hwnd).ok().filter(|path| !path.is_empty())?;
+    Some(default_app_id(&path, &known_folders()))
+  })
+}
+
+fn explicit_app_id(hwnd: HWND) -> Option<String> {
+  unsafe {
+    let store: IPropertyStore = SHGetPropertyStoreForWindow(hwnd).ok()?;
+    let mut value = store.GetValue(&PKEY_AppUserModel_ID).ok()?;
+    let text = PropVariantToStringAlloc(&value);
+    PropVariantClear(&mut value).unwrap_or_else(|_| ());
+
+    let text = text.ok()?;
+    let app_id = text.to_string().ok();
+    CoTaskMemFree(Some(text.0 as *const _));
+    app_id.filter(|app_id| !app_id.is_empty())
+  }
+}
+
+// Folders replaced by their id in derived AppIDs. `ProgramFiles` points to
+// the x86 folder in 32-bit processes, `ProgramW6432` never does.
+fn known_folders() -> Vec<(String, &'static str)> {
+  let var = |name: &str| env::var(name).unwrap_or_default();
+  let windows = var("SystemRoot");
+
+  vec![
+    (var("ProgramW6432"), PROGRAM_FILES_X64),
+    (var("ProgramFiles(x86)"), PROGRAM_FILES_X86),
+    (format!("{}\\System32", windows), SYSTEM),
+    (format!("{}\\SysWOW64", windows), SYSTEM_X86),
+    (windows, WINDOWS),
+  ]
+}
+
+// Titles and category names can point into a resource, e.g. `@shell32.dll,-123`
+fn resolve_indirect(text: &mut String) {
+  if !text.starts_with('@') {
+    return;
+  }
+
+  let mut buffer = [0u16; MAX_INDIRECT_LENGTH];
+  if unsafe { SHLoadIndirectString(&HSTRING::from(text.as_str()), &mut buffer, None) }.is_ok() {
+    let length = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+    *text = String::from_utf16_lossy(&buffer[..length]);
+  }
+}
+
+/// Jump list of the app `hwnd` belongs to
+pub fn window_jump_list(hwnd: HWND) -> Result<JumpList, String> {
+  let app_id = window_app_id(hwnd).ok_or("Failed to get the window's AppUserModelID")?;
+  let recent_dir = recent_dir().ok_or("Failed to find the recent items folder")?;
+
+  let mut jump_list = read_jump_list(&recent_dir, &app_id, USER_SETTINGS.dock.jump_list_items)
+    .map_err(|err| err.to_string())?;
+
+  for category in jump_list.categories.iter_mut() {
+    resolve_indirect(&mut category.name);
+    for item in category.items.iter_mut() {
+      resolve_indirect(&mut item.title);
+    }
+  }
+
+  Ok(jump_list)
+}
+
+#[tauri::command]
+pub fn jump_list(hwnd: isize) -> Result<JumpList, String> {
+  window_jump_list(HWND(hwnd))
+}
+
+/// Opens a jump list entry of the app `hwnd` belongs to, documents open with
+/// that app
+pub fn open_item(hwnd: isize, item: &JumpItem) -> Result<Option<u32>, LaunchError> {
+  if item.separator {
+    return Err(LaunchError::Failed("Separators can't be opened".into()));
+  }
+
+  let app = exe_path(HWND(hwnd)).unwrap_or_default();
+  let (application, command_line) = item.command(&app);
+  let working_dir = Some(item.working_dir.as_str()).filter(|dir| !dir.is_empty());
+
+  process::execute(&application, &command_line, working_dir)
+}
+
+#[tauri::command]
+pub async fn open_jump_item(hwnd: isize, item: JumpItem) -> Result<Option<u32>, LaunchError> {
+  tauri::async_runtime::spawn_blocking(move || open_item(hwnd, &item))
+    .await
+    .map_err(|err| LaunchError::Failed(err.to_string()))?
+}
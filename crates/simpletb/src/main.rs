@@ -1,12 +1,47 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod actions;
+mod apps;
 mod commands;
 mod hooks;
+mod jumplists;
+mod menu;
+mod process;
 
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use actions::*;
+use apps::*;
 use commands::*;
+use jumplists::*;
 use util::APP_HANDLE;
 
 fn main() {
+  // `--restore-taskbar [--watch <pid>]` puts the taskbar back, after `pid` exits
+  let args = env::args().collect::<Vec<_>>();
+  if args.iter().any(|arg| arg == "--restore-taskbar") {
+    match args
+      .iter()
+      .position(|arg| arg == "--watch")
+      .and_then(|index| args.get(index + 1)?.parse().ok())
+    {
+      Some(pid) => util::restore_taskbar_after(pid),
+      None => util::restore_taskbar(),
+    }
+    return;
+  }
+
+  // `--replay <trace> [--expect <apps>]` prints the dock apps a trace ends with
+  if let Some(trace) = arg_value(&args, "--replay") {
+    replay(trace, arg_value(&args, "--expect"));
+    return;
+  }
+
+  util::install_taskbar_guard();
+  spawn_watchdog();
+
   // Initialize Tokio runtime
   let runtime = tokio::runtime::Runtime::new().unwrap();
 
@@ -18,12 +53,65 @@ fn main() {
 
   // Run Tauri application within the Tokio runtime
   runtime.block_on(async {
-    if let Err(err) = app_builder.run(tauri::generate_context!()) {
-      eprintln!("Error while running Tauri application: {:?}", err);
+    match app_builder.build(tauri::generate_context!()) {
+      // Appbars outlive the process in the shell, they must be removed on exit
+      Ok(app) => app.run(|_app_handle, event| {
+        if let tauri::RunEvent::Exit = event {
+          ui::kill();
+        }
+      }),
+      Err(err) => eprintln!("Error while running Tauri application: {:?}", err),
     }
   });
 }
 
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a String> {
+  let index = args.iter().position(|arg| arg == name)?;
+  args.get(index + 1)
+}
+
+// Exits with an error if the apps differ from the JSON array in `expected`
+fn replay(trace: &str, expected: Option<&String>) {
+  let apps = ui::replay_file(trace).unwrap_or_else(|err| {
+    eprintln!("Failed to replay {}: {}", trace, err);
+    std::process::exit(2);
+  });
+  println!(
+    "{}",
+    serde_json::to_string_pretty(&apps).unwrap_or_default()
+  );
+
+  let Some(expected) = expected else {
+    return;
+  };
+  let expected = fs::read_to_string(expected)
+    .map_err(util::AppError::from)
+    .and_then(|json| Ok(serde_json::from_str::<Vec<ui::Window>>(&json)?))
+    .unwrap_or_else(|err| {
+      eprintln!("Failed to read expected apps: {}", err);
+      std::process::exit(2);
+    });
+
+  if apps != expected {
+    eprintln!("Replayed apps differ from the expected ones");
+    std::process::exit(1);
+  }
+}
+
+// Restores the taskbar even if this process gets killed
+fn spawn_watchdog() {
+  let spawned = env::current_exe().and_then(|exe| {
+    Command::new(exe)
+      .args(["--restore-taskbar", "--watch"])
+      .arg(std::process::id().to_string())
+      .spawn()
+  });
+
+  if let Err(err) = spawned {
+    eprintln!("Failed to start taskbar watchdog: {}", err);
+  }
+}
+
 // Function to initialize Tauri application
 fn initialize_tauri_app() -> tauri::Builder<tauri::Wry> {
   tauri::Builder::default()
@@ -32,26 +120,50 @@ fn initialize_tauri_app() -> tauri::Builder<tauri::Wry> {
     .plugin(tauri_plugin_shell::init())
     .invoke_handler(tauri::generate_handler![
       execute,
+      launch,
+      search_apps,
+      launch_app,
+      refresh_apps,
+      toggle_launcher,
+      hide_launcher,
       show_window,
+      icon_variant,
+      backdrop_state,
+      get_theme,
+      hotkey_errors,
+      is_fullscreen,
+      focus_history,
+      switch_window,
+      hide_switcher,
       open_settings,
-      open_context
+      open_context,
+      context_menu,
+      select_context_item,
+      close_context,
+      jump_list,
+      open_jump_item,
+      close_window,
+      kill_window_process,
+      maximize_window,
+      restore_window,
+      minimize_app,
+      move_to_monitor,
+      snap_window,
+      toggle_always_on_top,
+      new_window
     ])
 }
 
 // Function to setup Tauri application
 fn setup_tauri_app(app_builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::Wry> {
-  app_builder
-    .setup(move |app| {
-      // Initialize app handle
-      *APP_HANDLE.lock().unwrap() = Some(app.handle().clone());
-
-      hooks::init();
-      ui::init();
-
-      Ok(())
-    })
-    .on_window_event(|_window, event| match event {
-      tauri::WindowEvent::Destroyed | tauri::WindowEvent::CloseRequested { .. } => ui::kill(),
-      _ => {}
-    })
+  app_builder.setup(move |app| {
+    // Initialize app handle
+    *APP_HANDLE.lock().unwrap() = Some(app.handle().clone());
+
+    hooks::init();
+    ui::init();
+    apps::refresh();
+
+    Ok(())
+  })
 }
@@ -0,0 +1,441 @@
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use icons::get_cached_icon;
+use jumplist::{CategoryKind, JumpItem};
+use util::{exe_path, get_ex_styles, get_window_text, monitors};
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::IsZoomed;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOPMOST;
+
+use crate::actions::*;
+use crate::commands::open_settings;
+use crate::jumplists::{open_item, window_jump_list};
+use crate::process;
+
+// Sizes the context route renders items at, in logical pixels
+pub const MENU_WIDTH: i32 = 240;
+const ITEM_HEIGHT: i32 = 28;
+const SEPARATOR_HEIGHT: i32 = 9;
+const MENU_PADDING: i32 = 4;
+
+// Menu currently open, selections are looked up in it
+pub static CURRENT_MENU: LazyLock<Mutex<Menu>> = LazyLock::new(|| Mutex::new(Menu::default()));
+
+/// What was right-clicked
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "target", rename_all = "snake_case")]
+pub enum MenuTarget {
+  DockTile {
+    hwnd: isize,
+  },
+  // A launcher button, same arguments as the `execute` command
+  PinnedApp {
+    applicationname: String,
+    commandline: String,
+  },
+  MenubarWidget {
+    widget: String,
+  },
+}
+
+/// A context menu entry as the context route renders it
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MenuItem {
+  Action {
+    // Sent back to `select_context_item`
+    id: usize,
+    label: String,
+    shortcut: Option<String>,
+    // PNG bytes
+    icon: Option<Vec<u8>>,
+    // Checkable items have a state, others have none
+    checked: Option<bool>,
+    enabled: bool,
+  },
+  Separator,
+  Submenu {
+    label: String,
+    items: Vec<MenuItem>,
+  },
+}
+
+/// What selecting an item does, dispatched by [`dispatch`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MenuAction {
+  Activate(isize),
+  NewWindow(isize),
+  Maximize(isize),
+  Restore(isize),
+  MinimizeApp(isize),
+  Snap(isize, SnapSide),
+  MoveToMonitor(isize, MonitorDirection),
+  ToggleAlwaysOnTop(isize),
+  OpenJumpItem(isize, JumpItem),
+  Close(isize),
+  Kill(isize),
+  Launch {
+    applicationname: String,
+    commandline: String,
+  },
+  OpenLocation(String),
+  // `ms-settings:` and other URIs Explorer opens
+  OpenUri(String),
+  OpenSettings,
+  Quit,
+}
+
+/// A menu and the actions its items stand for
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Menu {
+  pub items: Vec<MenuItem>,
+  actions: Vec<MenuAction>,
+}
+
+impl Menu {
+  /// The action of the item `id`, if it exists and is enabled
+  pub fn action(&self, id: usize) -> Option<&MenuAction> {
+    fn enabled(items: &[MenuItem], id: usize) -> bool {
+      items.iter().any(|item| match item {
+        MenuItem::Action {
+          id: item, enabled, ..
+        } => *item == id && *enabled,
+        MenuItem::Submenu { items, .. } => enabled(items, id),
+        MenuItem::Separator => false,
+      })
+    }
+
+    enabled(&self.items, id)
+      .then(|| self.actions.get(id))
+      .flatten()
+  }
+
+  fn add(&mut self, label: &str, action: MenuAction) -> Entry {
+    self.actions.push(action);
+    Entry(MenuItem::Action {
+      id: self.actions.len() - 1,
+      label: label.into(),
+      shortcut: None,
+      icon: None,
+      checked: None,
+      enabled: true,
+    })
+  }
+}
+
+// Builds up an action item
+struct Entry(MenuItem);
+
+impl Entry {
+  fn shortcut(mut self, value: &str) -> Self {
+    if let MenuItem::Action { shortcut, .. } = &mut self.0 {
+      *shortcut = Some(value.into());
+    }
+    self
+  }
+
+  fn icon(mut self, value: Vec<u8>) -> Self {
+    if let MenuItem::Action { icon, .. } = &mut self.0 {
+      *icon = (!value.is_empty()).then_some(value);
+    }
+    self
+  }
+
+  fn checked(mut self, value: bool) -> Self {
+    if let MenuItem::Action { checked, .. } = &mut self.0 {
+      *checked = Some(value);
+    }
+    self
+  }
+
+  fn enabled(mut self, value: bool) -> Self {
+    if let MenuItem::Action { enabled, .. } = &mut self.0 {
+      *enabled = value;
+    }
+    self
+  }
+}
+
+impl From<Entry> for MenuItem {
+  fn from(entry: Entry) -> Self {
+    entry.0
+  }
+}
+
+/// Builds the menu for `target` from the current state of its window or app
+pub fn build_menu(target: &MenuTarget) -> Menu {
+  let mut menu = Menu::default();
+
+  menu.items = match target {
+    MenuTarget::DockTile { hwnd } => {
+      let hwnd = *hwnd;
+      let window = HWND(hwnd);
+      let path = exe_path(window).unwrap_or_default();
+      let zoomed = unsafe { IsZoomed(window) }.as_bool();
+      let iconic = unsafe { IsIconic(window) }.as_bool();
+      let topmost = get_ex_styles(window).contains(WS_EX_TOPMOST);
+      let title = get_window_text(window);
+
+      let mut items = jump_list_items(&mut menu, hwnd);
+      items.extend([
+        menu
+          .add(&title, MenuAction::Activate(hwnd))
+          .icon(get_cached_icon(&path).unwrap_or_default())
+          .into(),
+        menu.add("New window", MenuAction::NewWindow(hwnd)).into(),
+        MenuItem::Separator,
+        menu
+          .add("Maximize", MenuAction::Maximize(hwnd))
+          .enabled(!zoomed)
+          .into(),
+        menu
+          .add("Restore", MenuAction::Restore(hwnd))
+          .enabled(zoomed || iconic)
+          .into(),
+        menu
+          .add("Minimize all windows", MenuAction::MinimizeApp(hwnd))
+          .into(),
+        MenuItem::Submenu {
+          label: "Snap".into(),
+          items: vec![
+            menu
+              .add("Left half", MenuAction::Snap(hwnd, SnapSide::Left))
+              .shortcut("Win+Left")
+              .into(),
+            menu
+              .add("Right half", MenuAction::Snap(hwnd, SnapSide::Right))
+              .shortcut("Win+Right")
+              .into(),
+          ],
+        },
+        MenuItem::Submenu {
+          label: "Move to monitor".into(),
+          items: {
+            let several = monitors().len() > 1;
+            vec![
+              menu
+                .add(
+                  "Next",
+                  MenuAction::MoveToMonitor(hwnd, MonitorDirection::Next),
+                )
+                .shortcut("Win+Shift+Right")
+                .enabled(several)
+                .into(),
+              menu
+                .add(
+                  "Previous",
+                  MenuAction::MoveToMonitor(hwnd, MonitorDirection::Previous),
+                )
+                .shortcut("Win+Shift+Left")
+                .enabled(several)
+                .into(),
+            ]
+          },
+        },
+        menu
+          .add("Always on top", MenuAction::ToggleAlwaysOnTop(hwnd))
+          .checked(topmost)
+          .into(),
+        MenuItem::Separator,
+        menu
+          .add("Close window", MenuAction::Close(hwnd))
+          .shortcut("Alt+F4")
+          .into(),
+        menu.add("Force quit", MenuAction::Kill(hwnd)).into(),
+      ]);
+      items
+    }
+    MenuTarget::PinnedApp {
+      applicationname,
+      commandline,
+    } => vec![
+      menu
+        .add(
+          "Open",
+          MenuAction::Launch {
+            applicationname: applicationname.clone(),
+            commandline: commandline.clone(),
+          },
+        )
+        .icon(get_cached_icon(&process::expand_environment(applicationname)).unwrap_or_default())
+        .into(),
+      menu
+        .add(
+          "Open file location",
+          MenuAction::OpenLocation(applicationname.clone()),
+        )
+        .enabled(!applicationname.is_empty())
+        .into(),
+    ],
+    MenuTarget::MenubarWidget { widget } => {
+      let mut items = Vec::new();
+      if widget == "date" || widget == "clock" {
+        items.push(
+          menu
+            .add(
+              "Adjust date and time",
+              MenuAction::OpenUri("ms-settings:dateandtime".into()),
+            )
+            .into(),
+        );
+        items.push(MenuItem::Separator);
+      }
+
+      items.push(menu.add("Settings", MenuAction::OpenSettings).into());
+      items.push(MenuItem::Separator);
+      items.push(menu.add("Quit simpletb", MenuAction::Quit).into());
+      items
+    }
+  };
+
+  menu
+}
+
+// Jump list of the tile's app, categories open as submenus and tasks are
+// listed directly like the taskbar does
+fn jump_list_items(menu: &mut Menu, hwnd: isize) -> Vec<MenuItem> {
+  let Ok(jump_list) = window_jump_list(HWND(hwnd)) else {
+    return Vec::new();
+  };
+
+  let mut items = Vec::new();
+  for category in jump_list.categories {
+    let entries = category
+      .items
+      .into_iter()
+      .map(|item| {
+        if item.separator {
+          return MenuItem::Separator;
+        }
+        let title = item.title.clone();
+        menu
+          .add(&title, MenuAction::OpenJumpItem(hwnd, item))
+          .into()
+      })
+      .collect::<Vec<_>>();
+
+    if category.kind == CategoryKind::Tasks {
+      items.extend(entries);
+    } else {
+      items.push(MenuItem::Submenu {
+        label: category.name,
+        items: entries,
+      });
+    }
+  }
+
+  if !items.is_empty() {
+    items.push(MenuItem::Separator);
+  }
+  items
+}
+
+/// Logical size of `items` as the context route draws them, submenus open in
+/// place of their parent so the tallest level sets the height
+pub fn menu_size(items: &[MenuItem]) -> (i32, i32) {
+  fn height(items: &[MenuItem], nested: bool) -> i32 {
+    let own = items
+      .iter()
+      .map(|item| match item {
+        MenuItem::Separator => SEPARATOR_HEIGHT,
+        _ => ITEM_HEIGHT,
+      })
+      .sum::<i32>()
+      // Submenus get a back item
+      + if nested { ITEM_HEIGHT } else { 0 };
+
+    items
+      .iter()
+      .filter_map(|item| match item {
+        MenuItem::Submenu { items, .. } => Some(height(items, true)),
+        _ => None,
+      })
+      .fold(own, i32::max)
+  }
+
+  (MENU_WIDTH, height(items, false) + 2 * MENU_PADDING)
+}
+
+/// Places a menu of `size` at the screen point `at`, opening left or up when
+/// there is no room and staying inside `work_area`
+pub fn position_menu(at: (i32, i32), size: (i32, i32), work_area: RECT) -> (i32, i32) {
+  let place = |point: i32, size: i32, start: i32, end: i32| {
+    let position = if point + size > end {
+      point - size
+    } else {
+      point
+    };
+    position.clamp(start, (end - size).max(start))
+  };
+
+  (
+    place(at.0, size.0, work_area.left, work_area.right),
+    place(at.1, size.1, work_area.top, work_area.bottom),
+  )
+}
+
+/// Runs the action of a selected item
+pub fn dispatch(app: &tauri::AppHandle, action: MenuAction) -> Result<ActionOutcome, ActionError> {
+  match action {
+    MenuAction::Activate(hwnd) => activate(hwnd),
+    MenuAction::NewWindow(hwnd) => new_window(hwnd),
+    MenuAction::Maximize(hwnd) => maximize_window(hwnd),
+    MenuAction::Restore(hwnd) => restore_window(hwnd),
+    MenuAction::MinimizeApp(hwnd) => minimize_app(hwnd),
+    MenuAction::Snap(hwnd, side) => snap_window(hwnd, side),
+    MenuAction::MoveToMonitor(hwnd, direction) => move_to_monitor(hwnd, direction),
+    MenuAction::ToggleAlwaysOnTop(hwnd) => toggle_always_on_top(hwnd),
+    MenuAction::OpenJumpItem(hwnd, item) => open_item(hwnd, &item)
+      .map(|pid| ActionOutcome {
+        pid,
+        ..Default::default()
+      })
+      .map_err(ActionError::from),
+    MenuAction::Close(hwnd) => close_window(hwnd),
+    MenuAction::Kill(hwnd) => kill_window_process(hwnd),
+    MenuAction::Launch {
+      applicationname,
+      commandline,
+    } => process::execute(&applicationname, &commandline, None)
+      .map(|pid| ActionOutcome {
+        pid,
+        ..Default::default()
+      })
+      .map_err(ActionError::from),
+    // Explorer parses its own command line, the path is quoted after the comma
+    MenuAction::OpenLocation(path) => process::spawn(
+      None,
+      &format!(
+        "explorer.exe /select,\"{}\"",
+        process::expand_environment(path.trim())
+      ),
+      None,
+    )
+    .map(|pid| ActionOutcome {
+      pid: Some(pid),
+      ..Default::default()
+    })
+    .map_err(ActionError::from),
+    MenuAction::OpenUri(uri) => process::shell_execute(&uri, None, None, process::Verb::Open)
+      .map(|pid| ActionOutcome {
+        pid,
+        ..Default::default()
+      })
+      .map_err(ActionError::from),
+    MenuAction::OpenSettings => {
+      tauri::async_runtime::spawn(open_settings(app.clone()));
+      Ok(ActionOutcome::default())
+    }
+    MenuAction::Quit => {
+      app.exit(0);
+      Ok(ActionOutcome::default())
+    }
+  }
+}
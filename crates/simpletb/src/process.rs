@@ -0,0 +1,303 @@
+use std::env;
+use std::fmt;
+use std::iter;
+use std::mem;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use shell::command_line::{command_line, expand_variables, quote_argument};
+
+use windows::core::PCWSTR;
+use windows::core::PWSTR;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::ERROR_ACCESS_DENIED;
+use windows::Win32::Foundation::ERROR_BAD_PATHNAME;
+use windows::Win32::Foundation::ERROR_CANCELLED;
+use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+use windows::Win32::Foundation::ERROR_NO_ASSOCIATION;
+use windows::Win32::Foundation::ERROR_PATH_NOT_FOUND;
+use windows::Win32::Foundation::E_ACCESSDENIED;
+use windows::Win32::System::Com::CoInitializeEx;
+use windows::Win32::System::Com::CoUninitialize;
+use windows::Win32::System::Com::COINIT_APARTMENTTHREADED;
+use windows::Win32::System::Com::COINIT_DISABLE_OLE1DDE;
+use windows::Win32::System::Threading::CreateProcessW;
+use windows::Win32::System::Threading::GetProcessId;
+use windows::Win32::System::Threading::CREATE_NEW_CONSOLE;
+use windows::Win32::System::Threading::PROCESS_INFORMATION;
+use windows::Win32::System::Threading::STARTUPINFOW;
+use windows::Win32::UI::Shell::ShellExecuteExW;
+use windows::Win32::UI::Shell::SEE_MASK_NOASYNC;
+use windows::Win32::UI::Shell::SEE_MASK_NOCLOSEPROCESS;
+use windows::Win32::UI::Shell::SHELLEXECUTEINFOW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+// Started with `CreateProcessW`, anything else goes through the shell
+const EXECUTABLES: [&str; 2] = ["exe", "com"];
+
+/// Shell verb used to open a target
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verb {
+  #[default]
+  Open,
+  // Asks for elevation
+  Runas,
+  Edit,
+}
+
+impl Verb {
+  fn name(&self) -> &'static str {
+    match self {
+      Verb::Open => "open",
+      Verb::Runas => "runas",
+      Verb::Edit => "edit",
+    }
+  }
+}
+
+/// A program, document, folder or URL to open
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct LaunchRequest {
+  // Environment variables are expanded, e.g. `%LOCALAPPDATA%\app.exe`
+  pub target: String,
+  // Quoted and joined by the launcher
+  pub arguments: Vec<String>,
+  pub working_dir: Option<String>,
+  pub verb: Verb,
+}
+
+/// Why a launch failed, sent to the frontend as
+/// `{ "error": "...", "message": "..." }`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "error", content = "message", rename_all = "snake_case")]
+pub enum LaunchError {
+  NotFound(String),
+  AccessDenied(String),
+  // The elevation prompt was dismissed
+  Cancelled,
+  // No app is registered for the document or verb
+  NoAssociation(String),
+  Failed(String),
+}
+
+impl fmt::Display for LaunchError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      LaunchError::NotFound(message)
+      | LaunchError::AccessDenied(message)
+      | LaunchError::NoAssociation(message)
+      | LaunchError::Failed(message) => write!(f, "{}", message),
+      LaunchError::Cancelled => write!(f, "The operation was canceled by the user"),
+    }
+  }
+}
+
+impl From<windows::core::Error> for LaunchError {
+  fn from(err: windows::core::Error) -> Self {
+    let message = err.message().to_string();
+    match err.code() {
+      code
+        if [
+          ERROR_FILE_NOT_FOUND,
+          ERROR_PATH_NOT_FOUND,
+          ERROR_BAD_PATHNAME,
+        ]
+        .iter()
+        .any(|error| code == error.to_hresult()) =>
+      {
+        LaunchError::NotFound(message)
+      }
+      code if code == ERROR_ACCESS_DENIED.to_hresult() || code == E_ACCESSDENIED => {
+        LaunchError::AccessDenied(message)
+      }
+      code if code == ERROR_CANCELLED.to_hresult() => LaunchError::Cancelled,
+      code if code == ERROR_NO_ASSOCIATION.to_hresult() => LaunchError::NoAssociation(message),
+      _ => LaunchError::Failed(message),
+    }
+  }
+}
+
+// NUL terminated UTF-16 for the wide-char APIs
+fn wide(text: &str) -> Vec<u16> {
+  text.encode_utf16().chain(iter::once(0)).collect()
+}
+
+fn optional_wide(text: Option<&str>) -> Option<Vec<u16>> {
+  text.filter(|text| !text.is_empty()).map(wide)
+}
+
+fn pcwstr(text: &Option<Vec<u16>>) -> PCWSTR {
+  text
+    .as_ref()
+    .map_or(PCWSTR::null(), |text| PCWSTR(text.as_ptr()))
+}
+
+/// Replaces `%VARIABLE%`s with their values, unknown variables are kept
+pub fn expand_environment(text: &str) -> String {
+  expand_variables(text, |name| env::var(name).ok())
+}
+
+fn is_executable(path: &str) -> bool {
+  Path::new(path)
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .is_some_and(|extension| {
+      EXECUTABLES
+        .iter()
+        .any(|executable| extension.eq_ignore_ascii_case(executable))
+    })
+}
+
+/// Starts a process with `CreateProcessW`, returning its id without waiting
+/// for it
+///
+/// Without `application` the program is the first token of `command_line`,
+/// searched for in `PATH`.
+pub fn spawn(
+  application: Option<&str>,
+  command_line: &str,
+  working_dir: Option<&str>,
+) -> Result<u32, LaunchError> {
+  let application = optional_wide(application);
+  let working_dir = optional_wide(working_dir);
+  // `CreateProcessW` may write to the command line
+  let mut command_line = wide(command_line);
+
+  let startup_info = STARTUPINFOW {
+    cb: mem::size_of::<STARTUPINFOW>() as u32,
+    ..Default::default()
+  };
+  let mut process_info = PROCESS_INFORMATION::default();
+
+  unsafe {
+    CreateProcessW(
+      pcwstr(&application),
+      PWSTR(command_line.as_mut_ptr()),
+      None,
+      None,
+      false,
+      CREATE_NEW_CONSOLE,
+      None,
+      pcwstr(&working_dir),
+      &startup_info,
+      &mut process_info,
+    )?;
+
+    CloseHandle(process_info.hProcess).unwrap_or_else(|_| ());
+    CloseHandle(process_info.hThread).unwrap_or_else(|_| ());
+  }
+
+  Ok(process_info.dwProcessId)
+}
+
+/// Opens `file` through the shell, returning the id of the process it
+/// started if there is one
+///
+/// Documents handed to a running instance, e.g. over DDE, have none.
+pub fn shell_execute(
+  file: &str,
+  parameters: Option<&str>,
+  working_dir: Option<&str>,
+  verb: Verb,
+) -> Result<Option<u32>, LaunchError> {
+  let file = wide(file);
+  let parameters = optional_wide(parameters);
+  let working_dir = optional_wide(working_dir);
+  let verb = wide(verb.name());
+
+  let mut info = SHELLEXECUTEINFOW {
+    cbSize: mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+    fMask: SEE_MASK_NOCLOSEPROCESS | SEE_MASK_NOASYNC,
+    lpVerb: PCWSTR(verb.as_ptr()),
+    lpFile: PCWSTR(file.as_ptr()),
+    lpParameters: pcwstr(&parameters),
+    lpDirectory: pcwstr(&working_dir),
+    nShow: SW_SHOWNORMAL.0,
+    ..Default::default()
+  };
+
+  unsafe {
+    // Shell extensions handling the verb may need COM
+    let initialized =
+      CoInitializeEx(None, COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE).is_ok();
+    let result = ShellExecuteExW(&mut info);
+    if initialized {
+      CoUninitialize();
+    }
+    result?;
+
+    if info.hProcess.is_invalid() {
+      return Ok(None);
+    }
+    let pid = GetProcessId(info.hProcess);
+    CloseHandle(info.hProcess).unwrap_or_else(|_| ());
+
+    Ok((pid != 0).then_some(pid))
+  }
+}
+
+/// Opens what `request` describes, executables are started directly and
+/// everything else, or any verb other than open, goes through the shell
+pub fn launch(request: &LaunchRequest) -> Result<Option<u32>, LaunchError> {
+  let target = expand_environment(request.target.trim());
+  if target.is_empty() {
+    return Err(LaunchError::NotFound("Nothing to launch".into()));
+  }
+  let working_dir = request.working_dir.as_deref().map(expand_environment);
+
+  if request.verb == Verb::Open && is_executable(&target) {
+    return spawn(
+      None,
+      &command_line(&target, &request.arguments),
+      working_dir.as_deref(),
+    )
+    .map(Some);
+  }
+
+  let parameters = request
+    .arguments
+    .iter()
+    .map(|argument| quote_argument(argument))
+    .collect::<Vec<_>>()
+    .join(" ");
+  shell_execute(
+    &target,
+    Some(&parameters),
+    working_dir.as_deref(),
+    request.verb,
+  )
+}
+
+/// Runs an already quoted `command_line`, the shape the `execute` command
+/// takes. A non-executable `application` is opened through the shell with
+/// `command_line` as its parameters.
+pub fn execute(
+  application: &str,
+  command_line: &str,
+  working_dir: Option<&str>,
+) -> Result<Option<u32>, LaunchError> {
+  let application = expand_environment(application.trim());
+  let command_line = expand_environment(command_line.trim());
+  let working_dir = working_dir.map(expand_environment);
+  let working_dir = working_dir.as_deref();
+
+  match (application.is_empty(), command_line.is_empty()) {
+    (true, true) => Err(LaunchError::NotFound("Nothing to launch".into())),
+    (true, false) => spawn(None, &command_line, working_dir).map(Some),
+    (false, _) if !is_executable(&application) => {
+      shell_execute(&application, Some(&command_line), working_dir, Verb::Open)
+    }
+    // The command line starts with the program, as `CreateProcessW` expects
+    (false, true) => spawn(
+      Some(&application),
+      &quote_argument(&application),
+      working_dir,
+    )
+    .map(Some),
+    (false, false) => spawn(Some(&application), &command_line, working_dir).map(Some),
+  }
+}
@@ -0,0 +1,144 @@
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::thread;
+
+use backdrop::set_backdrop;
+use backdrop::Backdrop;
+use backdrop::Capabilities;
+use tauri::Emitter;
+
+use util::subclass_window;
+use util::APP_HANDLE;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::PBT_APMPOWERSTATUSCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::WM_DWMCOMPOSITIONCHANGED;
+use windows::Win32::UI::WindowsAndMessaging::WM_POWERBROADCAST;
+use windows::Win32::UI::WindowsAndMessaging::WM_SETTINGCHANGE;
+
+// A window whose backdrop follows the system capabilities
+struct Surface {
+  label: String,
+  hwnd: HWND,
+  preferences: Vec<Backdrop>,
+  current: Backdrop,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct BackdropState {
+  pub backdrop: Backdrop,
+  pub capabilities: Capabilities,
+}
+
+static SURFACES: LazyLock<Mutex<Vec<Surface>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+static CAPABILITIES: LazyLock<Mutex<Capabilities>> =
+  LazyLock::new(|| Mutex::new(Capabilities::probe()));
+
+/// Re-evaluates every backdrop when one of the probed settings changes
+///
+/// `hwnd` only needs to be a top-level window to receive the broadcasts.
+pub fn watch(hwnd: HWND) {
+  subclass_window(
+    hwnd,
+    Box::new(|_, msg, wparam, _| {
+      match msg {
+        WM_SETTINGCHANGE | WM_DWMCOMPOSITIONCHANGED => {
+          thread::spawn(refresh);
+        }
+        WM_POWERBROADCAST if wparam.0 == PBT_APMPOWERSTATUSCHANGE as usize => {
+          thread::spawn(refresh);
+        }
+        _ => {}
+      }
+
+      None
+    }),
+  )
+  .unwrap_or_else(|err| eprintln!("Failed to watch backdrop settings: {}", err));
+}
+
+/// Applies the best supported backdrop of `preferences` to the window `label`
+pub fn register(label: &str, hwnd: HWND, preferences: Vec<Backdrop>) {
+  let current = CAPABILITIES.lock().unwrap().choose(&preferences);
+  apply(label, hwnd, current);
+
+  let mut surfaces = SURFACES.lock().unwrap();
+  surfaces.retain(|surface| surface.label != label);
+  surfaces.push(Surface {
+    label: label.to_string(),
+    hwnd,
+    preferences,
+    current,
+  });
+}
+
+/// Replaces the preferences of an already registered window
+pub fn set_preferences(label: &str, preferences: Vec<Backdrop>) {
+  let capabilities = *CAPABILITIES.lock().unwrap();
+
+  for surface in SURFACES.lock().unwrap().iter_mut() {
+    if surface.label != label {
+      continue;
+    }
+
+    surface.preferences = preferences.clone();
+    let backdrop = capabilities.choose(&surface.preferences);
+    if backdrop != surface.current {
+      surface.current = backdrop;
+      apply(&surface.label, surface.hwnd, backdrop);
+    }
+  }
+}
+
+/// Backdrop currently drawn behind the window `label`
+pub fn state(label: &str) -> Option<BackdropState> {
+  let backdrop = SURFACES
+    .lock()
+    .unwrap()
+    .iter()
+    .find(|surface| surface.label == label)
+    .map(|surface| surface.current)?;
+
+  Some(BackdropState {
+    backdrop,
+    capabilities: *CAPABILITIES.lock().unwrap(),
+  })
+}
+
+/// Probes the system again and switches the backdrops that are affected
+pub fn refresh() {
+  let capabilities = Capabilities::probe();
+  {
+    let mut current = CAPABILITIES.lock().unwrap();
+    if *current == capabilities {
+      return;
+    }
+    *current = capabilities;
+  }
+
+  for surface in SURFACES.lock().unwrap().iter_mut() {
+    let backdrop = capabilities.choose(&surface.preferences);
+    if backdrop != surface.current {
+      surface.current = backdrop;
+      apply(&surface.label, surface.hwnd, backdrop);
+    }
+  }
+}
+
+fn apply(label: &str, hwnd: HWND, backdrop: Backdrop) {
+  set_backdrop(hwnd, backdrop)
+    .unwrap_or_else(|err| eprintln!("Failed to set {} backdrop: {}", label, err));
+
+  if let Some(app_handle) = APP_HANDLE.lock().unwrap().as_ref() {
+    app_handle
+      .emit_to(
+        label,
+        "backdrop-changed",
+        BackdropState {
+          backdrop,
+          capabilities: *CAPABILITIES.lock().unwrap(),
+        },
+      )
+      .unwrap_or_else(|_| ());
+  }
+}
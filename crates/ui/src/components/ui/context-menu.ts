@@ -0,0 +1,20 @@
+import { MouseEvent } from "react";
+
+import { invoke } from "@tauri-apps/api/core";
+
+export type MenuTarget =
+  | { target: "dock_tile"; hwnd: number }
+  | { target: "pinned_app"; applicationname: string; commandline: string }
+  | { target: "menubar_widget"; widget: string };
+
+// The backend places the menu in physical screen pixels
+export async function openContextMenu(target: MenuTarget, e: MouseEvent) {
+  e.preventDefault();
+  e.stopPropagation();
+
+  await invoke("open_context", {
+    target,
+    x: Math.round(e.screenX * window.devicePixelRatio),
+    y: Math.round(e.screenY * window.devicePixelRatio),
+  }).catch((error) => console.error(error));
+}
@@ -1,6 +1,7 @@
 import { HTMLAttributes } from "react";
 
 import { invoke } from "@tauri-apps/api/core";
+import { openContextMenu } from "./context-menu";
 
 export default function ExecuteButton({
   applicationname = "",
@@ -17,7 +18,13 @@ export default function ExecuteButton({
         await invoke("execute", {
           applicationname,
           commandline,
-        })
+        }).catch((error) => console.error(error))
+      }
+      onContextMenu={(e) =>
+        openContextMenu(
+          { target: "pinned_app", applicationname, commandline },
+          e,
+        )
       }
       className="cursor-default hover:bg-neutral-800/40 px-1.5 py-0.5 rounded-md"
       {...props}
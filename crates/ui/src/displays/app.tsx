@@ -1,7 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect } from "react";
 import { HashRouter } from "react-router-dom";
 import Routes from "./router";
 
+type Theme = {
+  name: string;
+  dark: boolean;
+  accent: string;
+  colors: Record<string, string>;
+  surfaces: Record<string, { radius: number }>;
+};
+
+// Exposes the theme tokens as CSS variables, e.g. `tile.hover` -> `--theme-tile-hover`
+function applyTheme(theme: Theme) {
+  const root = document.documentElement;
+  root.dataset.theme = theme.dark ? "dark" : "light";
+
+  for (const [token, color] of Object.entries(theme.colors)) {
+    root.style.setProperty(`--theme-${token.replace(/\./g, "-")}`, color);
+  }
+
+  for (const [surface, { radius }] of Object.entries(theme.surfaces)) {
+    root.style.setProperty(`--theme-${surface}-radius`, `${radius}px`);
+  }
+}
+
 export function App() {
+  useEffect(() => {
+    invoke<Theme>("get_theme").then(applyTheme);
+    listen<Theme>("theme-changed", (event) => applyTheme(event.payload));
+  }, []);
+
   return (
     <HashRouter>
       <Routes />
@@ -1,9 +1,13 @@
 import { Route, Routes } from "react-router-dom";
 import { Rounded } from "../components/ui/round-menubar";
+import { Context } from "./routes/context";
 import { Dock } from "./routes/dock";
 import { Hitbox } from "./routes/hitbox";
+import { Launcher } from "./routes/launcher";
 import { Menubar } from "./routes/menubar";
 import { Settings } from "./routes/settings";
+import { Switcher } from "./routes/switcher";
+import { Thumbnails } from "./routes/thumbnails";
 
 export default function RoutesElement() {
   return (
@@ -13,6 +17,10 @@ export default function RoutesElement() {
       <Route path="/dock" element={<Dock />} />
       <Route path="/hitbox" element={<Hitbox />} />
       <Route path="/rounded" element={<Rounded />} />
+      <Route path="/thumbnails" element={<Thumbnails />} />
+      <Route path="/context" element={<Context />} />
+      <Route path="/launcher" element={<Launcher />} />
+      <Route path="/switcher" element={<Switcher />} />
     </Routes>
   );
 }
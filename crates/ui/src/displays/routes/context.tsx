@@ -0,0 +1,114 @@
+import { invoke } from "@tauri-apps/api/core";
+import { Buffer } from "buffer";
+
+import { useEffect, useState } from "react";
+
+type MenuItem =
+  | {
+      type: "action";
+      id: number;
+      label: string;
+      shortcut: string | null;
+      icon: number[] | null;
+      checked: boolean | null;
+      enabled: boolean;
+    }
+  | { type: "separator" }
+  | { type: "submenu"; label: string; items: MenuItem[] };
+
+export function Context() {
+  const [items, setItems] = useState<MenuItem[]>([]);
+  // Open submenus, the last one is shown in place of its parent
+  const [path, setPath] = useState<MenuItem[][]>([]);
+
+  useEffect(() => {
+    invoke<MenuItem[]>("context_menu").then(setItems);
+
+    const close = () => invoke("close_context");
+    const onKeyDown = (e: KeyboardEvent) => {
+      if (e.key === "Escape") close();
+    };
+
+    window.addEventListener("blur", close);
+    window.addEventListener("keydown", onKeyDown);
+    return () => {
+      window.removeEventListener("blur", close);
+      window.removeEventListener("keydown", onKeyDown);
+    };
+  }, []);
+
+  const current = path.length > 0 ? path[path.length - 1] : items;
+  const row =
+    "flex items-center gap-2 h-7 px-2 rounded-md cursor-default text-sm";
+
+  return (
+    <div
+      className="flex flex-col h-full w-full p-1 rounded-lg bg-neutral-900/80 border border-white/[0.05] select-none"
+      onContextMenu={(e) => e.preventDefault()}
+    >
+      {path.length > 0 && (
+        <div
+          className={`${row} hover:bg-white/10 text-neutral-400`}
+          onClick={() => setPath(path.slice(0, -1))}
+        >
+          <span className="w-4">‹</span>
+          Back
+        </div>
+      )}
+
+      {current.map((item, index) => {
+        switch (item.type) {
+          case "separator":
+            return (
+              <div key={index} className="h-px my-1 mx-2 bg-white/10" />
+            );
+          case "submenu":
+            return (
+              <div
+                key={index}
+                className={`${row} hover:bg-white/10`}
+                onClick={() => setPath([...path, item.items])}
+              >
+                <span className="w-4" />
+                <span className="flex-1 truncate">{item.label}</span>
+                <span className="text-neutral-400">›</span>
+              </div>
+            );
+          case "action":
+            return (
+              <div
+                key={index}
+                data-enabled={item.enabled}
+                className={`${row} data-[enabled=true]:hover:bg-white/10 data-[enabled=false]:text-neutral-500`}
+                onClick={() =>
+                  item.enabled &&
+                  invoke("select_context_item", { id: item.id }).catch(
+                    (error) => console.error(error),
+                  )
+                }
+              >
+                <span className="w-4 flex items-center justify-center">
+                  {item.icon ? (
+                    <img
+                      className="w-4 h-4"
+                      src={`data:image/png;base64,${Buffer.from(
+                        item.icon,
+                      ).toString("base64")}`}
+                    />
+                  ) : (
+                    item.checked && "✓"
+                  )}
+                </span>
+                <span className="flex-1 truncate">{item.label}</span>
+                {item.shortcut && (
+                  <span className="text-xs text-neutral-400">
+                    {item.shortcut}
+                  </span>
+                )}
+              </div>
+            );
+        }
+      })}
+    </div>
+  );
+}
@@ -4,20 +4,123 @@ import { useEffect, useRef, useState } from "react";
 
 import { invoke } from "@tauri-apps/api/core";
 import { Buffer } from "buffer";
+import { openContextMenu } from "../../components/ui/context-menu";
 
 type App = {
   hwnd: number;
+  title: string;
   buffer: number[];
+  attention: boolean;
+  button: ButtonState;
+};
+
+type ProgressState = "none" | "indeterminate" | "normal" | "error" | "paused";
+
+// Set by the app through ITaskbarList3
+type ButtonState = {
+  progress_state: ProgressState;
+  progress: number;
+  overlay: number[];
+};
+
+type ButtonChange = {
+  hwnd: number;
+  button: ButtonState;
+};
+
+type TitleUpdate = {
+  hwnd: number;
+  title: string;
+};
+
+type AttentionUpdate = {
+  hwnd: number;
+  attention: boolean;
+};
+
+const progressColor: Record<ProgressState, string> = {
+  none: "",
+  indeterminate: "bg-green-400 animate-pulse",
+  normal: "bg-green-400",
+  error: "bg-red-400",
+  paused: "bg-yellow-400",
+};
+
+type Edge = "bottom" | "top" | "left" | "right";
+
+// Computed by the backend in physical pixels
+type Layout = {
+  edge: Edge;
+  vertical: boolean;
+  tile_size: number;
+  spacing: number;
+  padding: number;
+  scrollable: boolean;
+};
+
+// The active indicator sits on the side facing the screen edge
+const indicator: Record<Edge, string> = {
+  bottom:
+    "bottom-0 h-[0.18rem] group-data-[active=true]:w-4 group-data-[active=false]:w-1.5",
+  top:
+    "top-0 h-[0.18rem] group-data-[active=true]:w-4 group-data-[active=false]:w-1.5",
+  left:
+    "left-0 w-[0.18rem] group-data-[active=true]:h-4 group-data-[active=false]:h-1.5",
+  right:
+    "right-0 w-[0.18rem] group-data-[active=true]:h-4 group-data-[active=false]:h-1.5",
 };
 
-let timeout: NodeJS.Timeout;
 export function Dock() {
   const [active, setActive] = useState<number>(-1);
   const [apps, setApps] = useState<App[]>([]);
+  const [layout, setLayout] = useState<Layout>({
+    edge: "bottom",
+    vertical: false,
+    tile_size: 40,
+    spacing: 4,
+    padding: 5,
+    scrollable: false,
+  });
   const isJustReordered = useRef(false);
 
   useEffect(() => {
     listen<App[]>("set-apps", (event) => setApps(event.payload));
+    listen<Layout>("dock-layout", (event) => setLayout(event.payload));
+    listen<TitleUpdate[]>("window-titles", (event) => {
+      const titles = new Map(
+        event.payload.map((update) => [update.hwnd, update.title]),
+      );
+      setApps((apps) =>
+        apps.map((app) => ({
+          ...app,
+          title: titles.get(app.hwnd) ?? app.title,
+        })),
+      );
+    });
+
+    listen<AttentionUpdate[]>("window-attention", (event) => {
+      const attention = new Map(
+        event.payload.map((update) => [update.hwnd, update.attention]),
+      );
+      setApps((apps) =>
+        apps.map((app) => ({
+          ...app,
+          attention: attention.get(app.hwnd) ?? app.attention,
+        })),
+      );
+    });
+
+    listen<ButtonChange[]>("window-button", (event) => {
+      const buttons = new Map(
+        event.payload.map((change) => [change.hwnd, change.button]),
+      );
+      setApps((apps) =>
+        apps.map((app) => ({
+          ...app,
+          button: buttons.get(app.hwnd) ?? app.button,
+        })),
+      );
+    });
 
     listen<{ message: string; buffer: number[]; hwnd: number }>(
       "active-window",
@@ -27,22 +130,13 @@ export function Dock() {
           : setActive(event.payload.hwnd),
     );
 
-    listen("hover-hitbox", () => {
-      clearTimeout(timeout);
-      emit("mouse-in");
-    });
-
-    document.body.addEventListener("mouseleave", () => {
-      clearTimeout(timeout);
-      timeout = setTimeout(() => {
-        emit("mouse-out");
-      }, 3000);
-    });
+    document.body.addEventListener("mouseleave", () =>
+      emit("dock-pointer-leave"),
+    );
 
-    document.body.addEventListener("mouseenter", () => {
-      clearTimeout(timeout);
-      emit("hover-bar");
-    });
+    document.body.addEventListener("mouseenter", () =>
+      emit("dock-pointer-enter"),
+    );
 
     emit("ready");
   }, []);
@@ -64,32 +158,53 @@ export function Dock() {
     setApps(newOrder);
   };
 
+  const px = (value: number) => `${value / window.devicePixelRatio}px`;
+  const tileSize = px(layout.tile_size);
+
   return (
     <Reorder.Group
-      axis="x"
+      axis={layout.vertical ? "y" : "x"}
       values={apps}
       onReorder={onReorder}
-      className="flex grow aspect-square py-[0.2rem] gap-1 justify-center flex-nowrap items-center w-full px-1 h-[96.5%]"
+      data-scrollable={layout.scrollable}
+      className="flex flex-nowrap items-center justify-center data-[scrollable=true]:justify-start h-full w-full overflow-hidden data-[scrollable=true]:overflow-auto"
+      style={{
+        flexDirection: layout.vertical ? "column" : "row",
+        gap: px(layout.spacing),
+        padding: px(layout.padding),
+      }}
       as="ul"
     >
       {apps.map((app) => {
         return (
           <Reorder.Item
             data-active={active === app.hwnd}
+            data-attention={app.attention}
             key={app.hwnd}
             value={app}
             id={app.hwnd.toString()}
-            className="group backdrop-blur select-none h-10 w-10 relative flex items-center justify-center aspect-square bg-white/5 rounded-md hover:bg-white/15 border border-white/[0.025]"
+            title={app.title}
+            className="group backdrop-blur select-none shrink-0 relative flex items-center justify-center aspect-square bg-white/5 rounded-md hover:bg-white/15 border border-white/[0.025] data-[attention=true]:bg-orange-400/25 data-[attention=true]:animate-[attention_1.2s_ease-in-out_infinite]"
+            style={{ width: tileSize, height: tileSize }}
             onPointerUp={() =>
               !isJustReordered.current && handleChangeWindow(app)
             }
-            // onContextMenu={async (e) => {
-            //   e.preventDefault();
-            //   await invoke("open_context", {
-            //     x: e.clientX,
-            //     y: e.clientY,
-            //   });
-            // }}
+            onPointerEnter={(e) => {
+              // Thumbnails are placed in physical pixels
+              const rect = e.currentTarget.getBoundingClientRect();
+              const scale = window.devicePixelRatio;
+              emit("tile-pointer-enter", {
+                hwnd: app.hwnd,
+                x: Math.round(rect.x * scale),
+                y: Math.round(rect.y * scale),
+                width: Math.round(rect.width * scale),
+                height: Math.round(rect.height * scale),
+              });
+            }}
+            onPointerLeave={() => emit("tile-pointer-leave")}
+            onContextMenu={(e) =>
+              openContextMenu({ target: "dock_tile", hwnd: app.hwnd }, e)
+            }
           >
             {app.buffer.length === 0 ? (
               <motion.h1 className="text-lg group-data-[active=true]:animate-[bounce-up_0.55s_ease-in-out_1] group-data-[active=false]:animate-[bounce-down_0.55s_ease-in-out_1]">
@@ -98,13 +213,37 @@ export function Dock() {
             ) : (
               <motion.img
                 draggable="false"
-                className="object-scale-down select-none aspect-square h-[1.45rem] group-data-[active=true]:animate-[bounce-up_0.55s_ease-in-out_1] group-data-[active=false]:animate-[bounce-down_0.55s_ease-in-out_1]"
+                className="object-scale-down select-none aspect-square h-[58%] group-data-[active=true]:animate-[bounce-up_0.55s_ease-in-out_1] group-data-[active=false]:animate-[bounce-down_0.55s_ease-in-out_1]"
                 src={`data:image/png;base64,${Buffer.from(
                   app.buffer || [],
                 ).toString("base64")}`}
               />
             )}
-            <motion.div className="absolute duration-300 ease-in-out transition-all group-data-[active=true]:w-4 group-data-[active=false]:w-1.5 h-[0.18rem] group-data-[active=true]:bg-blue-400 group-data-[active=false]:bg-neutral-400 bottom-0 rounded-full" />
+            {app.button.progress_state !== "none" && (
+              <div className="absolute left-[12%] right-[12%] bottom-[12%] h-[0.18rem] rounded-full bg-white/15 overflow-hidden">
+                <div
+                  className={`h-full rounded-full transition-all ${progressColor[app.button.progress_state]}`}
+                  style={{
+                    width:
+                      app.button.progress_state === "indeterminate"
+                        ? "100%"
+                        : `${app.button.progress * 100}%`,
+                  }}
+                />
+              </div>
+            )}
+            {app.button.overlay.length > 0 && (
+              <img
+                draggable="false"
+                className="absolute right-[6%] bottom-[6%] h-[40%] aspect-square select-none"
+                src={`data:image/png;base64,${Buffer.from(
+                  app.button.overlay,
+                ).toString("base64")}`}
+              />
+            )}
+            <motion.div
+              className={`absolute duration-300 ease-in-out transition-all group-data-[active=true]:bg-blue-400 group-data-[active=false]:bg-neutral-400 rounded-full ${indicator[layout.edge]}`}
+            />
           </Reorder.Item>
         );
       })}
@@ -0,0 +1,126 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Buffer } from "buffer";
+
+import { useEffect, useRef, useState } from "react";
+
+type AppResult = {
+  id: string;
+  name: string;
+  kind: "shortcut" | "packaged" | "executable";
+  path: string;
+  score: number;
+  // PNG bytes, empty when the app has no icon file
+  image: number[];
+};
+
+const kinds = {
+  shortcut: "App",
+  packaged: "Store app",
+  executable: "Command",
+};
+
+export function Launcher() {
+  const [query, setQuery] = useState("");
+  const [results, setResults] = useState<AppResult[]>([]);
+  const [selected, setSelected] = useState(0);
+  const [error, setError] = useState<string | null>(null);
+  const input = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    // Every time it opens the launcher starts over
+    listen("launcher-show", () => {
+      setQuery("");
+      setError(null);
+      input.current?.focus();
+      invoke("refresh_apps");
+    });
+  }, []);
+
+  useEffect(() => {
+    let current = true;
+    invoke<AppResult[]>("search_apps", { query }).then((results) => {
+      // Answers to older queries can arrive late
+      if (!current) return;
+      setResults(results);
+      setSelected(0);
+    });
+
+    return () => {
+      current = false;
+    };
+  }, [query]);
+
+  const launch = async (app: AppResult | undefined) => {
+    if (!app) return;
+
+    try {
+      await invoke("launch_app", { id: app.id });
+      await invoke("hide_launcher");
+    } catch (error: any) {
+      setError(error?.message ?? String(error));
+    }
+  };
+
+  const onKeyDown = (e: React.KeyboardEvent) => {
+    switch (e.key) {
+      case "ArrowDown":
+        e.preventDefault();
+        setSelected((selected + 1) % Math.max(results.length, 1));
+        break;
+      case "ArrowUp":
+        e.preventDefault();
+        setSelected(
+          (selected - 1 + results.length) % Math.max(results.length, 1),
+        );
+        break;
+      case "Enter":
+        launch(results[selected]);
+        break;
+      case "Escape":
+        invoke("hide_launcher");
+        break;
+    }
+  };
+
+  return (
+    <div className="flex flex-col h-full w-full p-2 gap-2 rounded-xl bg-neutral-900/90 border border-white/[0.05] select-none">
+      <input
+        ref={input}
+        autoFocus
+        value={query}
+        placeholder="Search apps"
+        onChange={(e) => setQuery(e.target.value)}
+        onKeyDown={onKeyDown}
+        className="w-full h-11 px-3 rounded-lg bg-white/5 text-base outline-none placeholder:text-neutral-500"
+      />
+
+      {error && <p className="px-3 text-sm text-red-400">{error}</p>}
+
+      <div className="flex flex-col overflow-y-auto">
+        {results.map((app, index) => (
+          <div
+            key={app.id}
+            data-selected={index === selected}
+            className="flex items-center gap-3 h-11 px-3 rounded-lg cursor-default data-[selected=true]:bg-white/10"
+            onPointerMove={() => setSelected(index)}
+            onClick={() => launch(app)}
+          >
+            {app.image.length === 0 ? (
+              <span className="w-6 text-center">❔</span>
+            ) : (
+              <img
+                className="w-6 h-6"
+                src={`data:image/png;base64,${Buffer.from(app.image).toString(
+                  "base64",
+                )}`}
+              />
+            )}
+            <span className="flex-1 truncate text-sm">{app.name}</span>
+            <span className="text-xs text-neutral-400">{kinds[app.kind]}</span>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
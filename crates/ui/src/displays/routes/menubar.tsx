@@ -1,5 +1,7 @@
+import { invoke } from "@tauri-apps/api/core";
 import { Window } from "@tauri-apps/api/window";
 import { useEffect } from "react";
+import { openContextMenu } from "../../components/ui/context-menu";
 import ExecuteButton from "../../components/ui/execute-button";
 import ActiveWindow from "../../components/widgets/active-window";
 import Clock from "../../components/widgets/clock";
@@ -24,6 +26,18 @@ export function Menubar() {
         label: <ActiveWindow.Label />,
         render: <ActiveWindow.Render />,
       },
+      {
+        id: "launcher",
+        label: <></>,
+        render: (
+          <button
+            onClick={async () => await invoke("toggle_launcher")}
+            className="cursor-default hover:bg-neutral-800/40 px-1.5 py-0.5 rounded-md"
+          >
+            Apps
+          </button>
+        ),
+      },
       {
         id: "terminal",
         label: <></>,
@@ -100,7 +114,16 @@ export function Menubar() {
     >
       <div className="widgets left">
         {widgets.left.map((widget) => (
-          <div key={widget.id} className="widget">
+          <div
+            key={widget.id}
+            className="widget"
+            onContextMenu={(e) =>
+              openContextMenu(
+                { target: "menubar_widget", widget: widget.id },
+                e,
+              )
+            }
+          >
             {widget.label}
 
             {widget.render}
@@ -110,7 +133,16 @@ export function Menubar() {
 
       <div className="widgets center">
         {widgets.center.map((widget) => (
-          <div key={widget.id} className="widget">
+          <div
+            key={widget.id}
+            className="widget"
+            onContextMenu={(e) =>
+              openContextMenu(
+                { target: "menubar_widget", widget: widget.id },
+                e,
+              )
+            }
+          >
             {widget.label}
 
             {widget.render}
@@ -120,7 +152,16 @@ export function Menubar() {
 
       <div className="widgets right">
         {widgets.right.map((widget) => (
-          <div key={widget.id} className="widget">
+          <div
+            key={widget.id}
+            className="widget"
+            onContextMenu={(e) =>
+              openContextMenu(
+                { target: "menubar_widget", widget: widget.id },
+                e,
+              )
+            }
+          >
             {widget.label}
 
             {widget.render}
@@ -1,3 +1,70 @@
+import { invoke } from "@tauri-apps/api/core";
+
+import { useEffect, useState } from "react";
+
+type HotkeyAction =
+  | { action: "toggle_launcher" | "toggle_dock" | "show_switcher" }
+  | { action: "focus_dock_item"; index: number };
+
+type HotkeyError =
+  | { error: "invalid"; setting: string; text: string; reason: string }
+  | {
+      error: "conflict";
+      accelerator: string;
+      kept: HotkeyAction;
+      dropped: HotkeyAction;
+    }
+  | {
+      error: "unavailable";
+      accelerator: string;
+      action: HotkeyAction;
+      reason: string;
+    };
+
+// Named after the setting, like the backend logs them
+function actionName(action: HotkeyAction) {
+  switch (action.action) {
+    case "toggle_launcher":
+      return "launcher";
+    case "show_switcher":
+      return "switcher";
+    case "toggle_dock":
+      return "toggle_dock";
+    case "focus_dock_item":
+      return `focus_dock_item ${action.index + 1}`;
+  }
+}
+
+function describe(error: HotkeyError) {
+  switch (error.error) {
+    case "invalid":
+      return `Invalid hotkey ${error.setting} = "${error.text}": ${error.reason}`;
+    case "conflict":
+      return `${error.accelerator} is bound to both ${actionName(error.kept)} and ${actionName(error.dropped)}, ${actionName(error.dropped)} is ignored`;
+    case "unavailable":
+      return `Failed to register ${error.accelerator} for ${actionName(error.action)}: ${error.reason}`;
+  }
+}
+
 export function Settings() {
-  return <div className="h-full w-full bg-transparent" />;
+  const [hotkeyErrors, setHotkeyErrors] = useState<HotkeyError[]>([]);
+
+  useEffect(() => {
+    invoke<HotkeyError[]>("hotkey_errors").then(setHotkeyErrors);
+  }, []);
+
+  return (
+    <div className="h-full w-full bg-transparent p-4 text-sm text-white select-none">
+      {hotkeyErrors.length > 0 && (
+        <section className="flex flex-col gap-1 p-3 rounded-lg bg-red-500/10 border border-red-500/20">
+          <h2 className="font-medium">Some hotkeys are not active</h2>
+          {hotkeyErrors.map((error, index) => (
+            <p key={index} className="text-neutral-300">
+              {describe(error)}
+            </p>
+          ))}
+        </section>
+      )}
+    </div>
+  );
 }
@@ -0,0 +1,136 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Buffer } from "buffer";
+
+import { useEffect, useRef, useState } from "react";
+
+type App = {
+  hwnd: number;
+  path: string;
+  title: string;
+  buffer: number[];
+};
+
+type TitleUpdate = {
+  hwnd: number;
+  title: string;
+};
+
+type SwitcherShow = {
+  windows: App[];
+  selected: number;
+};
+
+export function Switcher() {
+  const [windows, setWindows] = useState<App[]>([]);
+  const [selected, setSelected] = useState(0);
+  const container = useRef<HTMLDivElement>(null);
+  // Read by the event listeners, which are only registered once
+  const current = useRef({ windows, selected });
+  current.current = { windows, selected };
+
+  const move = (step: number) =>
+    setSelected((selected) => {
+      const count = Math.max(current.current.windows.length, 1);
+      return (selected + step + count) % count;
+    });
+
+  const switchTo = (app: App | undefined) => {
+    if (app) {
+      invoke("switch_window", { hwnd: app.hwnd });
+    } else {
+      invoke("hide_switcher");
+    }
+  };
+
+  useEffect(() => {
+    listen<SwitcherShow>("switcher-show", (event) => {
+      container.current?.focus();
+      // A quick tap commits before the next render
+      current.current = event.payload;
+      setWindows(event.payload.windows);
+      setSelected(event.payload.selected);
+    });
+    listen("switcher-next", () => move(1));
+    listen<TitleUpdate[]>("window-titles", (event) => {
+      const titles = new Map(
+        event.payload.map((update) => [update.hwnd, update.title]),
+      );
+      setWindows((windows) =>
+        windows.map((app) => ({
+          ...app,
+          title: titles.get(app.hwnd) ?? app.title,
+        })),
+      );
+    });
+    // The hotkey's modifiers were released
+    listen("switcher-commit", () => {
+      const { windows, selected } = current.current;
+      switchTo(windows[selected]);
+    });
+  }, []);
+
+  const onKeyDown = (e: React.KeyboardEvent) => {
+    switch (e.key) {
+      case "Tab":
+        e.preventDefault();
+        move(e.shiftKey ? -1 : 1);
+        break;
+      case "ArrowRight":
+      case "ArrowDown":
+        e.preventDefault();
+        move(1);
+        break;
+      case "ArrowLeft":
+      case "ArrowUp":
+        e.preventDefault();
+        move(-1);
+        break;
+      case "Enter":
+        switchTo(windows[selected]);
+        break;
+      case "Escape":
+        invoke("hide_switcher");
+        break;
+    }
+  };
+
+  return (
+    <div
+      ref={container}
+      tabIndex={0}
+      onKeyDown={onKeyDown}
+      className="flex flex-col h-full w-full p-3 gap-3 rounded-xl bg-neutral-900/90 border border-white/[0.05] select-none outline-none"
+    >
+      <div className="flex flex-wrap content-start justify-center gap-2 overflow-y-auto">
+        {windows.map((app, index) => (
+          <div
+            key={app.hwnd}
+            data-selected={index === selected}
+            className="flex flex-col items-center justify-center gap-2 w-28 h-28 p-2 rounded-lg cursor-default data-[selected=true]:bg-white/10"
+            onPointerMove={() => setSelected(index)}
+            onClick={() => switchTo(app)}
+          >
+            {app.buffer.length === 0 ? (
+              <span className="text-3xl">❔</span>
+            ) : (
+              <img
+                className="w-10 h-10"
+                src={`data:image/png;base64,${Buffer.from(app.buffer).toString(
+                  "base64",
+                )}`}
+              />
+            )}
+            <span className="w-full truncate text-center text-xs">
+              {app.title}
+            </span>
+          </div>
+        ))}
+      </div>
+
+      {windows.length === 0 && (
+        <p className="m-auto text-sm text-neutral-400">No open windows</p>
+      )}
+    </div>
+  );
+}
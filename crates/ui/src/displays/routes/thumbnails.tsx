@@ -0,0 +1,77 @@
+import { emit, listen } from "@tauri-apps/api/event";
+
+import { useEffect, useState } from "react";
+
+type Bounds = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+};
+
+// Placed by the backend in physical pixels, DWM draws the window in `preview`
+type Preview = {
+  hwnd: number;
+  title: string;
+  slot: Bounds;
+  preview: Bounds;
+};
+
+type TitleUpdate = {
+  hwnd: number;
+  title: string;
+};
+
+export function Thumbnails() {
+  const [previews, setPreviews] = useState<Preview[]>([]);
+
+  useEffect(() => {
+    listen<Preview[]>("thumbnails", (event) => setPreviews(event.payload));
+    // Grouped windows keep their titles current while the popup is open
+    listen<TitleUpdate[]>("window-titles", (event) => {
+      const titles = new Map(
+        event.payload.map((update) => [update.hwnd, update.title]),
+      );
+      setPreviews((previews) =>
+        previews.map((preview) => ({
+          ...preview,
+          title: titles.get(preview.hwnd) ?? preview.title,
+        })),
+      );
+    });
+
+    document.body.addEventListener("mouseleave", () =>
+      emit("thumbnails-pointer-leave"),
+    );
+
+    document.body.addEventListener("mouseenter", () =>
+      emit("thumbnails-pointer-enter"),
+    );
+  }, []);
+
+  const px = (value: number) => `${value / window.devicePixelRatio}px`;
+
+  return (
+    <div className="relative h-full w-full rounded-lg bg-neutral-900/80 border border-white/[0.05] overflow-hidden">
+      {previews.map((preview) => (
+        <div
+          key={preview.hwnd}
+          title={preview.title}
+          className="absolute rounded-md hover:bg-white/10 border border-transparent hover:border-white/15 transition-colors"
+          style={{
+            left: px(preview.slot.x),
+            top: px(preview.slot.y),
+            width: px(preview.slot.width),
+            height: px(preview.slot.height),
+          }}
+          // Left click activates, middle click closes
+          onPointerUp={(e) => {
+            if (e.button === 0) emit("thumbnail-activate", preview.hwnd);
+            if (e.button === 1) emit("thumbnail-close", preview.hwnd);
+          }}
+          onAuxClick={(e) => e.preventDefault()}
+        />
+      ))}
+    </div>
+  );
+}
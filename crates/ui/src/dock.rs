@@ -1,3 +1,5 @@
+mod appbar;
+
 use tauri::Emitter;
 use tauri::Listener;
 
@@ -6,36 +8,51 @@ use tauri::PhysicalPosition;
 use tauri::PhysicalSize;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::RECT;
 use windows::Win32::UI::Accessibility::SetWinEventHook;
 use windows::Win32::UI::WindowsAndMessaging::EnumWindows;
+use windows::Win32::UI::WindowsAndMessaging::GetDesktopWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
 use windows::Win32::UI::WindowsAndMessaging::SetWindowLongA;
-use windows::Win32::UI::WindowsAndMessaging::EVENT_MAX;
-use windows::Win32::UI::WindowsAndMessaging::EVENT_MIN;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_CREATE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_HIDE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_LOCATIONCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_OBJECT_NAMECHANGE;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_FOREGROUND;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_MINIMIZEEND;
+use windows::Win32::UI::WindowsAndMessaging::EVENT_SYSTEM_MINIMIZESTART;
 use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
 use windows::Win32::UI::WindowsAndMessaging::WINEVENT_OUTOFCONTEXT;
 use windows::Win32::UI::WindowsAndMessaging::WINEVENT_SKIPOWNPROCESS;
+use windows::Win32::UI::WindowsAndMessaging::WM_DISPLAYCHANGE;
 use windows::Win32::UI::WindowsAndMessaging::WS_EX_NOACTIVATE;
 
-use backdrop::enable_blur;
 use util::*;
 
 use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
 use std::sync::LazyLock;
 use std::sync::Mutex;
+use std::sync::OnceLock;
 use std::thread;
+use std::time::Instant;
 
-use crate::hooks;
+pub use shell::dock::*;
 
-#[derive(Clone, serde::Serialize, Debug)]
-pub struct Window {
-  pub hwnd: isize,
-  pub path: String,
-  pub buffer: Vec<u8>,
-}
+use crate::backdrops;
+use crate::hooks;
+use crate::hotkeys;
+use crate::tasklist;
+use crate::theme;
 
 pub static WINDOW: LazyLock<Mutex<Option<tauri::WebviewWindow>>> =
   LazyLock::new(|| Mutex::new(None));
 pub static GLOBAL_APPS: LazyLock<Mutex<Vec<Window>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+static AUTOHIDE: OnceLock<Sender<DockEvent>> = OnceLock::new();
 
 pub fn init() {
   let window = setup_window().expect("Failed to setup dock window");
@@ -43,19 +60,45 @@ pub fn init() {
   *WINDOW.lock().unwrap() = Some(window.clone());
 
   // Hooks
+  hooks::init();
+  hooks::watch_shell(hwnd);
+  tasklist::host(hwnd);
+  hotkeys::init(hwnd);
   unsafe { setup_hooks() };
 
   // Styles
   hide_taskbar(true);
+  watch_taskbars(hwnd);
+  watch_displays(hwnd);
+
+  if USER_SETTINGS.dock.mode == DockMode::ReserveSpace {
+    appbar::register(hwnd, USER_SETTINGS.dock.edge);
+  }
 
   // Listeners
-  window.listen("mouse-out", move |_| hide());
-  window.listen("mouse-in", move |_| show());
+  let (sender, receiver) = mpsc::channel();
+  AUTOHIDE
+    .set(sender)
+    .expect("Dock auto-hide already initialized");
+
+  for (name, event) in [
+    ("dock-pointer-enter", DockEvent::PointerEntered(Area::Dock)),
+    ("dock-pointer-leave", DockEvent::PointerLeft(Area::Dock)),
+    (
+      "hitbox-pointer-enter",
+      DockEvent::PointerEntered(Area::Hitbox),
+    ),
+    ("hitbox-pointer-leave", DockEvent::PointerLeft(Area::Hitbox)),
+  ] {
+    window.listen(name, move |_| send(event));
+  }
+
   window.once("ready", move |_| {
     thread::spawn(move || {
-      enable_blur(hwnd, "#10101000", true);
+      backdrops::register("dock", hwnd, theme::current().backdrops("dock"));
       update();
       show();
+      run_autohide(receiver);
     });
   });
 
@@ -67,12 +110,17 @@ pub fn update() {
   let binding = WINDOW.lock().unwrap();
   let window = binding.as_ref().unwrap();
 
-  window.set_position(position()).unwrap();
-  window.set_size(size()).unwrap();
+  let apps = GLOBAL_APPS.lock().unwrap().to_vec();
+  let layout = current_layout(apps.len());
+
+  window.set_position(position(&layout)).unwrap();
+  window.set_size(size(&layout)).unwrap();
+  appbar::set_pos(&layout);
 
   window
-    .emit("set-apps", GLOBAL_APPS.lock().unwrap().to_vec())
-    .expect("Failed to set apps");
+    .emit("dock-layout", layout)
+    .expect("Failed to set layout");
+  window.emit("set-apps", apps).expect("Failed to set apps");
 }
 
 pub fn hide() {
@@ -93,6 +141,65 @@ pub fn show() {
   unsafe { SetWindowLongA(hwnd, GWL_EXSTYLE, WS_EX_NOACTIVATE.0 as i32) };
 }
 
+pub fn remove() {
+  appbar::remove();
+}
+
+// Monitors were added, removed or resized, the dock follows its edge
+fn watch_displays(hwnd: HWND) {
+  subclass_window(
+    hwnd,
+    Box::new(|_, msg, _, _| {
+      if msg == WM_DISPLAYCHANGE {
+        thread::spawn(update);
+      }
+
+      None
+    }),
+  )
+  .unwrap_or_else(|err| eprintln!("Failed to watch displays: {}", err));
+}
+
+/// Feeds an event to the auto-hide state machine
+pub fn send(event: DockEvent) {
+  if let Some(sender) = AUTOHIDE.get() {
+    sender.send(event).unwrap_or_else(|_| ());
+  }
+}
+
+// Drives the state machine, waking up for delayed transitions
+fn run_autohide(receiver: Receiver<DockEvent>) {
+  let mut autohide = AutoHide::new(&USER_SETTINGS.dock, true, SystemClock);
+  let mut change = autohide.evaluate();
+
+  loop {
+    match change {
+      Some(true) => show(),
+      Some(false) => hide(),
+      None => {}
+    }
+
+    let event = match autohide.deadline() {
+      Some(deadline) => {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+          Ok(event) => Some(event),
+          Err(RecvTimeoutError::Timeout) => None,
+          Err(RecvTimeoutError::Disconnected) => return,
+        }
+      }
+      None => match receiver.recv() {
+        Ok(event) => Some(event),
+        Err(_) => return,
+      },
+    };
+
+    change = match event {
+      Some(event) => autohide.handle(event),
+      None => autohide.tick(),
+    };
+  }
+}
+
 fn setup_window() -> Result<tauri::WebviewWindow, ()> {
   let window = tauri::WebviewWindowBuilder::new(
     APP_HANDLE
@@ -121,20 +228,28 @@ fn setup_window() -> Result<tauri::WebviewWindow, ()> {
 
 // Hooks
 pub unsafe fn setup_hooks() {
-  active_window(); // TODO: fix this, causes a crash in window
+  watch_windows();
   enum_opened_windows();
 }
 
-pub unsafe fn active_window() {
-  SetWinEventHook(
-    EVENT_MIN,
-    EVENT_MAX,
-    None,
-    Some(hooks::win_event_hook_callback),
-    0,
-    0,
-    WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
-  );
+// Only the events the dock handles, hooking everything floods the worker
+pub unsafe fn watch_windows() {
+  for (min, max) in [
+    (EVENT_SYSTEM_FOREGROUND, EVENT_SYSTEM_FOREGROUND),
+    (EVENT_SYSTEM_MINIMIZESTART, EVENT_SYSTEM_MINIMIZEEND),
+    (EVENT_OBJECT_CREATE, EVENT_OBJECT_HIDE),
+    (EVENT_OBJECT_LOCATIONCHANGE, EVENT_OBJECT_NAMECHANGE),
+  ] {
+    SetWinEventHook(
+      min,
+      max,
+      None,
+      Some(hooks::win_event_hook_callback),
+      0,
+      0,
+      WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+    );
+  }
 }
 
 pub unsafe fn enum_opened_windows() {
@@ -142,21 +257,60 @@ pub unsafe fn enum_opened_windows() {
 }
 
 // Get size and position
-pub fn position() -> PhysicalPosition<i32> {
-  let length = GLOBAL_APPS.lock().unwrap().len() as i32;
-  let screen_rect = ScreenGeometry::new();
-
+pub fn position(layout: &DockLayout) -> PhysicalPosition<i32> {
   PhysicalPosition {
-    x: (screen_rect.width / 2) - ((length * 44 / 2) + 8),
-    y: screen_rect.height - 51 - USER_SETTINGS.margin_bottom,
+    x: layout.bounds.x,
+    y: layout.bounds.y,
   }
 }
 
-pub fn size() -> PhysicalSize<i32> {
-  let length = GLOBAL_APPS.lock().unwrap().len() as i32;
-
+pub fn size(layout: &DockLayout) -> PhysicalSize<i32> {
   PhysicalSize {
-    width: (length * 44) + 8,
-    height: 51,
+    width: layout.bounds.width,
+    height: layout.bounds.height,
+  }
+}
+
+/// The part of the screen the dock can use, below the menubar
+pub fn screen() -> Bounds {
+  let screen_rect = ScreenGeometry::new();
+
+  Bounds {
+    x: screen_rect.x,
+    y: screen_rect.y + USER_SETTINGS.height,
+    width: screen_rect.width - screen_rect.x,
+    height: screen_rect.height - screen_rect.y - USER_SETTINGS.height,
   }
 }
+
+/// Layout of a dock holding `count` apps on the current screen
+pub fn current_layout(count: usize) -> DockLayout {
+  compute_layout(
+    &USER_SETTINGS.dock,
+    USER_SETTINGS.margin_bottom,
+    count,
+    screen(),
+    // The dock lives on the primary monitor, like `screen`
+    scale_factor(unsafe { GetDesktopWindow() }),
+  )
+}
+
+/// Whether one of `apps`' windows covers part of the dock
+pub fn is_overlapped(apps: &[Window]) -> bool {
+  let dock = current_layout(apps.len()).bounds;
+
+  apps.iter().any(|app| {
+    let hwnd = HWND(app.hwnd);
+    let mut rect = RECT::default();
+
+    is_window_visible(hwnd)
+      && !unsafe { IsIconic(hwnd) }.as_bool()
+      && unsafe { GetWindowRect(hwnd, &mut rect) }.is_ok()
+      && dock.intersects(&Bounds {
+        x: rect.left,
+        y: rect.top,
+        width: rect.right - rect.left,
+        height: rect.bottom - rect.top,
+      })
+  })
+}
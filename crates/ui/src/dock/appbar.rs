@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::thread;
+
+use util::AppBar;
+use util::AppBarNotification;
+use util::DockEdge;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::UI::Shell::ABE_BOTTOM;
+use windows::Win32::UI::Shell::ABE_LEFT;
+use windows::Win32::UI::Shell::ABE_RIGHT;
+use windows::Win32::UI::Shell::ABE_TOP;
+
+use crate::dock;
+use crate::dock::DockLayout;
+use crate::fullscreen;
+
+static APPBAR: LazyLock<Mutex<Option<AppBar>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Registers the dock as an appbar so maximized windows avoid its edge
+///
+/// Must be called from the thread that created the window, the position is set
+/// by [`set_pos`] once the layout is known.
+pub fn register(hwnd: HWND, edge: DockEdge) {
+  let edge = match edge {
+    DockEdge::Bottom => ABE_BOTTOM,
+    DockEdge::Top => ABE_TOP,
+    DockEdge::Left => ABE_LEFT,
+    DockEdge::Right => ABE_RIGHT,
+  };
+
+  let handler = Arc::new(|notification: AppBarNotification| match notification {
+    // Another appbar or the taskbar moved, the granted rect may differ
+    AppBarNotification::PosChanged => {
+      thread::spawn(dock::update);
+    }
+    AppBarNotification::FullscreenApp(fullscreen) => fullscreen::shell_notified(fullscreen),
+    _ => {}
+  });
+
+  match AppBar::register(hwnd, edge, handler) {
+    Ok(appbar) => *APPBAR.lock().unwrap() = Some(appbar),
+    Err(err) => eprintln!("Failed to register dock appbar: {}", err),
+  }
+}
+
+/// Asks the shell to reserve `layout.reserved`, if the dock is an appbar
+pub fn set_pos(layout: &DockLayout) {
+  if let Some(appbar) = APPBAR.lock().unwrap().as_ref() {
+    let reserved = layout.reserved;
+    appbar.set_pos(RECT {
+      left: reserved.x,
+      top: reserved.y,
+      right: reserved.right(),
+      bottom: reserved.bottom(),
+    });
+  }
+}
+
+/// Gives the reserved space back to the shell
+pub fn remove() {
+  APPBAR.lock().unwrap().take();
+}
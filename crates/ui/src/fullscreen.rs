@@ -0,0 +1,170 @@
+use std::ffi::c_void;
+use std::mem;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use tauri::Emitter;
+use util::{get_class, get_styles, monitor_of, APP_HANDLE};
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+use windows::Win32::UI::Shell::{
+  SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_RUNNING_D3D_FULL_SCREEN,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+  GetForegroundWindow, GetShellWindow, GetWindowRect, IsZoomed, WS_CAPTION,
+};
+
+pub use shell::fullscreen::*;
+
+use crate::dock::{self, Bounds, DockEvent, SystemClock};
+use crate::menubar;
+
+// The notification state has no change event, it is sampled this often
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// Desktop windows cover the monitor without being fullscreen apps
+const DESKTOP_CLASSES: [&str; 2] = ["Progman", "WorkerW"];
+
+static EVENTS: OnceLock<Sender<Event>> = OnceLock::new();
+// Last state reported, see `is_fullscreen`
+static FULLSCREEN: AtomicBool = AtomicBool::new(false);
+
+enum Event {
+  // Something may have changed, the foreground window is checked again
+  Refresh,
+  // `ABN_FULLSCREENAPP` reached one of the appbars
+  Shell(bool),
+}
+
+/// Starts watching for fullscreen apps
+///
+/// Changes are reported to the dock and the menubar, and emitted to every
+/// window as `fullscreen-changed` with the new state.
+pub fn init() {
+  let (sender, receiver) = mpsc::channel();
+  if EVENTS.set(sender).is_ok() {
+    thread::spawn(move || run(receiver));
+  }
+}
+
+/// Checks the foreground window again, e.g. after it moved or changed
+pub fn refresh() {
+  send(Event::Refresh);
+}
+
+/// Forwards an `ABN_FULLSCREENAPP` notification received by an appbar
+pub fn shell_notified(fullscreen: bool) {
+  send(Event::Shell(fullscreen));
+}
+
+/// The state last reported to the dock and the menubar
+pub fn is_fullscreen() -> bool {
+  FULLSCREEN.load(Ordering::SeqCst)
+}
+
+fn send(event: Event) {
+  if let Some(sender) = EVENTS.get() {
+    sender.send(event).unwrap_or_else(|_| ());
+  }
+}
+
+// Samples the signals on every event and poll, waking up for delayed changes
+fn run(receiver: Receiver<Event>) {
+  let mut detector = FullscreenDetector::new(SystemClock);
+  let mut shell = false;
+
+  loop {
+    let timeout = detector.deadline().map_or(POLL_INTERVAL, |deadline| {
+      deadline
+        .saturating_duration_since(Instant::now())
+        .min(POLL_INTERVAL)
+    });
+
+    match receiver.recv_timeout(timeout) {
+      Ok(Event::Shell(fullscreen)) => shell = fullscreen,
+      Ok(Event::Refresh) | Err(RecvTimeoutError::Timeout) => {}
+      Err(RecvTimeoutError::Disconnected) => return,
+    }
+
+    if let Some(fullscreen) = detector.observe(sample(shell)) {
+      publish(fullscreen);
+    }
+  }
+}
+
+fn publish(fullscreen: bool) {
+  FULLSCREEN.store(fullscreen, Ordering::SeqCst);
+  dock::send(DockEvent::FullscreenChanged(fullscreen));
+  menubar::set_fullscreen(fullscreen);
+
+  if let Some(app_handle) = APP_HANDLE.lock().unwrap().as_ref() {
+    app_handle
+      .emit("fullscreen-changed", fullscreen)
+      .unwrap_or_else(|_| ());
+  }
+}
+
+fn sample(shell: bool) -> Signals {
+  let hwnd = unsafe { GetForegroundWindow() };
+
+  Signals {
+    notification_state: matches!(
+      unsafe { SHQueryUserNotificationState() },
+      Ok(QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE)
+    ),
+    covers_monitor: hwnd.0 != 0 && covers_monitor(hwnd),
+    cloaked: hwnd.0 != 0 && is_cloaked(hwnd),
+    shell,
+  }
+}
+
+fn covers_monitor(hwnd: HWND) -> bool {
+  if hwnd == unsafe { GetShellWindow() }
+    || DESKTOP_CLASSES.contains(&get_class(hwnd).unwrap_or_default().as_str())
+  {
+    return false;
+  }
+
+  // Without appbars on its monitor a maximized window's frame reaches past
+  // the edges, it still has its title bar though
+  if unsafe { IsZoomed(hwnd) }.as_bool() && get_styles(hwnd).contains(WS_CAPTION) {
+    return false;
+  }
+
+  let mut rect = RECT::default();
+  if unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
+    return false;
+  }
+  let Some(monitor) = monitor_of(hwnd) else {
+    return false;
+  };
+
+  let bounds = |rect: RECT| Bounds {
+    x: rect.left,
+    y: rect.top,
+    width: rect.right - rect.left,
+    height: rect.bottom - rect.top,
+  };
+  covers(bounds(rect), bounds(monitor.bounds))
+}
+
+fn is_cloaked(hwnd: HWND) -> bool {
+  let mut cloaked = 0u32;
+  unsafe {
+    DwmGetWindowAttribute(
+      hwnd,
+      DWMWA_CLOAKED,
+      &mut cloaked as *mut u32 as *mut c_void,
+      mem::size_of::<u32>() as u32,
+    )
+  }
+  .is_ok()
+    && cloaked != 0
+}
@@ -1,32 +1,21 @@
 use std::path::PathBuf;
 
-use util::ScreenGeometry;
 use util::APP_HANDLE;
 
-use tauri::Emitter;
-use tauri::Listener;
-use tauri::Manager;
-
 use windows::Win32::Foundation::HWND;
 use windows::Win32::UI::WindowsAndMessaging::MoveWindow;
 
+use crate::dock;
+
 pub fn init() {
   let window = setup_window().expect("Failed to setup dock window");
   let hwnd = HWND(window.hwnd().unwrap().0);
-  let screen_rect = ScreenGeometry::new();
+  let hitbox = dock::current_layout(0).hitbox;
 
   unsafe {
-    MoveWindow(hwnd, 0, screen_rect.height - 2, screen_rect.width, 2, true)
+    MoveWindow(hwnd, hitbox.x, hitbox.y, hitbox.width, hitbox.height, true)
       .expect("Failed to move window");
   }
-
-  let cloned_window = window.clone();
-  cloned_window.listen("mouse-in", move |_msg| {
-    window
-      .app_handle()
-      .emit("hide-taskbar", ())
-      .unwrap_or_else(|_| ());
-  });
 }
 
 pub fn setup_window() -> Result<tauri::WebviewWindow, ()> {
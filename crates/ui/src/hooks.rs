@@ -1,20 +1,64 @@
+use std::fs::File;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::OnceLock;
 use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
-use icons::get_icon;
+use icons::get_cached_icon;
 use tauri::Emitter;
-use util::{exe_path, get_class, hide_taskbar, is_real_window};
+use util::{
+  exe_path, get_class, get_ex_styles, get_styles, get_window_text, hide_taskbar, is_real_window,
+  subclass_window, DockMode, APP_HANDLE, USER_SETTINGS,
+};
+use windows::core::w;
 use windows::Win32::{
   Foundation::{BOOL, HWND, LPARAM},
   UI::{
     Accessibility::HWINEVENTHOOK,
     WindowsAndMessaging::{
-      GetParent, EVENT_OBJECT_CREATE, EVENT_OBJECT_DESTROY, EVENT_OBJECT_HIDE,
-      EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_SHOW,
+      GetParent, RegisterShellHookWindow, RegisterWindowMessageW, CHILDID_SELF,
+      EVENT_OBJECT_CREATE, EVENT_OBJECT_DESTROY, EVENT_OBJECT_HIDE, EVENT_OBJECT_LOCATIONCHANGE,
+      EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_SHOW, EVENT_SYSTEM_FOREGROUND,
+      EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZESTART, HSHELL_HIGHBIT, HSHELL_REDRAW,
+      HSHELL_WINDOWACTIVATED, OBJID_WINDOW,
     },
   },
 };
 
-use crate::dock::{position, size, Window, GLOBAL_APPS, WINDOW};
+use crate::dock::{
+  self, coalesce, is_overlapped, reduce, Changes, DockEvent, EventKind, Recorder, SystemClock,
+  TitleDebounce, Window, WindowEvent, WindowInfo, GLOBAL_APPS, WINDOW,
+};
+use crate::fullscreen;
+use crate::tasklist;
+
+// Missing from the bindings, see winuser.h
+const HSHELL_FLASH: u32 = HSHELL_REDRAW | HSHELL_HIGHBIT;
+const HSHELL_RUDEAPPACTIVATED: u32 = HSHELL_WINDOWACTIVATED | HSHELL_HIGHBIT;
+// Titles are read at most this often per window, see `TitleDebounce`
+const TITLE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+// Events are only queued by the hook, the worker does the rest
+static EVENTS: OnceLock<Sender<(EventKind, isize)>> = OnceLock::new();
+// Last overlap sent to the dock, so only changes are reported
+static OVERLAPPED: AtomicBool = AtomicBool::new(false);
+// Set when `dock.trace_file` is, every resolved event is recorded
+static RECORDER: LazyLock<Mutex<Option<Recorder<File>>>> = LazyLock::new(|| {
+  let recorder = USER_SETTINGS.dock.trace_file.as_ref().and_then(|path| {
+    Recorder::open(path)
+      .map_err(|err| eprintln!("Failed to open trace file: {}", err))
+      .ok()
+  });
+  Mutex::new(recorder)
+});
 
 pub unsafe extern "system" fn enum_windows_proc(hwnd: HWND, _: LPARAM) -> BOOL {
   let mut global_apps = GLOBAL_APPS.lock().unwrap();
@@ -29,16 +73,32 @@ pub unsafe extern "system" fn enum_windows_proc(hwnd: HWND, _: LPARAM) -> BOOL {
       return true.into();
     }
 
+    // Windows open at startup are recorded as shown, so replays start from
+    // the same apps
+    record(&resolve(EventKind::Show, hwnd));
+    tasklist::button_created(hwnd);
+
     global_apps.push(Window {
       hwnd: hwnd.0,
       path: exe_path.clone(),
-      buffer: get_icon(&exe_path).unwrap_or_else(|_| Vec::new()),
+      title: get_window_text(hwnd),
+      buffer: get_cached_icon(&exe_path).unwrap_or_else(|_| Vec::new()),
+      attention: false,
+      button: Default::default(),
     });
   }
 
   true.into()
 }
 
+/// Starts the worker handling the events queued by the hooks
+pub fn init() {
+  let (sender, receiver) = mpsc::channel();
+  if EVENTS.set(sender).is_ok() {
+    thread::spawn(move || run(receiver));
+  }
+}
+
 pub unsafe extern "system" fn win_event_hook_callback(
   _hook_handle: HWINEVENTHOOK,
   _event_id: u32,
@@ -48,97 +108,212 @@ pub unsafe extern "system" fn win_event_hook_callback(
   _thread_id: u32,
   _timestamp: u32,
 ) {
-  let mut global_apps = GLOBAL_APPS.lock().unwrap();
+  // Carets, cursors and controls raise the same events
+  if _object_id != OBJID_WINDOW.0 || _child_id != CHILDID_SELF as i32 {
+    return;
+  }
 
-  fn update() {
-    thread::spawn(move || {
-      let binding = WINDOW.lock().unwrap();
-      let window = binding.as_ref().unwrap();
+  let kind = match _event_id {
+    EVENT_OBJECT_CREATE => EventKind::Create,
+    EVENT_OBJECT_DESTROY => EventKind::Destroy,
+    EVENT_OBJECT_SHOW => EventKind::Show,
+    EVENT_OBJECT_HIDE => EventKind::Hide,
+    EVENT_OBJECT_NAMECHANGE => EventKind::NameChange,
+    EVENT_OBJECT_LOCATIONCHANGE => EventKind::LocationChange,
+    EVENT_SYSTEM_FOREGROUND => EventKind::Foreground,
+    EVENT_SYSTEM_MINIMIZESTART => EventKind::MinimizeStart,
+    EVENT_SYSTEM_MINIMIZEEND => EventKind::MinimizeEnd,
+    _ => return,
+  };
 
-      window.set_position(position()).unwrap();
-      window.set_size(size()).unwrap();
+  queue(kind, _window_handle.0);
+}
 
-      window
-        .emit("set-apps", GLOBAL_APPS.lock().unwrap().to_vec())
-        .expect("Failed to set apps");
-    });
+fn queue(kind: EventKind, hwnd: isize) {
+  if let Some(sender) = EVENTS.get() {
+    sender.send((kind, hwnd)).unwrap_or_else(|_| ());
+  }
+}
+
+/// Makes `hwnd` receive the shell hook notifications the taskbar gets, so
+/// flashing windows still reach the dock while it is hidden
+///
+/// Must be called from the thread that created the window.
+pub fn watch_shell(hwnd: HWND) {
+  let message = unsafe { RegisterWindowMessageW(w!("SHELLHOOK")) };
+  if message == 0 || !unsafe { RegisterShellHookWindow(hwnd) }.as_bool() {
+    eprintln!("Failed to register shell hook window");
+    return;
   }
 
-  match _event_id {
-    EVENT_OBJECT_SHOW | EVENT_OBJECT_CREATE => {
-      if "Shell_TrayWnd" == get_class(_window_handle).expect("Failed to get class") {
-        hide_taskbar(true);
+  subclass_window(
+    hwnd,
+    Box::new(move |_, msg, wparam, lparam| {
+      if msg != message {
+        return None;
       }
 
-      if global_apps
-        .iter()
-        .any(|window| window.hwnd == _window_handle.0)
-      {
-        return;
+      match wparam.0 as u32 {
+        HSHELL_FLASH => queue(EventKind::Flash, lparam.0),
+        // Without the high bit only the title or icon changed
+        HSHELL_REDRAW => queue(EventKind::NameChange, lparam.0),
+        HSHELL_WINDOWACTIVATED | HSHELL_RUDEAPPACTIVATED => queue(EventKind::Foreground, lparam.0),
+        _ => {}
       }
 
-      if is_real_window(_window_handle, false) {
-        let exe_path = exe_path(_window_handle).unwrap_or_default();
-        global_apps.push(Window {
-          hwnd: _window_handle.0,
-          path: exe_path.clone(),
-          buffer: get_icon(&exe_path).expect("Failed to get icon"),
-        });
+      None
+    }),
+  )
+  .unwrap_or_else(|err| eprintln!("Failed to watch shell hooks: {}", err));
+}
+
+// Handles the events in batches, a batch collects everything that arrives
+// within `dock.coalesce_ms` of its first event. Name changes wait for
+// `TITLE_DEBOUNCE` on top of that and join the batch they are due in.
+fn run(receiver: Receiver<(EventKind, isize)>) {
+  let window = Duration::from_millis(USER_SETTINGS.dock.coalesce_ms);
+  let mut titles = TitleDebounce::new(TITLE_DEBOUNCE, SystemClock);
 
-        update();
+  loop {
+    let first = match titles.deadline() {
+      Some(deadline) => {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+          Ok(event) => Some(event),
+          Err(RecvTimeoutError::Timeout) => None,
+          Err(RecvTimeoutError::Disconnected) => return,
+        }
       }
-    }
-    EVENT_OBJECT_DESTROY => {
-      if global_apps
-        .iter()
-        .any(|window| window.hwnd == _window_handle.0)
-      {
-        global_apps.retain(|window| window.hwnd != _window_handle.0);
+      None => match receiver.recv() {
+        Ok(event) => Some(event),
+        Err(_) => return,
+      },
+    };
 
-        update();
+    let mut events = Vec::new();
+    if let Some(event) = first {
+      events.push(event);
+      let deadline = Instant::now() + window;
+
+      while let Ok(event) =
+        receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+      {
+        events.push(event);
       }
     }
-    EVENT_OBJECT_NAMECHANGE => {
-      if global_apps
-        .iter()
-        .any(|window| window.hwnd == _window_handle.0)
-      {
-        // Todo
-      } else if is_real_window(_window_handle, false) {
-        let exe_path = exe_path(_window_handle).unwrap_or_default();
-        global_apps.push(Window {
-          hwnd: _window_handle.0,
-          path: exe_path.clone(),
-          buffer: get_icon(&exe_path).expect("Failed to get icon"),
-        });
-
-        update();
+
+    let mut events = titles.defer(events);
+    events.extend(titles.due());
+    if events.is_empty() {
+      continue;
+    }
+
+    let events = coalesce(events)
+      .into_iter()
+      .map(|(kind, hwnd)| resolve(kind, HWND(hwnd)))
+      .collect::<Vec<_>>();
+    events.iter().for_each(record);
+    apply(&events);
+  }
+}
+
+/// Reads the attributes of the window `kind` needs
+pub fn resolve(kind: EventKind, hwnd: HWND) -> WindowEvent {
+  let window = match kind {
+    EventKind::Create | EventKind::Show | EventKind::Hide | EventKind::NameChange => WindowInfo {
+      hwnd: hwnd.0,
+      class: get_class(hwnd).unwrap_or_default(),
+      title: get_window_text(hwnd),
+      exe: exe_path(hwnd).unwrap_or_default(),
+      style: get_styles(hwnd).0,
+      ex_style: get_ex_styles(hwnd).0,
+      parent: unsafe { GetParent(hwnd) }.0,
+      real: is_real_window(hwnd, false),
+    },
+    // Destroyed windows have nothing left to read
+    _ => WindowInfo {
+      hwnd: hwnd.0,
+      ..Default::default()
+    },
+  };
+
+  WindowEvent { kind, window }
+}
+
+fn record(event: &WindowEvent) {
+  if let Some(recorder) = RECORDER.lock().unwrap().as_mut() {
+    recorder
+      .record(event)
+      .unwrap_or_else(|err| eprintln!("Failed to record window event: {}", err));
+  }
+}
+
+fn apply(events: &[WindowEvent]) {
+  let mut changes = Changes::default();
+  let added = {
+    let mut global_apps = GLOBAL_APPS.lock().unwrap();
+    for event in events {
+      reduce(&mut global_apps, event, &mut changes);
+    }
+
+    // Windows moving over the dock only matter for intelligent hide
+    if changes.moved && USER_SETTINGS.dock.mode == DockMode::IntelligentHide {
+      let overlapped = is_overlapped(&global_apps);
+      if OVERLAPPED.swap(overlapped, Ordering::Relaxed) != overlapped {
+        dock::send(DockEvent::OverlapChanged(overlapped));
       }
     }
-    EVENT_OBJECT_HIDE => {
-      if global_apps
-        .iter()
-        .any(|window| window.hwnd == _window_handle.0)
-      {
-        let parent = GetParent(_window_handle);
-        if parent.0 != 0 {
-          for app in global_apps.iter_mut() {
-            if app.hwnd == _window_handle.0 {
-              app.hwnd = parent.0;
-              break;
-            }
-          }
-
-          update();
-        } else {
-          if !is_real_window(_window_handle, false) {
-            global_apps.retain(|window| window.hwnd != _window_handle.0);
-
-            update();
-          }
-        }
+
+    global_apps
+      .iter()
+      .filter(|app| changes.added.contains(&app.hwnd))
+      .map(|app| (app.hwnd, app.path.clone()))
+      .collect::<Vec<_>>()
+  };
+
+  // Icons are extracted without holding the lock
+  if !added.is_empty() {
+    let icons = added
+      .into_iter()
+      .map(|(hwnd, path)| (hwnd, get_cached_icon(&path).unwrap_or_default()))
+      .collect::<Vec<_>>();
+
+    for app in GLOBAL_APPS.lock().unwrap().iter_mut() {
+      if let Some((_, icon)) = icons.iter().find(|(hwnd, _)| *hwnd == app.hwnd) {
+        app.buffer = icon.clone();
       }
     }
-    _ => {}
+  }
+
+  for hwnd in &changes.added {
+    tasklist::button_created(HWND(*hwnd));
+  }
+
+  if changes.taskbar_shown {
+    hide_taskbar(true);
+  }
+
+  if changes.apps {
+    dock::update();
+  }
+
+  // Any batch may have moved or replaced the foreground window
+  fullscreen::refresh();
+
+  // Titles are shown by the dock's tooltips and by the window lists of the
+  // thumbnails and the switcher
+  if !changes.titles.is_empty() {
+    if let Some(app_handle) = APP_HANDLE.lock().unwrap().as_ref() {
+      app_handle
+        .emit("window-titles", changes.titles)
+        .unwrap_or_else(|_| ());
+    }
+  }
+
+  if let Some(window) = WINDOW.lock().unwrap().as_ref() {
+    if !changes.attention.is_empty() {
+      window
+        .emit("window-attention", changes.attention)
+        .unwrap_or_else(|_| ());
+    }
   }
 }
@@ -0,0 +1,90 @@
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::thread;
+
+use util::{activate_window, subclass_window, USER_SETTINGS};
+use windows::Win32::Foundation::{HWND, LRESULT};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+  RegisterHotKey, HOT_KEY_MODIFIERS, MOD_NOREPEAT,
+};
+use windows::Win32::UI::WindowsAndMessaging::WM_HOTKEY;
+
+pub use shell::hotkeys::*;
+
+use crate::dock::{self, DockEvent, GLOBAL_APPS};
+use crate::launcher;
+use crate::switcher;
+
+// Registered under their index plus one, ids start at 1
+static BINDINGS: LazyLock<Mutex<Vec<Binding>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+static ERRORS: LazyLock<Mutex<Vec<HotkeyError>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Registers the hotkeys from the settings on the dock window `hwnd`
+///
+/// Must be called from the thread that created the window, `WM_HOTKEY` is
+/// posted to it.
+pub fn init(hwnd: HWND) {
+  let (bindings, mut errors) = bindings(&USER_SETTINGS.hotkeys);
+
+  let mut registered = Vec::new();
+  for binding in bindings {
+    let id = registered.len() as i32 + 1;
+    let modifiers = HOT_KEY_MODIFIERS(binding.accelerator.modifiers.flags()) | MOD_NOREPEAT;
+
+    match unsafe { RegisterHotKey(hwnd, id, modifiers, binding.accelerator.key) } {
+      Ok(()) => registered.push(binding),
+      Err(err) => errors.push(HotkeyError::Unavailable {
+        accelerator: binding.accelerator.to_string(),
+        action: binding.action,
+        reason: err.message().to_string(),
+      }),
+    }
+  }
+
+  for error in &errors {
+    eprintln!("{}", error);
+  }
+  *BINDINGS.lock().unwrap() = registered;
+  *ERRORS.lock().unwrap() = errors;
+
+  subclass_window(
+    hwnd,
+    Box::new(|_, msg, wparam, _| {
+      if msg != WM_HOTKEY {
+        return None;
+      }
+
+      // Handlers talk to other windows, the message loop isn't held up
+      let id = wparam.0;
+      thread::spawn(move || dispatch(id));
+      Some(LRESULT(0))
+    }),
+  )
+  .unwrap_or_else(|err| eprintln!("Failed to watch hotkeys: {}", err));
+}
+
+/// Hotkeys from the settings that couldn't be registered
+pub fn errors() -> Vec<HotkeyError> {
+  ERRORS.lock().unwrap().clone()
+}
+
+fn dispatch(id: usize) {
+  let Some(binding) = BINDINGS.lock().unwrap().get(id.wrapping_sub(1)).copied() else {
+    return;
+  };
+
+  match binding.action {
+    HotkeyAction::ToggleLauncher => launcher::toggle(),
+    HotkeyAction::ToggleDock => dock::send(DockEvent::Toggled),
+    HotkeyAction::FocusDockItem(index) => focus_dock_item(index),
+    HotkeyAction::ShowSwitcher => switcher::cycle(binding.accelerator.modifiers),
+  }
+}
+
+// Brings the dock's Nth app to the front
+fn focus_dock_item(index: usize) {
+  let hwnd = GLOBAL_APPS.lock().unwrap().get(index).map(|app| app.hwnd);
+  if let Some(hwnd) = hwnd {
+    activate_window(HWND(hwnd));
+  }
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use tauri::Emitter;
+use tauri::WindowEvent;
+use util::APP_HANDLE;
+
+// Logical size of the launcher
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 420.0;
+
+static WINDOW: LazyLock<Mutex<Option<tauri::WebviewWindow>>> = LazyLock::new(|| Mutex::new(None));
+
+pub fn init() {
+  let window = setup_window().expect("Failed to setup launcher window");
+
+  // Clicking anywhere else dismisses it, like the Start menu. Hiding can
+  // send this event right away, so it doesn't go through `WINDOW`.
+  let launcher = window.clone();
+  window.on_window_event(move |event| {
+    if let WindowEvent::Focused(false) = event {
+      launcher.hide().unwrap_or_else(|_| ());
+    }
+  });
+
+  *WINDOW.lock().unwrap() = Some(window);
+}
+
+/// Shows the launcher centered on its monitor with an empty query
+pub fn show() {
+  let window = WINDOW.lock().unwrap().clone();
+  if let Some(window) = window {
+    window.center().unwrap_or_else(|_| ());
+    window.show().unwrap_or_else(|_| ());
+    window.set_focus().unwrap_or_else(|_| ());
+    window.emit("launcher-show", ()).unwrap_or_else(|_| ());
+  }
+}
+
+pub fn hide() {
+  let window = WINDOW.lock().unwrap().clone();
+  if let Some(window) = window {
+    window.hide().unwrap_or_else(|_| ());
+  }
+}
+
+pub fn toggle() {
+  let visible = WINDOW
+    .lock()
+    .unwrap()
+    .as_ref()
+    .is_some_and(|window| window.is_visible().unwrap_or(false));
+
+  if visible {
+    hide();
+  } else {
+    show();
+  }
+}
+
+fn setup_window() -> Result<tauri::WebviewWindow, ()> {
+  let window = tauri::WebviewWindowBuilder::new(
+    APP_HANDLE
+      .lock()
+      .unwrap()
+      .as_ref()
+      .unwrap_or_else(|| panic!("Failed to get app handle")),
+    "launcher",
+    tauri::WebviewUrl::App(PathBuf::from("/#/launcher")),
+  )
+  .title("Launcher")
+  .inner_size(WIDTH, HEIGHT)
+  .transparent(true)
+  .always_on_top(true)
+  .decorations(false)
+  .resizable(false)
+  .maximizable(false)
+  .minimizable(false)
+  .skip_taskbar(true)
+  .visible(false)
+  .build()
+  .expect("Failed to build launcher window");
+
+  Ok(window)
+}
@@ -1,54 +1,53 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::Mutex;
+use std::thread;
 
-use backdrop::enable_blur;
-use serde::de::IntoDeserializer;
+use backdrop::Backdrop;
 use tauri::WebviewWindow;
 
+use crate::backdrops;
+use crate::fullscreen;
+use crate::theme;
+
+use util::to_physical;
+use util::AppBar;
+use util::AppBarNotification;
 use util::ScreenGeometry;
 use util::APP_HANDLE;
 use util::USER_SETTINGS;
 
-use windows::Win32::Foundation::BOOL;
-use windows::Win32::UI::HiDpi::GetDpiForWindow;
-use windows::Win32::UI::Shell::SHAppBarMessage;
 use windows::Win32::UI::WindowsAndMessaging::MoveWindow;
 
 use windows::Win32::Foundation::HWND;
-use windows::Win32::Foundation::LPARAM;
 use windows::Win32::Foundation::RECT;
-use windows::Win32::UI::Shell::APPBARDATA;
 
 use windows::Win32::UI::Shell::ABE_TOP;
-use windows::Win32::UI::Shell::ABM_NEW;
-use windows::Win32::UI::Shell::ABM_QUERYPOS;
-use windows::Win32::UI::Shell::ABM_REMOVE;
-use windows::Win32::UI::Shell::ABM_SETAUTOHIDEBAR;
-use windows::Win32::UI::Shell::ABM_SETPOS;
 use windows::Win32::UI::WindowsAndMessaging::SetMenu;
 use windows::Win32::UI::WindowsAndMessaging::SetWindowLongPtrA;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
 use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
-use windows::Win32::UI::WindowsAndMessaging::USER_DEFAULT_SCREEN_DPI;
+use windows::Win32::UI::WindowsAndMessaging::HWND_BOTTOM;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOMOVE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
 use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
 
 static WINDOW_HWND: LazyLock<Mutex<HWND>> = LazyLock::new(|| Mutex::new(HWND::default()));
+static APPBAR: LazyLock<Mutex<Option<AppBar>>> = LazyLock::new(|| Mutex::new(None));
 
 pub fn init() {
   let window = setup_window().unwrap();
   let hwnd = HWND(window.hwnd().unwrap().0);
   *WINDOW_HWND.lock().unwrap() = hwnd;
 
-  unsafe {
-    add().expect("Failed to add app bar");
-
-    SetWindowLongPtrA(hwnd, GWL_EXSTYLE, WS_EX_TOOLWINDOW.0 as isize);
+  unsafe { SetWindowLongPtrA(hwnd, GWL_EXSTYLE, WS_EX_TOOLWINDOW.0 as isize) };
 
-    // Set window size using MoveWindow
-    let width = ScreenGeometry::new().width;
-    let height = USER_SETTINGS.height;
-    MoveWindow(hwnd, 0, 0, width, height, true).expect("Failed to set window size");
-  }
+  add().expect("Failed to add app bar");
+  backdrops::watch(hwnd);
+  theme::watch(hwnd);
 }
 
 fn setup_window() -> Result<tauri::WebviewWindow, ()> {
@@ -62,7 +61,12 @@ fn setup_window() -> Result<tauri::WebviewWindow, ()> {
     tauri::WebviewUrl::App(PathBuf::from("/#/menubar?blur=false")),
   )
   .title("Menubar")
-  .transparent(USER_SETTINGS.menubar.blur)
+  .transparent(
+    theme::current()
+      .backdrops("menubar")
+      .iter()
+      .any(|backdrop| *backdrop != Backdrop::None),
+  )
   .always_on_top(true)
   .decorations(false)
   .shadow(false)
@@ -120,62 +124,85 @@ pub fn create_round_window() -> Result<WebviewWindow, String> {
   Ok(webview_window)
 }
 
-unsafe fn get_pos(appbar_data: *mut APPBARDATA, height: i32) -> APPBARDATA {
-  let geometry = ScreenGeometry::new();
-
+// Reserves the top of the screen and moves the menubar into the granted rect
+fn reposition() {
   let hwnd = *WINDOW_HWND.lock().unwrap();
-  let dpi = GetDpiForWindow::<HWND>(hwnd);
-  let device_pixel_ratio = dpi as f64 / USER_DEFAULT_SCREEN_DPI as f64;
-  let bar_height = height * (device_pixel_ratio as i32);
-
-  (*appbar_data).rc = RECT {
-    left: geometry.x,
-    top: geometry.y,
-    right: geometry.x + bar_height,
-    bottom: geometry.y + bar_height,
+  let geometry = ScreenGeometry::new();
+  let height = to_physical(hwnd, USER_SETTINGS.height);
+
+  let rect = match APPBAR.lock().unwrap().as_ref() {
+    Some(appbar) => appbar.set_pos(RECT {
+      left: geometry.x,
+      top: geometry.y,
+      right: geometry.width,
+      bottom: geometry.y + height,
+    }),
+    None => return,
   };
 
-  *appbar_data
+  unsafe {
+    MoveWindow(
+      hwnd,
+      rect.left,
+      rect.top,
+      rect.right - rect.left,
+      rect.bottom - rect.top,
+      true,
+    )
+  }
+  .unwrap_or_else(|err| eprintln!("Failed to move menubar: {}", err));
 }
 
-pub unsafe fn add() -> Result<(), &'static str> {
-  let hwnd = *WINDOW_HWND.lock().unwrap();
-  let (width, height) = (ScreenGeometry::new().width, USER_SETTINGS.height);
-
-  let default_appbar_data: *mut APPBARDATA = &mut APPBARDATA {
-    cbSize: std::mem::size_of::<APPBARDATA>() as u32,
-    hWnd: hwnd,
-    uEdge: ABE_TOP,
-    rc: RECT::default(),
-    lParam: LPARAM(0),
-    uCallbackMessage: 0,
-  };
+// Fullscreen apps go above the menubar, like they do with the taskbar
+fn set_topmost(hwnd: HWND, topmost: bool) {
+  let insert_after = if topmost { HWND_TOPMOST } else { HWND_BOTTOM };
 
-  SHAppBarMessage(ABM_NEW, default_appbar_data);
+  unsafe {
+    SetWindowPos(
+      hwnd,
+      insert_after,
+      0,
+      0,
+      0,
+      0,
+      SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+    )
+  }
+  .unwrap_or_else(|err| eprintln!("Failed to reorder menubar: {}", err));
+}
 
-  let auto_hide = false;
-  let mut taskbar_pos = get_pos(default_appbar_data, height);
-  if auto_hide {
-    taskbar_pos.lParam = windows::Win32::Foundation::LPARAM(1);
+/// Lets fullscreen apps cover the menubar, see [`crate::fullscreen`]
+pub fn set_fullscreen(fullscreen: bool) {
+  set_topmost(*WINDOW_HWND.lock().unwrap(), !fullscreen);
+}
 
-    SHAppBarMessage(ABM_SETAUTOHIDEBAR, &mut taskbar_pos as *mut APPBARDATA);
-    taskbar_pos.rc.bottom = taskbar_pos.rc.top + height;
+pub fn add() -> Result<(), String> {
+  let hwnd = *WINDOW_HWND.lock().unwrap();
 
-    SHAppBarMessage(ABM_QUERYPOS, &mut taskbar_pos as *mut APPBARDATA);
-    SHAppBarMessage(ABM_SETPOS, &mut taskbar_pos as *mut APPBARDATA);
-    MoveWindow(hwnd, 0, 0, width, height, BOOL::from(true)).expect("Failed to move window");
-  } else {
-    SHAppBarMessage(ABM_SETPOS, &mut taskbar_pos as *mut APPBARDATA);
-    MoveWindow(hwnd, 0, 0, width, height, BOOL::from(true)).expect("Failed to move window");
-  }
+  let handler = Arc::new(|notification: AppBarNotification| match notification {
+    AppBarNotification::PosChanged => {
+      thread::spawn(reposition);
+    }
+    AppBarNotification::FullscreenApp(fullscreen) => fullscreen::shell_notified(fullscreen),
+    _ => {}
+  });
+
+  let appbar = AppBar::register(hwnd, ABE_TOP, handler)?;
+  *APPBAR.lock().unwrap() = Some(appbar);
+  reposition();
 
   if USER_SETTINGS.menubar.round_corners {
     create_round_window().unwrap();
-  } else if USER_SETTINGS.menubar.blur {
-    enable_blur(hwnd, &USER_SETTINGS.menubar.color, true);
+  } else {
+    backdrops::register("menubar", hwnd, theme::current().backdrops("menubar"));
   }
 
-  if USER_SETTINGS.menubar.round_corners && USER_SETTINGS.menubar.blur {
+  if USER_SETTINGS.menubar.round_corners
+    && theme::current()
+      .backdrops("menubar")
+      .iter()
+      .any(|backdrop| *backdrop != Backdrop::None)
+  {
     println!("Blur with round corners is not supported, the cornes will have no blur effect.");
   }
 
@@ -183,15 +210,5 @@ pub unsafe fn add() -> Result<(), &'static str> {
 }
 
 pub fn remove() {
-  let hwnd = *WINDOW_HWND.lock().unwrap();
-  let mut default_appbar_data = APPBARDATA {
-    cbSize: std::mem::size_of::<APPBARDATA>() as u32,
-    hWnd: hwnd,
-    uCallbackMessage: 0,
-    uEdge: ABE_TOP,
-    rc: RECT::default(),
-    lParam: LPARAM(0),
-  };
-
-  unsafe { SHAppBarMessage(ABM_REMOVE, &mut default_appbar_data as *mut APPBARDATA) };
+  APPBAR.lock().unwrap().take();
 }
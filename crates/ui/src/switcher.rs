@@ -0,0 +1,183 @@
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::Emitter;
+use tauri::WindowEvent;
+use util::{activate_window, APP_HANDLE};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+  GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+};
+use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+pub use shell::switcher::*;
+
+use crate::dock::{Window, GLOBAL_APPS};
+use crate::hotkeys::Modifiers;
+
+// Logical size of the switcher
+const WIDTH: f64 = 720.0;
+const HEIGHT: f64 = 360.0;
+// How often held modifiers are checked while the switcher is open
+const RELEASE_POLL: Duration = Duration::from_millis(20);
+
+static WINDOW: LazyLock<Mutex<Option<tauri::WebviewWindow>>> = LazyLock::new(|| Mutex::new(None));
+static HISTORY: LazyLock<Mutex<FocusHistory>> = LazyLock::new(|| Mutex::new(FocusHistory::new()));
+// Bumped every time the switcher opens, older release watchers stop
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Dock windows in most recently used order, and their apps
+#[derive(Serialize, Clone, Debug)]
+pub struct RecentWindows {
+  pub windows: Vec<Window>,
+  // Executable paths, most recently used first
+  pub apps: Vec<String>,
+}
+
+/// Sent with `switcher-show`, so the list is ready before the modifiers can
+/// be released
+#[derive(Serialize, Clone, Debug)]
+pub struct SwitcherShow {
+  pub windows: Vec<Window>,
+  pub selected: usize,
+}
+
+pub fn init() {
+  let window = setup_window().expect("Failed to setup switcher window");
+
+  // Like the launcher it goes away when anything else is clicked
+  let switcher = window.clone();
+  window.on_window_event(move |event| {
+    if let WindowEvent::Focused(false) = event {
+      switcher.hide().unwrap_or_else(|_| ());
+    }
+  });
+
+  *WINDOW.lock().unwrap() = Some(window);
+}
+
+/// Records that `hwnd`, a window of the executable `app`, came to the front
+pub fn focused(hwnd: isize, app: &str) {
+  HISTORY.lock().unwrap().focused(hwnd, app);
+}
+
+/// The dock's windows, most recently focused first
+pub fn recent_windows() -> RecentWindows {
+  let windows = GLOBAL_APPS.lock().unwrap().to_vec();
+
+  let mut history = HISTORY.lock().unwrap();
+  // Closed windows leave the dock, they are forgotten here too
+  history.retain(&windows.iter().map(|window| window.hwnd).collect::<Vec<_>>());
+
+  RecentWindows {
+    windows: history.order(windows, |window| window.hwnd),
+    apps: history.apps().to_vec(),
+  }
+}
+
+/// Opens the switcher, or moves to the next window if it is already open
+///
+/// Once `modifiers` are all released the selected window is activated, as
+/// with Alt+Tab.
+pub fn cycle(modifiers: Modifiers) {
+  let Some(window) = WINDOW.lock().unwrap().clone() else {
+    return;
+  };
+
+  if window.is_visible().unwrap_or(false) {
+    window.emit("switcher-next", ()).unwrap_or_else(|_| ());
+    return;
+  }
+
+  // The current window is listed first, the switcher starts on the one
+  // before it
+  let foreground = unsafe { GetForegroundWindow() }.0;
+  let windows = recent_windows().windows;
+  let selected = match windows.first() {
+    Some(first) if first.hwnd == foreground => 1.min(windows.len() - 1),
+    _ => 0,
+  };
+
+  window.center().unwrap_or_else(|_| ());
+  window.show().unwrap_or_else(|_| ());
+  window.set_focus().unwrap_or_else(|_| ());
+  window
+    .emit("switcher-show", SwitcherShow { windows, selected })
+    .unwrap_or_else(|_| ());
+
+  let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+  if !modifiers.is_empty() {
+    thread::spawn(move || watch_release(window, modifiers, generation));
+  }
+}
+
+// Tells the switcher to commit once none of `modifiers` is held anymore
+fn watch_release(window: tauri::WebviewWindow, modifiers: Modifiers, generation: usize) {
+  let keys = [
+    (modifiers.alt, &[VK_MENU][..]),
+    (modifiers.ctrl, &[VK_CONTROL][..]),
+    (modifiers.shift, &[VK_SHIFT][..]),
+    (modifiers.win, &[VK_LWIN, VK_RWIN][..]),
+  ];
+  let held = |key: &VIRTUAL_KEY| unsafe { GetAsyncKeyState(key.0 as i32) } < 0;
+
+  loop {
+    thread::sleep(RELEASE_POLL);
+    if GENERATION.load(Ordering::SeqCst) != generation || !window.is_visible().unwrap_or(false) {
+      return;
+    }
+
+    if !keys
+      .iter()
+      .any(|(modifier, keys)| *modifier && keys.iter().any(held))
+    {
+      window.emit("switcher-commit", ()).unwrap_or_else(|_| ());
+      return;
+    }
+  }
+}
+
+pub fn hide() {
+  let window = WINDOW.lock().unwrap().clone();
+  if let Some(window) = window {
+    window.hide().unwrap_or_else(|_| ());
+  }
+}
+
+/// Closes the switcher and brings `hwnd` to the front
+pub fn switch_to(hwnd: isize) {
+  hide();
+  activate_window(HWND(hwnd));
+}
+
+fn setup_window() -> Result<tauri::WebviewWindow, ()> {
+  let window = tauri::WebviewWindowBuilder::new(
+    APP_HANDLE
+      .lock()
+      .unwrap()
+      .as_ref()
+      .unwrap_or_else(|| panic!("Failed to get app handle")),
+    "switcher",
+    tauri::WebviewUrl::App(PathBuf::from("/#/switcher")),
+  )
+  .title("Switcher")
+  .inner_size(WIDTH, HEIGHT)
+  .transparent(true)
+  .always_on_top(true)
+  .decorations(false)
+  .resizable(false)
+  .maximizable(false)
+  .minimizable(false)
+  .skip_taskbar(true)
+  .visible(false)
+  .build()
+  .expect("Failed to build switcher window");
+
+  Ok(window)
+}
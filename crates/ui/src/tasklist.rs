@@ -0,0 +1,163 @@
+use std::mem;
+use std::sync::atomic::AtomicIsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::sync::OnceLock;
+use std::thread;
+
+use icons::hicon_to_png;
+use tauri::Emitter;
+use util::subclass_window;
+use windows::core::w;
+use windows::core::PCSTR;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::FARPROC;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::LibraryLoader::GetProcAddress;
+use windows::Win32::System::LibraryLoader::LoadLibraryA;
+use windows::Win32::UI::Shell::TBPFLAG;
+use windows::Win32::UI::Shell::TBPF_ERROR;
+use windows::Win32::UI::Shell::TBPF_INDETERMINATE;
+use windows::Win32::UI::Shell::TBPF_NORMAL;
+use windows::Win32::UI::Shell::TBPF_PAUSED;
+use windows::Win32::UI::WindowsAndMessaging::RegisterWindowMessageW;
+use windows::Win32::UI::WindowsAndMessaging::SendNotifyMessageW;
+use windows::Win32::UI::WindowsAndMessaging::HICON;
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
+
+use crate::dock::{update_button, ButtonUpdate, Changes, ProgressState, GLOBAL_APPS, WINDOW};
+
+// Sent by `ITaskbarList3` to the taskman window, the window handle is in
+// `wparam`, same as ManagedShell handles them
+const WM_PROGRESS_VALUE: u32 = WM_USER + 64;
+const WM_PROGRESS_STATE: u32 = WM_USER + 65;
+const WM_OVERLAY_ICON: u32 = WM_USER + 75;
+
+// Undocumented, exported by user32 since Windows 2000
+type SetTaskmanWindowFn = unsafe extern "system" fn(HWND) -> BOOL;
+type GetTaskmanWindowFn = unsafe extern "system" fn() -> HWND;
+
+static UPDATES: OnceLock<Sender<(isize, ButtonUpdate)>> = OnceLock::new();
+// `TaskbarButtonCreated`, apps wait for it before using `ITaskbarList3`
+static BUTTON_CREATED: OnceLock<u32> = OnceLock::new();
+// Taskman window from before simpletb, Explorer's taskbar
+static PREVIOUS: AtomicIsize = AtomicIsize::new(0);
+
+fn user32_proc(name: &str) -> FARPROC {
+  let name = format!("{}\0", name);
+  unsafe {
+    let hmodule = LoadLibraryA(PCSTR("user32.dll\0".as_ptr() as *const u8)).ok()?;
+    GetProcAddress(hmodule, PCSTR(name.as_ptr()))
+  }
+}
+
+fn set_taskman_window(hwnd: HWND) -> bool {
+  user32_proc("SetTaskmanWindow").is_some_and(|address| {
+    let set_taskman_window: SetTaskmanWindowFn = unsafe { mem::transmute(address) };
+    unsafe { set_taskman_window(hwnd) }.as_bool()
+  })
+}
+
+fn taskman_window() -> HWND {
+  user32_proc("GetTaskmanWindow")
+    .map(|address| {
+      let get_taskman_window: GetTaskmanWindowFn = unsafe { mem::transmute(address) };
+      unsafe { get_taskman_window() }
+    })
+    .unwrap_or_default()
+}
+
+/// Makes `hwnd` the window `ITaskbarList3` reports to, so progress and overlay
+/// icons reach the dock while Explorer's taskbar is hidden
+///
+/// Must be called from the thread that created the window.
+pub fn host(hwnd: HWND) {
+  let (sender, receiver) = mpsc::channel();
+  if UPDATES.set(sender).is_err() {
+    return;
+  }
+  thread::spawn(move || run(receiver));
+
+  BUTTON_CREATED.get_or_init(|| unsafe { RegisterWindowMessageW(w!("TaskbarButtonCreated")) });
+
+  PREVIOUS.store(taskman_window().0, Ordering::SeqCst);
+  if !set_taskman_window(hwnd) {
+    eprintln!("Failed to set taskman window");
+    return;
+  }
+
+  subclass_window(
+    hwnd,
+    Box::new(|_, msg, wparam, lparam| {
+      let update = match msg {
+        WM_PROGRESS_VALUE => ButtonUpdate::ProgressValue(lparam.0 as u32),
+        WM_PROGRESS_STATE => ButtonUpdate::ProgressState(progress_state(lparam.0)),
+        // The icon may be destroyed once the call returns, it is read right away
+        WM_OVERLAY_ICON => {
+          let hicon = HICON(lparam.0);
+          ButtonUpdate::Overlay(if hicon.is_invalid() {
+            Vec::new()
+          } else {
+            hicon_to_png(&hicon).unwrap_or_default()
+          })
+        }
+        _ => return None,
+      };
+
+      if let Some(sender) = UPDATES.get() {
+        sender
+          .send((wparam.0 as isize, update))
+          .unwrap_or_else(|_| ());
+      }
+      Some(Default::default())
+    }),
+  )
+  .unwrap_or_else(|err| eprintln!("Failed to host taskbar list: {}", err));
+}
+
+/// Gives the taskman window back to Explorer
+pub fn remove() {
+  let previous = PREVIOUS.swap(0, Ordering::SeqCst);
+  if previous != 0 {
+    set_taskman_window(HWND(previous));
+  }
+}
+
+/// Tells `hwnd` its taskbar button exists, the cue for apps to set progress
+/// and overlays
+pub fn button_created(hwnd: HWND) {
+  if let Some(message) = BUTTON_CREATED.get() {
+    unsafe { SendNotifyMessageW(hwnd, *message, WPARAM(0), LPARAM(0)) }.unwrap_or_else(|_| ());
+  }
+}
+
+fn progress_state(flag: isize) -> ProgressState {
+  match TBPFLAG(flag as i32) {
+    TBPF_INDETERMINATE => ProgressState::Indeterminate,
+    TBPF_NORMAL => ProgressState::Normal,
+    TBPF_ERROR => ProgressState::Error,
+    TBPF_PAUSED => ProgressState::Paused,
+    _ => ProgressState::None,
+  }
+}
+
+// Applies the updates in order, off the window thread
+fn run(receiver: Receiver<(isize, ButtonUpdate)>) {
+  while let Ok((hwnd, update)) = receiver.recv() {
+    let mut changes = Changes::default();
+    update_button(&mut GLOBAL_APPS.lock().unwrap(), hwnd, update, &mut changes);
+
+    if changes.buttons.is_empty() {
+      continue;
+    }
+    if let Some(window) = WINDOW.lock().unwrap().as_ref() {
+      window
+        .emit("window-button", changes.buttons)
+        .unwrap_or_else(|_| ());
+    }
+  }
+}
@@ -0,0 +1,360 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::thread;
+
+use backdrop::read_registry_dword;
+use backdrop::Backdrop;
+use backdrop::Color;
+use serde::Deserialize;
+use serde::Serialize;
+use tauri::Emitter;
+
+use util::backdrop_preferences;
+use util::config_dir;
+use util::subclass_window;
+use util::APP_HANDLE;
+use util::USER_SETTINGS;
+
+use windows::core::w;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Registry::HKEY_CURRENT_USER;
+use windows::Win32::UI::WindowsAndMessaging::WM_DWMCOLORIZATIONCOLORCHANGED;
+use windows::Win32::UI::WindowsAndMessaging::WM_SETTINGCHANGE;
+
+use crate::backdrops;
+
+// Windows that have a backdrop and a corner radius in themes
+pub const SURFACES: [&str; 3] = ["menubar", "dock", "settings"];
+
+const DEFAULT_THEME: &str = r##"{
+  "colors": {
+    "accent": "accent"
+  },
+  "dark": {
+    "colors": {
+      "background": "#101010",
+      "foreground": "#ffffff",
+      "muted": "#a3a3a3",
+      "border": "#ffffff0a",
+      "tile": "#ffffff0d",
+      "tile.hover": "#ffffff26",
+      "indicator": "#a3a3a3",
+      "indicator.active": "$accent"
+    }
+  },
+  "light": {
+    "colors": {
+      "background": "#f3f3f3",
+      "foreground": "#000000",
+      "muted": "#525252",
+      "border": "#0000000f",
+      "tile": "#0000000d",
+      "tile.hover": "#00000026",
+      "indicator": "#525252",
+      "indicator.active": "$accent"
+    }
+  },
+  "surfaces": {
+    "menubar": { "radius": 0 },
+    "dock": { "radius": 8 },
+    "settings": { "radius": 8 }
+  }
+}"##;
+
+/// Light/dark preference and accent colour of the system
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SystemTheme {
+  pub dark: bool,
+  pub accent: Color,
+}
+
+/// A theme file as written by the user
+///
+/// Colour tokens are CSS colours, `accent` for the system accent colour or
+/// `$token` to reuse another token. `light` and `dark` override the base
+/// tokens and surfaces depending on the system preference.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ThemeFile {
+  pub colors: BTreeMap<String, String>,
+  pub surfaces: BTreeMap<String, SurfaceFile>,
+  pub light: ThemeVariant,
+  pub dark: ThemeVariant,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ThemeVariant {
+  pub colors: BTreeMap<String, String>,
+  pub surfaces: BTreeMap<String, SurfaceFile>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SurfaceFile {
+  #[serde(deserialize_with = "backdrop_preferences")]
+  pub backdrop: Vec<Backdrop>,
+  pub radius: Option<u32>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Surface {
+  pub backdrop: Vec<Backdrop>,
+  pub radius: u32,
+}
+
+/// Theme with every token resolved against the system theme
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Theme {
+  pub name: String,
+  pub dark: bool,
+  pub accent: Color,
+  pub colors: BTreeMap<String, Color>,
+  pub surfaces: BTreeMap<String, Surface>,
+}
+
+static THEME: LazyLock<Mutex<Theme>> = LazyLock::new(|| Mutex::new(load()));
+
+impl SystemTheme {
+  pub fn query() -> Self {
+    let personalize = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize");
+    let dark =
+      read_registry_dword(HKEY_CURRENT_USER, personalize, w!("AppsUseLightTheme")) == Some(0);
+
+    // Stored as 0xAABBGGRR
+    let accent = read_registry_dword(
+      HKEY_CURRENT_USER,
+      w!("Software\\Microsoft\\Windows\\DWM"),
+      w!("AccentColor"),
+    )
+    .map(|abgr| {
+      let [r, g, b, _] = abgr.to_le_bytes();
+      Color::rgb(r, g, b)
+    })
+    .unwrap_or(Color::rgb(0x00, 0x78, 0xd4));
+
+    Self { dark, accent }
+  }
+}
+
+impl Theme {
+  pub fn backdrops(&self, surface: &str) -> Vec<Backdrop> {
+    self
+      .surfaces
+      .get(surface)
+      .map(|surface| surface.backdrop.clone())
+      .unwrap_or_default()
+  }
+}
+
+impl ThemeFile {
+  pub fn builtin() -> Self {
+    serde_json::from_str(DEFAULT_THEME).expect("Failed to parse default theme")
+  }
+
+  // Layers `other` on top of this theme
+  fn merge(mut self, other: ThemeFile) -> Self {
+    self.colors.extend(other.colors);
+    merge_surfaces(&mut self.surfaces, other.surfaces);
+    self.light.colors.extend(other.light.colors);
+    merge_surfaces(&mut self.light.surfaces, other.light.surfaces);
+    self.dark.colors.extend(other.dark.colors);
+    merge_surfaces(&mut self.dark.surfaces, other.dark.surfaces);
+    self
+  }
+}
+
+fn merge_surfaces(
+  surfaces: &mut BTreeMap<String, SurfaceFile>,
+  overrides: BTreeMap<String, SurfaceFile>,
+) {
+  for (name, overrides) in overrides {
+    let surface = surfaces.entry(name).or_default();
+    if !overrides.backdrop.is_empty() {
+      surface.backdrop = overrides.backdrop;
+    }
+    if overrides.radius.is_some() {
+      surface.radius = overrides.radius;
+    }
+  }
+}
+
+/// Resolves every token of `file` for the given system theme
+///
+/// Surfaces without a backdrop fall back to `fallback`, which comes from the
+/// `backdrop` section of the config.
+pub fn resolve(
+  name: &str,
+  file: &ThemeFile,
+  system: SystemTheme,
+  fallback: &BTreeMap<String, Vec<Backdrop>>,
+) -> Result<Theme, String> {
+  let variant = if system.dark { &file.dark } else { &file.light };
+
+  let mut tokens = file.colors.clone();
+  tokens.extend(variant.colors.clone());
+
+  let mut colors = BTreeMap::new();
+  for token in tokens.keys() {
+    colors.insert(token.clone(), resolve_token(token, &tokens, system, 0)?);
+  }
+
+  let mut surfaces = BTreeMap::new();
+  for surface in SURFACES {
+    let base = file.surfaces.get(surface).cloned().unwrap_or_default();
+    let overrides = variant.surfaces.get(surface).cloned().unwrap_or_default();
+
+    let backdrop = [overrides.backdrop, base.backdrop]
+      .into_iter()
+      .find(|backdrop| !backdrop.is_empty())
+      .or_else(|| fallback.get(surface).cloned())
+      .unwrap_or_default()
+      .into_iter()
+      .map(|backdrop| follow_system(backdrop, system))
+      .collect();
+
+    surfaces.insert(
+      surface.to_string(),
+      Surface {
+        backdrop,
+        radius: overrides.radius.or(base.radius).unwrap_or_default(),
+      },
+    );
+  }
+
+  Ok(Theme {
+    name: name.to_string(),
+    dark: system.dark,
+    accent: system.accent,
+    colors,
+    surfaces,
+  })
+}
+
+fn resolve_token(
+  token: &str,
+  tokens: &BTreeMap<String, String>,
+  system: SystemTheme,
+  depth: usize,
+) -> Result<Color, String> {
+  // Deep enough for any sane theme, and stops reference cycles
+  if depth > 8 {
+    return Err(format!("Color token `{}` references itself", token));
+  }
+
+  let value = tokens
+    .get(token)
+    .ok_or_else(|| format!("Unknown color token `{}`", token))?;
+
+  if value == "accent" {
+    Ok(system.accent)
+  } else if let Some(reference) = value.strip_prefix('$') {
+    resolve_token(reference, tokens, system, depth + 1)
+  } else {
+    value
+      .parse()
+      .map_err(|err| format!("Color token `{}`: {}", token, err))
+  }
+}
+
+// Mica follows the system light/dark preference
+fn follow_system(backdrop: Backdrop, system: SystemTheme) -> Backdrop {
+  match backdrop {
+    Backdrop::Mica { .. } => Backdrop::Mica {
+      dark_mode: system.dark,
+    },
+    Backdrop::MicaAlt { .. } => Backdrop::MicaAlt {
+      dark_mode: system.dark,
+    },
+    backdrop => backdrop,
+  }
+}
+
+fn load() -> Theme {
+  let name = USER_SETTINGS.theme.clone().unwrap_or("default".to_string());
+  let mut file = ThemeFile::builtin();
+
+  if let Some(theme) = &USER_SETTINGS.theme {
+    let path = config_dir().join("themes").join(format!("{}.json", theme));
+    match fs::read_to_string(&path)
+      .map_err(|err| err.to_string())
+      .and_then(|json| serde_json::from_str::<ThemeFile>(&json).map_err(|err| err.to_string()))
+    {
+      Ok(user_theme) => file = file.merge(user_theme),
+      Err(err) => eprintln!("Failed to load theme {}: {}", path.display(), err),
+    }
+  }
+
+  let fallback = BTreeMap::from([
+    ("menubar".to_string(), USER_SETTINGS.menubar_backdrops()),
+    ("dock".to_string(), USER_SETTINGS.backdrop.dock.clone()),
+    (
+      "settings".to_string(),
+      USER_SETTINGS.backdrop.settings.clone(),
+    ),
+  ]);
+
+  let system = SystemTheme::query();
+  resolve(&name, &file, system, &fallback).unwrap_or_else(|err| {
+    eprintln!("Invalid theme {}: {}", name, err);
+    resolve("default", &ThemeFile::builtin(), system, &fallback)
+      .expect("Failed to resolve default theme")
+  })
+}
+
+pub fn current() -> Theme {
+  THEME.lock().unwrap().clone()
+}
+
+/// Reloads the theme when the system colours change
+pub fn watch(hwnd: HWND) {
+  subclass_window(
+    hwnd,
+    Box::new(|_, msg, _, lparam| {
+      match msg {
+        WM_SETTINGCHANGE if is_color_set_change(lparam.0) => {
+          thread::spawn(refresh);
+        }
+        WM_DWMCOLORIZATIONCOLORCHANGED => {
+          thread::spawn(refresh);
+        }
+        _ => {}
+      }
+
+      None
+    }),
+  )
+  .unwrap_or_else(|err| eprintln!("Failed to watch theme changes: {}", err));
+}
+
+// WM_SETTINGCHANGE carries the name of the changed area in lParam
+fn is_color_set_change(lparam: isize) -> bool {
+  lparam != 0
+    && unsafe { PCWSTR(lparam as *const u16).to_string() }
+      .is_ok_and(|area| area == "ImmersiveColorSet")
+}
+
+/// Resolves the theme again, notifying the windows and re-applying backdrops
+pub fn refresh() {
+  let theme = load();
+  {
+    let mut current = THEME.lock().unwrap();
+    if *current == theme {
+      return;
+    }
+    *current = theme.clone();
+  }
+
+  for surface in SURFACES {
+    backdrops::set_preferences(surface, theme.backdrops(surface));
+  }
+
+  if let Some(app_handle) = APP_HANDLE.lock().unwrap().as_ref() {
+    app_handle
+      .emit("theme-changed", theme)
+      .unwrap_or_else(|_| ());
+  }
+}
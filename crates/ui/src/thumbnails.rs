@@ -0,0 +1,317 @@
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tauri::Emitter;
+use tauri::Listener;
+use tauri::PhysicalPosition;
+use tauri::PhysicalSize;
+use util::{activate_window, get_window_text, monitor_of, scale_factor, APP_HANDLE, USER_SETTINGS};
+use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT, WPARAM};
+use windows::Win32::Graphics::Dwm::{
+  DwmQueryThumbnailSourceSize, DwmRegisterThumbnail, DwmUnregisterThumbnail,
+  DwmUpdateThumbnailProperties, DWM_THUMBNAIL_PROPERTIES, DWM_TNP_RECTDESTINATION,
+  DWM_TNP_SOURCECLIENTAREAONLY, DWM_TNP_VISIBLE,
+};
+use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_CLOSE};
+
+pub use shell::thumbnails::*;
+
+use crate::dock::{self, Bounds, GLOBAL_APPS};
+
+// Leaving a tile for the popup takes a moment, the popup waits for it
+const HIDE_DELAY: Duration = Duration::from_millis(300);
+
+static WINDOW: LazyLock<Mutex<Option<tauri::WebviewWindow>>> = LazyLock::new(|| Mutex::new(None));
+static POPUP: LazyLock<Mutex<Option<Popup>>> = LazyLock::new(|| Mutex::new(None));
+// Bumped whenever the pointer moves to a tile or the popup, delayed hides
+// only go through if nothing happened since
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+static HOVERED: AtomicBool = AtomicBool::new(false);
+
+/// A dock tile under the pointer, relative to the dock window
+#[derive(Deserialize, Clone, Copy, Debug)]
+struct TileHover {
+  hwnd: isize,
+  x: i32,
+  y: i32,
+  width: i32,
+  height: i32,
+}
+
+/// A thumbnail as the popup renders it
+#[derive(Serialize, Clone, Debug)]
+struct Preview {
+  hwnd: isize,
+  title: String,
+  #[serde(flatten)]
+  thumbnail: Thumbnail,
+}
+
+// Thumbnails currently shown, ids are unregistered when the popup hides
+struct Popup {
+  anchor: Bounds,
+  windows: Vec<isize>,
+  thumbnails: Vec<isize>,
+}
+
+pub fn init() {
+  let window = setup_window().expect("Failed to setup thumbnails window");
+  *WINDOW.lock().unwrap() = Some(window.clone());
+
+  // Thumbnails are disabled with a zero height
+  if USER_SETTINGS.dock.thumbnail_height <= 0 {
+    return;
+  }
+
+  window.listen("tile-pointer-enter", |event| {
+    if let Ok(tile) = serde_json::from_str::<TileHover>(event.payload()) {
+      GENERATION.fetch_add(1, Ordering::SeqCst);
+      thread::spawn(move || show(tile));
+    }
+  });
+  window.listen("tile-pointer-leave", |_| schedule_hide());
+  window.listen("thumbnails-pointer-enter", |_| {
+    HOVERED.store(true, Ordering::SeqCst);
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+  });
+  window.listen("thumbnails-pointer-leave", |_| {
+    HOVERED.store(false, Ordering::SeqCst);
+    schedule_hide();
+  });
+  window.listen("thumbnail-activate", |event| {
+    if let Ok(hwnd) = serde_json::from_str::<isize>(event.payload()) {
+      thread::spawn(move || {
+        hide();
+        activate_window(HWND(hwnd));
+      });
+    }
+  });
+  window.listen("thumbnail-close", |event| {
+    if let Ok(hwnd) = serde_json::from_str::<isize>(event.payload()) {
+      thread::spawn(move || close(HWND(hwnd)));
+    }
+  });
+}
+
+// Windows sharing the tile's executable are previewed together
+fn group(hwnd: isize) -> Vec<isize> {
+  let apps = GLOBAL_APPS.lock().unwrap();
+  let Some(path) = apps
+    .iter()
+    .find(|app| app.hwnd == hwnd)
+    .map(|app| app.path.clone())
+  else {
+    return Vec::new();
+  };
+
+  apps
+    .iter()
+    .filter(|app| app.hwnd == hwnd || (!path.is_empty() && app.path == path))
+    .map(|app| app.hwnd)
+    .collect()
+}
+
+fn show(tile: TileHover) {
+  let dock = dock::current_layout(GLOBAL_APPS.lock().unwrap().len()).bounds;
+  let anchor = Bounds {
+    x: dock.x + tile.x,
+    y: dock.y + tile.y,
+    width: tile.width,
+    height: tile.height,
+  };
+
+  show_windows(anchor, group(tile.hwnd));
+}
+
+fn show_windows(anchor: Bounds, windows: Vec<isize>) {
+  let (monitor, scale) = dock_monitor();
+  let physical = |length: i32| (length as f64 * scale).round() as i32;
+  let binding = WINDOW.lock().unwrap();
+  let Some(window) = binding.as_ref() else {
+    return;
+  };
+  let popup = HWND(window.hwnd().unwrap().0);
+
+  let mut current = POPUP.lock().unwrap();
+  unregister(current.take());
+  if windows.is_empty() {
+    window.hide().unwrap_or_else(|_| ());
+    return;
+  }
+
+  let registered = windows
+    .iter()
+    .filter_map(|&hwnd| {
+      let thumbnail = unsafe { DwmRegisterThumbnail(popup, HWND(hwnd)) }.ok()?;
+      let size = unsafe { DwmQueryThumbnailSourceSize(thumbnail) }.unwrap_or_default();
+      Some((hwnd, thumbnail, (size.cx, size.cy)))
+    })
+    .collect::<Vec<_>>();
+
+  let sources = registered
+    .iter()
+    .map(|(_, _, size)| *size)
+    .collect::<Vec<_>>();
+  let layout = layout_thumbnails(
+    &sources,
+    anchor,
+    USER_SETTINGS.dock.edge,
+    monitor,
+    ThumbnailMetrics {
+      height: physical(USER_SETTINGS.dock.thumbnail_height),
+      spacing: physical(USER_SETTINGS.dock.spacing * 2),
+      padding: physical(USER_SETTINGS.dock.padding * 2),
+    },
+  );
+
+  for ((_, thumbnail, _), placed) in registered.iter().zip(&layout.thumbnails) {
+    let properties = DWM_THUMBNAIL_PROPERTIES {
+      dwFlags: DWM_TNP_RECTDESTINATION | DWM_TNP_VISIBLE | DWM_TNP_SOURCECLIENTAREAONLY,
+      rcDestination: RECT {
+        left: placed.preview.x,
+        top: placed.preview.y,
+        right: placed.preview.right(),
+        bottom: placed.preview.bottom(),
+      },
+      fVisible: BOOL::from(true),
+      fSourceClientAreaOnly: BOOL::from(false),
+      ..Default::default()
+    };
+    unsafe { DwmUpdateThumbnailProperties(*thumbnail, &properties) }
+      .unwrap_or_else(|err| eprintln!("Failed to update thumbnail: {}", err));
+  }
+
+  let previews = registered
+    .iter()
+    .zip(&layout.thumbnails)
+    .map(|((hwnd, _, _), thumbnail)| Preview {
+      hwnd: *hwnd,
+      title: get_window_text(HWND(*hwnd)),
+      thumbnail: *thumbnail,
+    })
+    .collect::<Vec<_>>();
+
+  let bounds = layout.bounds;
+  window
+    .set_position(PhysicalPosition::new(bounds.x, bounds.y))
+    .unwrap_or_else(|_| ());
+  window
+    .set_size(PhysicalSize::new(bounds.width, bounds.height))
+    .unwrap_or_else(|_| ());
+  window.emit("thumbnails", previews).unwrap_or_else(|_| ());
+  window.show().unwrap_or_else(|_| ());
+
+  *current = Some(Popup {
+    anchor,
+    windows: registered.iter().map(|(hwnd, _, _)| *hwnd).collect(),
+    thumbnails: registered
+      .iter()
+      .map(|(_, thumbnail, _)| *thumbnail)
+      .collect(),
+  });
+}
+
+// Work area and scale of the monitor the dock's tiles are on, which is not
+// always the primary one
+fn dock_monitor() -> (Bounds, f64) {
+  let dock = dock::WINDOW
+    .lock()
+    .unwrap()
+    .as_ref()
+    .and_then(|window| window.hwnd().ok())
+    .map(|hwnd| HWND(hwnd.0));
+
+  let work_area = dock
+    .and_then(monitor_of)
+    .map(|monitor| Bounds {
+      x: monitor.work_area.left,
+      y: monitor.work_area.top,
+      width: monitor.work_area.right - monitor.work_area.left,
+      height: monitor.work_area.bottom - monitor.work_area.top,
+    })
+    .unwrap_or_else(dock::screen);
+
+  (work_area, dock.map_or(1.0, scale_factor))
+}
+
+fn unregister(popup: Option<Popup>) {
+  for thumbnail in popup.map(|popup| popup.thumbnails).unwrap_or_default() {
+    unsafe { DwmUnregisterThumbnail(thumbnail) }.unwrap_or_else(|_| ());
+  }
+}
+
+// Hides the popup unless the pointer reaches a tile or the popup in time
+fn schedule_hide() {
+  let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+  thread::spawn(move || {
+    thread::sleep(HIDE_DELAY);
+    if GENERATION.load(Ordering::SeqCst) == generation && !HOVERED.load(Ordering::SeqCst) {
+      hide();
+    }
+  });
+}
+
+pub fn hide() {
+  let binding = WINDOW.lock().unwrap();
+  if let Some(window) = binding.as_ref() {
+    unregister(POPUP.lock().unwrap().take());
+    window.hide().unwrap_or_else(|_| ());
+  }
+  HOVERED.store(false, Ordering::SeqCst);
+}
+
+// Asks the window to close, the popup keeps showing the rest of the group
+fn close(hwnd: HWND) {
+  unsafe { PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)) }
+    .unwrap_or_else(|err| eprintln!("Failed to close window: {}", err));
+
+  let Some((anchor, windows)) = POPUP.lock().unwrap().as_ref().map(|popup| {
+    let windows = popup
+      .windows
+      .iter()
+      .copied()
+      .filter(|window| *window != hwnd.0)
+      .collect::<Vec<_>>();
+    (popup.anchor, windows)
+  }) else {
+    return;
+  };
+
+  show_windows(anchor, windows);
+}
+
+fn setup_window() -> Result<tauri::WebviewWindow, ()> {
+  let window = tauri::WebviewWindowBuilder::new(
+    APP_HANDLE
+      .lock()
+      .unwrap()
+      .as_ref()
+      .unwrap_or_else(|| panic!("Failed to get app handle")),
+    "thumbnails",
+    tauri::WebviewUrl::App(PathBuf::from("/#/thumbnails")),
+  )
+  .title("Thumbnails")
+  .transparent(true)
+  .always_on_top(true)
+  .decorations(false)
+  .shadow(false)
+  .resizable(false)
+  .maximizable(false)
+  .minimizable(false)
+  .skip_taskbar(true)
+  .focused(false)
+  .visible(false)
+  .build()
+  .expect("Failed to build thumbnails window");
+
+  Ok(window)
+}
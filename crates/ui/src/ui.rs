@@ -1,14 +1,31 @@
+pub mod backdrops;
 mod dock;
+pub mod fullscreen;
 mod hitbox;
 mod hooks;
+pub mod hotkeys;
+pub mod launcher;
 mod menubar;
+pub mod switcher;
+mod tasklist;
+pub mod theme;
+mod thumbnails;
+
+pub use dock::{replay, replay_file, Window};
 
 pub fn init() {
+  fullscreen::init();
   dock::init();
   hitbox::init();
+  launcher::init();
   menubar::init();
+  switcher::init();
+  thumbnails::init();
 }
 
 pub fn kill() {
   menubar::remove();
+  dock::remove();
+  tasklist::remove();
+  util::restore_taskbar();
 }
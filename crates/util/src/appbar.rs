@@ -0,0 +1,273 @@
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::Once;
+use std::sync::TryLockError;
+use std::thread;
+
+use windows::core::w;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::UI::Shell::SHAppBarMessage;
+use windows::Win32::UI::Shell::ABE_BOTTOM;
+use windows::Win32::UI::Shell::ABE_LEFT;
+use windows::Win32::UI::Shell::ABE_RIGHT;
+use windows::Win32::UI::Shell::ABE_TOP;
+use windows::Win32::UI::Shell::ABM_ACTIVATE;
+use windows::Win32::UI::Shell::ABM_NEW;
+use windows::Win32::UI::Shell::ABM_QUERYPOS;
+use windows::Win32::UI::Shell::ABM_REMOVE;
+use windows::Win32::UI::Shell::ABM_SETPOS;
+use windows::Win32::UI::Shell::ABM_WINDOWPOSCHANGED;
+use windows::Win32::UI::Shell::ABN_FULLSCREENAPP;
+use windows::Win32::UI::Shell::ABN_POSCHANGED;
+use windows::Win32::UI::Shell::ABN_STATECHANGE;
+use windows::Win32::UI::Shell::ABN_WINDOWARRANGE;
+use windows::Win32::UI::Shell::APPBARDATA;
+use windows::Win32::UI::WindowsAndMessaging::RegisterWindowMessageW;
+use windows::Win32::UI::WindowsAndMessaging::WM_ACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
+use windows::Win32::UI::WindowsAndMessaging::WM_WINDOWPOSCHANGED;
+
+use crate::subclass_window;
+use crate::{AppError, Result};
+
+// Sent by the shell with an `ABN_*` notification in wParam
+const CALLBACK_MESSAGE: u32 = WM_USER + 1;
+
+/// Shell notifications an appbar reacts to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppBarNotification {
+  // The taskbar or another appbar moved, or Explorer restarted
+  PosChanged,
+  // A fullscreen window opened (`true`) or the last one closed
+  FullscreenApp(bool),
+  StateChange,
+  // Windows are being cascaded or tiled (`true`) or are done
+  WindowArrange(bool),
+}
+
+pub type AppBarHandler = Arc<dyn Fn(AppBarNotification) + Send + Sync>;
+
+struct State {
+  hwnd: HWND,
+  edge: u32,
+  registered: bool,
+  // Last rect asked for, to register again after Explorer restarts
+  requested: Option<RECT>,
+  // Rect granted by the shell on the last `ABM_SETPOS`
+  granted: RECT,
+}
+
+/// A window registered as an appbar, removed from the shell when dropped
+///
+/// The shell keeps the reserved space after the process dies, so every
+/// registered appbar is also removed when the process panics, see
+/// [`remove_all_appbars`].
+pub struct AppBar {
+  state: Arc<Mutex<State>>,
+}
+
+// Registered windows, for removal when the process goes down
+static REGISTERED: LazyLock<Mutex<Vec<isize>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+static PANIC_HOOK: Once = Once::new();
+
+impl AppBar {
+  /// Registers `hwnd` as an appbar on `edge` (one of the `ABE_*` constants)
+  ///
+  /// Must be called from the thread that created the window. `handler` runs on
+  /// that thread and should not block.
+  pub fn register(hwnd: HWND, edge: u32, handler: AppBarHandler) -> Result<Self> {
+    if ![ABE_LEFT, ABE_TOP, ABE_RIGHT, ABE_BOTTOM].contains(&edge) {
+      return Err(AppError::from("Invalid appbar edge"));
+    }
+
+    install_panic_hook();
+
+    let state = Arc::new(Mutex::new(State {
+      hwnd,
+      edge,
+      registered: false,
+      requested: None,
+      granted: RECT::default(),
+    }));
+    state.lock().unwrap().register()?;
+
+    let taskbar_created = unsafe { RegisterWindowMessageW(w!("TaskbarCreated")) };
+    let watched = state.clone();
+    subclass_window(
+      hwnd,
+      Box::new(move |hwnd, msg, wparam, lparam| {
+        match msg {
+          CALLBACK_MESSAGE => {
+            let notification = match wparam.0 as u32 {
+              ABN_POSCHANGED => AppBarNotification::PosChanged,
+              ABN_FULLSCREENAPP => AppBarNotification::FullscreenApp(lparam.0 != 0),
+              ABN_STATECHANGE => AppBarNotification::StateChange,
+              ABN_WINDOWARRANGE => AppBarNotification::WindowArrange(lparam.0 != 0),
+              _ => return None,
+            };
+            handler(notification);
+          }
+          // Both are required by the appbar protocol
+          WM_ACTIVATE => unsafe {
+            SHAppBarMessage(ABM_ACTIVATE, &mut appbar_data(hwnd));
+          },
+          WM_WINDOWPOSCHANGED => unsafe {
+            SHAppBarMessage(ABM_WINDOWPOSCHANGED, &mut appbar_data(hwnd));
+          },
+          // Explorer restarted and forgot every appbar
+          msg if msg == taskbar_created && taskbar_created != 0 => {
+            let (state, handler) = (watched.clone(), handler.clone());
+            thread::spawn(move || {
+              if state.lock().unwrap().register_again() {
+                handler(AppBarNotification::PosChanged);
+              }
+            });
+          }
+          _ => {}
+        }
+
+        None
+      }),
+    )?;
+
+    Ok(Self { state })
+  }
+
+  pub fn hwnd(&self) -> HWND {
+    self.state.lock().unwrap().hwnd
+  }
+
+  /// Asks the shell for `rect` and returns the rect it granted
+  ///
+  /// The shell only moves the side on the appbar's edge, the requested
+  /// thickness is kept.
+  pub fn set_pos(&self, rect: RECT) -> RECT {
+    let mut state = self.state.lock().unwrap();
+    state.requested = Some(rect);
+    state.set_pos(rect)
+  }
+
+  /// Gives the reserved space back to the shell
+  pub fn remove(&self) {
+    self.state.lock().unwrap().remove();
+  }
+}
+
+impl Drop for AppBar {
+  fn drop(&mut self) {
+    self.remove();
+  }
+}
+
+impl State {
+  fn register(&mut self) -> Result {
+    if unsafe { SHAppBarMessage(ABM_NEW, &mut appbar_data(self.hwnd)) } == 0 {
+      return Err(AppError::from("Failed to register appbar"));
+    }
+
+    self.registered = true;
+    self.granted = RECT::default();
+    REGISTERED.lock().unwrap().push(self.hwnd.0);
+    Ok(())
+  }
+
+  // Returns whether the appbar is registered again
+  fn register_again(&mut self) -> bool {
+    if !self.registered {
+      return false;
+    }
+
+    REGISTERED
+      .lock()
+      .unwrap()
+      .retain(|hwnd| *hwnd != self.hwnd.0);
+    if let Err(err) = self.register() {
+      self.registered = false;
+      eprintln!("{}", err);
+      return false;
+    }
+
+    if let Some(rect) = self.requested {
+      self.set_pos(rect);
+    }
+
+    true
+  }
+
+  fn set_pos(&mut self, rect: RECT) -> RECT {
+    if !self.registered {
+      return rect;
+    }
+
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    let mut data = appbar_data(self.hwnd);
+    data.uEdge = self.edge;
+    data.rc = rect;
+
+    unsafe { SHAppBarMessage(ABM_QUERYPOS, &mut data) };
+    match self.edge {
+      ABE_BOTTOM => data.rc.top = data.rc.bottom - height,
+      ABE_TOP => data.rc.bottom = data.rc.top + height,
+      ABE_LEFT => data.rc.right = data.rc.left + width,
+      _ => data.rc.left = data.rc.right - width,
+    }
+
+    // Setting the same rect again would notify every other appbar for nothing
+    if data.rc != self.granted {
+      unsafe { SHAppBarMessage(ABM_SETPOS, &mut data) };
+      self.granted = data.rc;
+    }
+
+    self.granted
+  }
+
+  fn remove(&mut self) {
+    if !self.registered {
+      return;
+    }
+
+    unsafe { SHAppBarMessage(ABM_REMOVE, &mut appbar_data(self.hwnd)) };
+    self.registered = false;
+    REGISTERED
+      .lock()
+      .unwrap()
+      .retain(|hwnd| *hwnd != self.hwnd.0);
+  }
+}
+
+fn appbar_data(hwnd: HWND) -> APPBARDATA {
+  APPBARDATA {
+    cbSize: std::mem::size_of::<APPBARDATA>() as u32,
+    hWnd: hwnd,
+    uCallbackMessage: CALLBACK_MESSAGE,
+    uEdge: 0,
+    rc: RECT::default(),
+    lParam: LPARAM(0),
+  }
+}
+
+/// Removes every registered appbar, for when the process is going down
+pub fn remove_all_appbars() {
+  // Called from the panic hook, the panicking thread may hold the lock
+  let hwnds = match REGISTERED.try_lock() {
+    Ok(mut hwnds) => std::mem::take(&mut *hwnds),
+    Err(TryLockError::Poisoned(err)) => std::mem::take(&mut *err.into_inner()),
+    Err(TryLockError::WouldBlock) => return,
+  };
+  for hwnd in hwnds {
+    unsafe { SHAppBarMessage(ABM_REMOVE, &mut appbar_data(HWND(hwnd))) };
+  }
+}
+
+fn install_panic_hook() {
+  PANIC_HOOK.call_once(|| {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+      remove_all_appbars();
+      previous(info);
+    }));
+  });
+}
@@ -1,6 +1,21 @@
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::POINT;
 use windows::Win32::Foundation::RECT;
+use windows::Win32::Graphics::Gdi::EnumDisplayMonitors;
+use windows::Win32::Graphics::Gdi::GetMonitorInfoW;
+use windows::Win32::Graphics::Gdi::MonitorFromPoint;
+use windows::Win32::Graphics::Gdi::MonitorFromWindow;
+use windows::Win32::Graphics::Gdi::HDC;
+use windows::Win32::Graphics::Gdi::HMONITOR;
+use windows::Win32::Graphics::Gdi::MONITORINFO;
+use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONEAREST;
+use windows::Win32::UI::HiDpi::GetDpiForWindow;
 use windows::Win32::UI::WindowsAndMessaging::GetDesktopWindow;
 use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+use windows::Win32::UI::WindowsAndMessaging::MONITORINFOF_PRIMARY;
+use windows::Win32::UI::WindowsAndMessaging::USER_DEFAULT_SCREEN_DPI;
 
 pub struct ScreenGeometry {
   pub x: i32,
@@ -34,3 +49,79 @@ impl ScreenGeometry {
     geometry
   }
 }
+
+/// Physical pixels per logical pixel on `hwnd`'s monitor
+pub fn scale_factor(hwnd: HWND) -> f64 {
+  let dpi = match unsafe { GetDpiForWindow(hwnd) } {
+    0 => USER_DEFAULT_SCREEN_DPI,
+    dpi => dpi,
+  };
+
+  dpi as f64 / USER_DEFAULT_SCREEN_DPI as f64
+}
+
+/// Converts a length in logical pixels to the physical pixels of `hwnd`'s monitor
+pub fn to_physical(hwnd: HWND, length: i32) -> i32 {
+  (length as f64 * scale_factor(hwnd)).round() as i32
+}
+
+/// A display and the part of it not covered by appbars, in physical pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Monitor {
+  pub handle: isize,
+  pub bounds: RECT,
+  pub work_area: RECT,
+  pub primary: bool,
+}
+
+fn monitor_info(monitor: HMONITOR) -> Option<Monitor> {
+  let mut info = MONITORINFO {
+    cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+    ..Default::default()
+  };
+
+  unsafe { GetMonitorInfoW(monitor, &mut info) }
+    .as_bool()
+    .then_some(Monitor {
+      handle: monitor.0,
+      bounds: info.rcMonitor,
+      work_area: info.rcWork,
+      primary: info.dwFlags & MONITORINFOF_PRIMARY != 0,
+    })
+}
+
+/// Every monitor, in the order the system enumerates them
+pub fn monitors() -> Vec<Monitor> {
+  unsafe extern "system" fn collect(
+    monitor: HMONITOR,
+    _: HDC,
+    _: *mut RECT,
+    lparam: LPARAM,
+  ) -> BOOL {
+    let monitors = &mut *(lparam.0 as *mut Vec<HMONITOR>);
+    monitors.push(monitor);
+    true.into()
+  }
+
+  let mut handles: Vec<HMONITOR> = Vec::new();
+  unsafe {
+    EnumDisplayMonitors(
+      HDC::default(),
+      None,
+      Some(collect),
+      LPARAM(&mut handles as *mut _ as isize),
+    )
+  };
+
+  handles.into_iter().filter_map(monitor_info).collect()
+}
+
+/// The monitor showing most of `hwnd`, or the nearest one
+pub fn monitor_of(hwnd: HWND) -> Option<Monitor> {
+  monitor_info(unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) })
+}
+
+/// The monitor containing the screen point, or the nearest one
+pub fn monitor_at(x: i32, y: i32) -> Option<Monitor> {
+  monitor_info(unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST) })
+}
@@ -25,6 +25,7 @@ define_app_errors!(
   Tauri(tauri::Error);
   TauriShell(tauri_plugin_shell::Error);
   Windows(windows::core::Error);
+  Backdrop(backdrop::BackdropError);
   SerdeJson(serde_json::Error);
   Utf8(std::string::FromUtf8Error);
   Utf16(std::string::FromUtf16Error);
@@ -43,6 +44,7 @@ impl std::error::Error for AppError {
       AppError::Io(err) => Some(err),
       AppError::Tauri(err) => Some(err),
       AppError::Windows(err) => Some(err),
+      AppError::Backdrop(err) => Some(err),
       AppError::SerdeJson(err) => Some(err),
       AppError::Utf8(err) => Some(err),
       AppError::Utf16(err) => Some(err),
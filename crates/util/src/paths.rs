@@ -6,3 +6,17 @@ pub fn home_dir() -> Option<PathBuf> {
     .and_then(|h| if h.is_empty() { None } else { Some(h) })
     .map(PathBuf::from);
 }
+
+pub fn config_dir() -> PathBuf {
+  home_dir().unwrap_or_default().join(".simpletb")
+}
+
+// Recent items and the destinations files of jump lists
+pub fn recent_dir() -> Option<PathBuf> {
+  env::var_os("APPDATA").filter(|h| !h.is_empty()).map(|h| {
+    PathBuf::from(h)
+      .join("Microsoft")
+      .join("Windows")
+      .join("Recent")
+  })
+}
@@ -1,13 +1,50 @@
+use backdrop::Backdrop;
+use backdrop::Color;
 use serde::Deserialize;
+use serde::Deserializer;
 use std::{fs, sync::LazyLock};
 
-use crate::home_dir;
+use crate::config_dir;
+
+pub use shell::settings::{
+  DockAlignment, DockEdge, DockMode, DockOverflow, DockSettings, HotkeySettings,
+};
 
 #[derive(Deserialize, Clone)]
 pub struct MenubarSettings {
   pub round_corners: bool,
   pub blur: bool,
-  pub color: String,
+  pub color: Color,
+}
+
+// Backdrop of each window as an ordered list of fallbacks, the first one the
+// system supports is used. The menubar falls back to `menubar.blur` when empty.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct BackdropSettings {
+  #[serde(deserialize_with = "backdrop_preferences")]
+  pub menubar: Vec<Backdrop>,
+  #[serde(deserialize_with = "backdrop_preferences")]
+  pub dock: Vec<Backdrop>,
+  #[serde(deserialize_with = "backdrop_preferences")]
+  pub settings: Vec<Backdrop>,
+}
+
+// Accepts a single backdrop as well as a list
+pub fn backdrop_preferences<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Vec<Backdrop>, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum Preferences {
+    One(Backdrop),
+    Many(Vec<Backdrop>),
+  }
+
+  Ok(match Preferences::deserialize(deserializer)? {
+    Preferences::One(backdrop) => vec![backdrop],
+    Preferences::Many(backdrops) => backdrops,
+  })
 }
 
 #[derive(Deserialize)]
@@ -15,6 +52,15 @@ pub struct Settings {
   pub height: i32,
   pub margin_bottom: i32,
   pub menubar: MenubarSettings,
+  #[serde(default)]
+  pub dock: DockSettings,
+  #[serde(default)]
+  pub backdrop: BackdropSettings,
+  #[serde(default)]
+  pub hotkeys: HotkeySettings,
+  // Name of a theme file in `~/.simpletb/themes`, without the extension
+  #[serde(default)]
+  pub theme: Option<String>,
 }
 
 pub static USER_SETTINGS: LazyLock<Settings> =
@@ -25,7 +71,32 @@ impl Default for MenubarSettings {
     Self {
       round_corners: true,
       blur: false,
-      color: String::from("#10101000"),
+      color: Color::rgba(0x10, 0x10, 0x10, 0x00),
+    }
+  }
+}
+
+impl Default for BackdropSettings {
+  fn default() -> Self {
+    Self {
+      menubar: Vec::new(),
+      dock: vec![
+        Backdrop::Acrylic {
+          color: Color::rgba(0x10, 0x10, 0x10, 0x00),
+        },
+        Backdrop::Blur {
+          color: Color::rgba(0x10, 0x10, 0x10, 0x80),
+        },
+        Backdrop::Solid {
+          color: Color::rgb(0x10, 0x10, 0x10),
+        },
+      ],
+      settings: vec![
+        Backdrop::Mica { dark_mode: false },
+        Backdrop::Solid {
+          color: Color::rgb(0x20, 0x20, 0x20),
+        },
+      ],
     }
   }
 }
@@ -36,6 +107,10 @@ impl Default for Settings {
       height: 26,
       margin_bottom: 5,
       menubar: MenubarSettings::default(),
+      dock: DockSettings::default(),
+      backdrop: BackdropSettings::default(),
+      hotkeys: HotkeySettings::default(),
+      theme: None,
     }
   }
 }
@@ -45,11 +120,25 @@ impl Settings {
     Settings::load_settings().unwrap_or_default()
   }
 
+  pub fn menubar_backdrops(&self) -> Vec<Backdrop> {
+    if !self.backdrop.menubar.is_empty() {
+      self.backdrop.menubar.clone()
+    } else if self.menubar.blur {
+      vec![
+        Backdrop::Acrylic {
+          color: self.menubar.color,
+        },
+        Backdrop::Blur {
+          color: self.menubar.color,
+        },
+      ]
+    } else {
+      vec![Backdrop::None]
+    }
+  }
+
   pub fn load_settings() -> Result<Self, Box<dyn std::error::Error>> {
-    let file = fs::File::open(format!(
-      "{}\\.simpletb\\config.json",
-      home_dir().unwrap_or_default().display()
-    ))?;
+    let file = fs::File::open(config_dir().join("config.json"))?;
 
     let config: Settings = serde_json::from_reader(file)?;
     Ok(config)
@@ -0,0 +1,57 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::Shell::DefSubclassProc;
+use windows::Win32::UI::Shell::RemoveWindowSubclass;
+use windows::Win32::UI::Shell::SetWindowSubclass;
+use windows::Win32::UI::WindowsAndMessaging::WM_NCDESTROY;
+
+use crate::{AppError, Result};
+
+/// Handles a message sent to a subclassed window
+///
+/// Returning `None` passes the message on to the next handler and eventually
+/// to the window's own procedure.
+pub type MessageHandler = Box<dyn Fn(HWND, u32, WPARAM, LPARAM) -> Option<LRESULT> + Send + Sync>;
+
+static NEXT_SUBCLASS_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Intercepts the messages of `hwnd` with `handler`
+///
+/// Must be called from the thread that created the window (Tauri's main thread).
+pub fn subclass_window(hwnd: HWND, handler: MessageHandler) -> Result {
+  let id = NEXT_SUBCLASS_ID.fetch_add(1, Ordering::Relaxed);
+  let data = Box::into_raw(Box::new(handler)) as usize;
+
+  if !unsafe { SetWindowSubclass(hwnd, Some(subclass_proc), id, data) }.as_bool() {
+    drop(unsafe { Box::from_raw(data as *mut MessageHandler) });
+    return Err(AppError::from("Failed to subclass window"));
+  }
+
+  Ok(())
+}
+
+unsafe extern "system" fn subclass_proc(
+  hwnd: HWND,
+  msg: u32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+  id: usize,
+  data: usize,
+) -> LRESULT {
+  if msg == WM_NCDESTROY {
+    let _ = RemoveWindowSubclass(hwnd, Some(subclass_proc), id);
+    drop(Box::from_raw(data as *mut MessageHandler));
+    return DefSubclassProc(hwnd, msg, wparam, lparam);
+  }
+
+  let handler = &*(data as *const MessageHandler);
+  match handler(hwnd, msg, wparam, lparam) {
+    Some(result) => result,
+    None => DefSubclassProc(hwnd, msg, wparam, lparam),
+  }
+}
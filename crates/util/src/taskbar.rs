@@ -0,0 +1,233 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::Once;
+use std::thread;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use windows::core::w;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::System::Console::SetConsoleCtrlHandler;
+use windows::Win32::System::Threading::OpenProcess;
+use windows::Win32::System::Threading::WaitForSingleObject;
+use windows::Win32::System::Threading::INFINITE;
+use windows::Win32::System::Threading::PROCESS_SYNCHRONIZE;
+use windows::Win32::UI::Shell::SHAppBarMessage;
+use windows::Win32::UI::Shell::ABM_GETSTATE;
+use windows::Win32::UI::Shell::ABM_SETSTATE;
+use windows::Win32::UI::Shell::ABS_ALWAYSONTOP;
+use windows::Win32::UI::Shell::ABS_AUTOHIDE;
+use windows::Win32::UI::Shell::APPBARDATA;
+use windows::Win32::UI::WindowsAndMessaging::FindWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::FindWindowW;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNA;
+use windows::Win32::UI::WindowsAndMessaging::WM_DISPLAYCHANGE;
+use windows::Win32::UI::WindowsAndMessaging::WM_ENDSESSION;
+
+use crate::config_dir;
+use crate::subclass_window;
+
+/// Taskbar state from before simpletb hid it
+///
+/// Written to disk on the first hide so the watchdog, or the next run after a
+/// crash, can put the user's settings back.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaskbarRecord {
+  // `ABS_*` flags returned by `ABM_GETSTATE`
+  pub state: u32,
+}
+
+static RECORD: LazyLock<Mutex<Option<TaskbarRecord>>> = LazyLock::new(|| Mutex::new(None));
+// Set once the taskbar is restored for good, hiding becomes a no-op
+static RESTORED: AtomicBool = AtomicBool::new(false);
+static GUARD: Once = Once::new();
+
+fn record_path() -> PathBuf {
+  config_dir().join("taskbar.json")
+}
+
+fn read_record() -> Option<TaskbarRecord> {
+  let json = fs::read_to_string(record_path()).ok()?;
+  serde_json::from_str(&json).ok()
+}
+
+fn write_record(record: TaskbarRecord) {
+  let path = record_path();
+  fs::create_dir_all(config_dir())
+    .and_then(|_| fs::write(&path, serde_json::to_string(&record).unwrap_or_default()))
+    .unwrap_or_else(|err| eprintln!("Failed to save taskbar state: {}", err));
+}
+
+fn taskbar() -> HWND {
+  unsafe { FindWindowW(w!("Shell_TrayWnd"), PCWSTR::null()) }
+}
+
+/// Taskbars of the other monitors, one per monitor
+pub fn secondary_taskbars() -> Vec<HWND> {
+  let mut taskbars = Vec::new();
+  let mut hwnd = HWND::default();
+
+  loop {
+    hwnd = unsafe {
+      FindWindowExW(
+        HWND::default(),
+        hwnd,
+        w!("Shell_SecondaryTrayWnd"),
+        PCWSTR::null(),
+      )
+    };
+    if hwnd.0 == 0 {
+      return taskbars;
+    }
+    taskbars.push(hwnd);
+  }
+}
+
+fn appbar_data(hwnd: HWND, state: u32) -> APPBARDATA {
+  APPBARDATA {
+    cbSize: std::mem::size_of::<APPBARDATA>() as u32,
+    hWnd: hwnd,
+    uCallbackMessage: 0,
+    uEdge: 0,
+    rc: RECT::default(),
+    lParam: LPARAM(state as isize),
+  }
+}
+
+pub fn hide_taskbar(hide: bool) {
+  let hwnd = taskbar();
+  if hwnd.0 == 0 {
+    return;
+  }
+
+  let state = if hide {
+    if RESTORED.load(Ordering::SeqCst) {
+      return;
+    }
+
+    let mut record = RECORD.lock().unwrap();
+    if record.is_none() {
+      // A record left on disk means the last run crashed with the taskbar hidden
+      *record = read_record().or_else(|| {
+        let state = unsafe { SHAppBarMessage(ABM_GETSTATE, &mut appbar_data(hwnd, 0)) };
+        let record = TaskbarRecord {
+          state: state as u32,
+        };
+        write_record(record);
+        Some(record)
+      });
+    }
+
+    ABS_AUTOHIDE
+  } else {
+    RECORD
+      .lock()
+      .unwrap()
+      .or_else(read_record)
+      .map(|record| record.state)
+      .unwrap_or(ABS_ALWAYSONTOP)
+  };
+
+  // The state is shared by every taskbar, each window is hidden on its own
+  unsafe { SHAppBarMessage(ABM_SETSTATE, &mut appbar_data(hwnd, state)) };
+  for hwnd in [hwnd].into_iter().chain(secondary_taskbars()) {
+    unsafe { ShowWindow(hwnd, if hide { SW_HIDE } else { SW_SHOWNA }) };
+  }
+}
+
+/// Puts the taskbar back as it was before simpletb started and stops hiding it
+pub fn restore_taskbar() {
+  RESTORED.store(true, Ordering::SeqCst);
+  hide_taskbar(false);
+
+  RECORD.lock().unwrap_or_else(|err| err.into_inner()).take();
+  fs::remove_file(record_path()).unwrap_or_else(|_| ());
+}
+
+/// Restores the taskbar on panic, console close and logoff
+///
+/// Only panics on the calling thread, which should be the one running the
+/// event loop, count. Other threads dying leaves the shell running, and the
+/// taskbar hidden with it. GUI processes only hear about logoff through their
+/// windows, see [`watch_taskbars`].
+pub fn install_taskbar_guard() {
+  GUARD.call_once(|| {
+    let main = std::thread::current().id();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+      if std::thread::current().id() != main {
+        previous(info);
+        return;
+      }
+
+      // The panicking thread may hold the record lock, the file is enough
+      if RECORD.try_lock().is_ok() {
+        restore_taskbar();
+      } else {
+        RESTORED.store(true, Ordering::SeqCst);
+      }
+      previous(info);
+    }));
+
+    unsafe { SetConsoleCtrlHandler(Some(console_handler), true) }
+      .unwrap_or_else(|err| eprintln!("Failed to set console handler: {}", err));
+  });
+}
+
+unsafe extern "system" fn console_handler(_ctrl_type: u32) -> BOOL {
+  // Ctrl+C, close, logoff and shutdown all end the process
+  restore_taskbar();
+  false.into()
+}
+
+/// Restores the taskbars when the session of `hwnd` ends, and hides the ones
+/// Explorer creates when monitors are added
+///
+/// Must be called from the thread that created the window.
+pub fn watch_taskbars(hwnd: HWND) {
+  subclass_window(
+    hwnd,
+    Box::new(|_, msg, wparam, _| {
+      match msg {
+        WM_ENDSESSION if wparam.0 != 0 => restore_taskbar(),
+        WM_DISPLAYCHANGE => {
+          thread::spawn(|| hide_taskbar(true));
+        }
+        _ => {}
+      }
+
+      None
+    }),
+  )
+  .unwrap_or_else(|err| eprintln!("Failed to watch taskbars: {}", err));
+}
+
+/// Waits for the process `pid` to exit, then restores the taskbar if it left
+/// it hidden
+///
+/// Runs in a separate watchdog process, so even a killed simpletb gets its
+/// taskbar back.
+pub fn restore_taskbar_after(pid: u32) {
+  if let Ok(process) = unsafe { OpenProcess(PROCESS_SYNCHRONIZE, false, pid) } {
+    unsafe {
+      WaitForSingleObject(process, INFINITE);
+      let _ = CloseHandle(process);
+    }
+  }
+
+  if read_record().is_some() {
+    restore_taskbar();
+  }
+}
@@ -1,13 +1,19 @@
+pub mod appbar;
 pub mod constants;
 pub mod geometry;
 pub mod handler;
 pub mod paths;
 pub mod settings;
+pub mod subclass;
+pub mod taskbar;
 pub mod windows_api;
 
+pub use appbar::*;
 pub use constants::*;
 pub use geometry::*;
 pub use handler::*;
 pub use paths::*;
 pub use settings::*;
+pub use subclass::*;
+pub use taskbar::*;
 pub use windows_api::*;
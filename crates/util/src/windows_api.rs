@@ -1,23 +1,15 @@
 use std::path::Path;
 
-use windows::core::PCWSTR;
 use windows::core::PWSTR;
 use windows::Win32::Foundation::CloseHandle;
 use windows::Win32::Foundation::HANDLE;
 use windows::Win32::Foundation::HWND;
-use windows::Win32::Foundation::LPARAM;
 use windows::Win32::Foundation::MAX_PATH;
 use windows::Win32::System::Threading::OpenProcess;
 use windows::Win32::System::Threading::QueryFullProcessImageNameW;
 use windows::Win32::System::Threading::PROCESS_ACCESS_RIGHTS;
 use windows::Win32::System::Threading::PROCESS_NAME_WIN32;
 use windows::Win32::System::Threading::PROCESS_QUERY_LIMITED_INFORMATION;
-use windows::Win32::UI::Shell::SHAppBarMessage;
-use windows::Win32::UI::Shell::ABM_SETSTATE;
-use windows::Win32::UI::Shell::ABS_ALWAYSONTOP;
-use windows::Win32::UI::Shell::ABS_AUTOHIDE;
-use windows::Win32::UI::Shell::APPBARDATA;
-use windows::Win32::UI::WindowsAndMessaging::FindWindowW;
 use windows::Win32::UI::WindowsAndMessaging::GetClassNameW;
 use windows::Win32::UI::WindowsAndMessaging::GetCursorInfo;
 use windows::Win32::UI::WindowsAndMessaging::GetParent;
@@ -25,15 +17,16 @@ use windows::Win32::UI::WindowsAndMessaging::GetWindow;
 use windows::Win32::UI::WindowsAndMessaging::GetWindowLongW;
 use windows::Win32::UI::WindowsAndMessaging::GetWindowTextW;
 use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
 use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
 use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
 use windows::Win32::UI::WindowsAndMessaging::CURSORINFO;
 use windows::Win32::UI::WindowsAndMessaging::CURSOR_SHOWING;
 use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
 use windows::Win32::UI::WindowsAndMessaging::GWL_STYLE;
 use windows::Win32::UI::WindowsAndMessaging::GW_OWNER;
-use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
-use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
 use windows::Win32::UI::WindowsAndMessaging::WINDOW_EX_STYLE;
 use windows::Win32::UI::WindowsAndMessaging::WINDOW_STYLE;
 use windows::Win32::UI::WindowsAndMessaging::WS_EX_APPWINDOW;
@@ -62,32 +55,6 @@ pub fn get_class(hwnd: HWND) -> Result<String> {
   String::from_utf16(&text[..length]).map_err(|err| AppError::Utf16(err))
 }
 
-pub fn hide_taskbar(hide: bool) {
-  let lparam = if hide {
-    LPARAM(ABS_AUTOHIDE as isize)
-  } else {
-    LPARAM(ABS_ALWAYSONTOP as isize)
-  };
-  let cmdshow = if hide { SW_HIDE } else { SW_SHOWNORMAL };
-
-  let name = format!("Shell_TrayWnd\0")
-    .encode_utf16()
-    .collect::<Vec<_>>();
-  let mut app_bar = APPBARDATA {
-    cbSize: std::mem::size_of::<APPBARDATA>() as u32,
-    hWnd: unsafe { FindWindowW(PCWSTR(name.as_ptr()), PCWSTR::null()) },
-    ..unsafe { std::mem::zeroed() }
-  };
-
-  if app_bar.hWnd.0 != 0 {
-    app_bar.lParam = lparam;
-    unsafe {
-      SHAppBarMessage(ABM_SETSTATE, &mut app_bar);
-      ShowWindow(app_bar.hWnd, cmdshow);
-    }
-  }
-}
-
 pub fn get_ex_styles(hwnd: HWND) -> WINDOW_EX_STYLE {
   WINDOW_EX_STYLE(unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 })
 }
@@ -154,6 +121,16 @@ pub fn is_window_visible(hwnd: HWND) -> bool {
   unsafe { IsWindowVisible(hwnd) }.into()
 }
 
+/// Brings `hwnd` to the front, restoring it when minimized
+pub fn activate_window(hwnd: HWND) {
+  unsafe {
+    if IsIconic(hwnd).as_bool() {
+      ShowWindow(hwnd, SW_RESTORE);
+    }
+    SetForegroundWindow(hwnd);
+  }
+}
+
 pub fn get_window_text(hwnd: HWND) -> String {
   let mut text = [0u16; 512];
   let len = unsafe { GetWindowTextW(hwnd, &mut text) };